@@ -1,11 +1,24 @@
 extern crate rand;
+extern crate serde;
+#[macro_use]
+extern crate serde_derive;
 
 use std::fmt;
 
-use rand::StdRng;
+use rand::{StdRng, SeedableRng};
+use serde::{Serialize, Serializer, Deserialize, Deserializer};
 use std::collections::HashMap;
 use std::collections::HashSet;
-
+use std::rc::Rc;
+
+//`src/main.rs` hands this to the library as a pointer, and in debug builds
+//keeps handing over the same one after the library is reloaded, so its
+//layout is pinned with `#[repr(C)]` and it mustn't change while a game is
+//running. Changing it means restarting rather than reloading. The same goes
+//for the types passed through it, `Point`, `Size`, `Rect`, `Color` and
+//`KeyCode`. Both sides are always built by the same compiler, so the plain
+//`fn`s are called the same way on either side.
+#[repr(C)]
 pub struct Platform {
     pub print_xy: fn(i32, i32, &str),
     pub clear: fn(Option<Rect>),
@@ -24,43 +37,286 @@ pub struct Platform {
     pub get_layer: fn() -> i32,
 }
 
+#[derive(Serialize, Deserialize)]
 pub struct State {
-    pub rng: StdRng,
+    pub rng: GameRng,
     pub title_screen: bool,
-    pub player: Role,
-    pub initial_player: Role,
-    pub cpu_roles: Vec<Role>,
-    pub initial_cpu_roles: Vec<Role>,
-    pub table_roles: [Role; 3],
+    pub table: Table,
+    //the cards as they were dealt, before anyone woke up
+    pub initial_table: Table,
     pub turn: Turn,
     pub player_knowledge: Knowledge,
     pub cpu_knowledge: Vec<Knowledge>,
     pub votes: Vec<(Participant, Participant)>,
+    //why each cpu voted the way they did, in the same order as `votes`
+    pub vote_reasons: Vec<(Participant, VoteReason)>,
+    #[serde(with = "claim_pairs")]
     pub claims: HashMap<Participant, Claim>,
+    //everything said during the discussion, in order. `claims` is where each
+    //speaker's story stands now.
+    pub transcript: Vec<Statement>,
+    #[serde(skip, default = "UIContext::new")]
     pub ui_context: UIContext,
     pub role_spec: RoleSpec,
     pub show_role_spec: bool,
+    pub night_script: Vec<NightStep>,
+    pub night_log: Vec<NightEvent>,
+    pub show_night_log: bool,
+    //`rng` is reset from this when the cards are dealt, so this, the
+    //`RoleSpec` and the player's choices are enough to replay a game.
+    pub seed: usize,
+    //the seed and `RoleSpec` of the most recent deal
+    pub last_deal: Option<(usize, RoleSpec)>,
+    //what has been typed so far while entering a seed
+    pub seed_entry: Option<String>,
+    //whether there was a saved game to continue when the game started
+    pub can_continue: bool,
+    //why the game couldn't be saved or continued, for the player to see
+    #[serde(skip)]
+    pub save_error: Option<String>,
+    //what was picked for each cpu seat on the `Ready` screen, `None` meaning
+    //a random one each deal
+    pub profile_picks: Vec<Option<CpuProfile>>,
+    //how each cpu player is playing this game
+    pub cpu_profiles: Vec<CpuProfile>,
+    //Cpu seats that play by something other than their profile, by index.
+    //These are code rather than data, so they aren't saved, and don't last
+    //through the library being reloaded.
+    #[serde(skip)]
+    pub cpu_strategies: HashMap<usize, Rc<dyn CpuStrategy>>,
 }
 
 impl fmt::Debug for State {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         fmt.debug_struct("State")
             .field("title_screen", &self.title_screen)
-            .field("player", &self.player)
-            .field("cpu_roles", &self.cpu_roles)
-            .field("table_roles", &self.table_roles)
+            .field("seed", &self.seed)
+            .field("table", &self.table)
             .field("turn", &self.turn)
             .field("player_knowledge", &self.player_knowledge)
             .field("cpu_knowledge", &self.cpu_knowledge)
             .field("votes", &self.votes)
+            .field("night_script", &self.night_script)
+            .field("night_log", &self.night_log)
             .field("ui_context", &self.ui_context)
             .finish()
     }
 }
 
-#[derive(Clone,Copy, Debug, PartialEq, Eq,PartialOrd, Ord, Hash)]
+//Json only allows strings as map keys, so the claims are saved as a list.
+mod claim_pairs {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(claims: &HashMap<Participant, Claim>,
+                                    serializer: S)
+                                    -> Result<S::Ok, S::Error> {
+        let mut pairs: Vec<(Participant, Claim)> = claims.iter()
+            .map(|(&p, &c)| (p, c))
+            .collect();
+        pairs.sort();
+
+        pairs.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D)
+                                                  -> Result<HashMap<Participant, Claim>, D::Error> {
+        let pairs: Vec<(Participant, Claim)> = Deserialize::deserialize(deserializer)?;
+
+        Ok(pairs.into_iter().collect())
+    }
+}
+
+//A `StdRng` that counts how many numbers it has handed out. `StdRng` can't
+//be saved directly, but reseeding and skipping `draws` numbers gets back to
+//exactly the same place.
+pub struct GameRng {
+    rng: StdRng,
+    seed: usize,
+    draws: u64,
+}
+
+impl GameRng {
+    pub fn new(seed: usize) -> Self {
+        let seed_slice: &[_] = &[seed];
+
+        GameRng {
+            rng: SeedableRng::from_seed(seed_slice),
+            seed,
+            draws: 0,
+        }
+    }
+
+    pub fn resume(seed: usize, draws: u64) -> Self {
+        let mut result = GameRng::new(seed);
+
+        for _ in 0..draws {
+            result.next_u64();
+        }
+
+        result
+    }
+
+    pub fn seed(&self) -> usize {
+        self.seed
+    }
+
+    pub fn draws(&self) -> u64 {
+        self.draws
+    }
+}
+
+//every `StdRng` method goes through one of these two, and they each use up
+//exactly one number.
+impl Rng for GameRng {
+    fn next_u32(&mut self) -> u32 {
+        self.draws += 1;
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.draws += 1;
+        self.rng.next_u64()
+    }
+}
+
+impl Serialize for GameRng {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (self.seed, self.draws).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for GameRng {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (seed, draws) = Deserialize::deserialize(deserializer)?;
+
+        Ok(GameRng::resume(seed, draws))
+    }
+}
+
+//The cards in front of each participant and the three in the center.
+//Cards only ever move around the table, so the night actions go through
+//`swap` and `swap_with_center` rather than writing cards in directly.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Table {
+    player: Card,
+    cpus: Vec<Card>,
+    center: [Card; 3],
+}
+
+impl Table {
+    pub fn new(player: Card, cpus: Vec<Card>, center: [Card; 3]) -> Self {
+        Table {
+            player,
+            cpus,
+            center,
+        }
+    }
+
+    pub fn view(&self, participant: Participant) -> Option<Card> {
+        match participant {
+            Participant::Player => Some(self.player),
+            Participant::Cpu(i) => self.cpus.get(i).cloned(),
+        }
+    }
+
+    pub fn view_center(&self, center_card: CenterCard) -> Card {
+        self.center[center_index(center_card)]
+    }
+
+    pub fn player(&self) -> Card {
+        self.player
+    }
+
+    pub fn cpu_cards(&self) -> &[Card] {
+        &self.cpus
+    }
+
+    pub fn center(&self) -> [Card; 3] {
+        self.center
+    }
+
+    //returns false and leaves the table alone if either participant isn't seated
+    pub fn swap(&mut self, p1: Participant, p2: Participant) -> bool {
+        if let (Some(card1), Some(card2)) = (self.view(p1), self.view(p2)) {
+            self.set(p1, card2);
+            self.set(p2, card1);
+
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn swap_with_center(&mut self, participant: Participant, center_card: CenterCard) -> bool {
+        if let Some(card) = self.view(participant) {
+            let index = center_index(center_card);
+
+            let center = self.center[index];
+            self.center[index] = card;
+            self.set(participant, center);
+
+            true
+        } else {
+            false
+        }
+    }
+
+    //The Doppelganger card takes on the role it copies. This is the only way
+    //a card changes, and it is still the Doppelganger card afterwards.
+    pub fn copy_role(&mut self, doppelganger: Participant, target: Participant, role: Role) -> bool {
+        match self.view(doppelganger) {
+            Some(Card::Plain(Doppelganger)) if role != Doppelganger => {
+                self.set(doppelganger, Card::Doppel(target, role));
+
+                true
+            }
+            _ => false,
+        }
+    }
+
+    //What is printed on every card on the table, sorted.
+    pub fn cards(&self) -> Vec<Role> {
+        let mut result: Vec<Role> = Some(self.player)
+            .into_iter()
+            .chain(self.cpus.iter().cloned())
+            .chain(self.center.iter().cloned())
+            .map(|c| c.face())
+            .collect();
+
+        result.sort();
+
+        result
+    }
+
+    pub fn has_same_cards(&self, other: &Table) -> bool {
+        self.cards() == other.cards()
+    }
+
+    fn set(&mut self, participant: Participant, card: Card) {
+        match participant {
+            Participant::Player => self.player = card,
+            Participant::Cpu(i) => {
+                if let Some(c) = self.cpus.get_mut(i) {
+                    *c = card;
+                }
+            }
+        }
+    }
+}
+
+fn center_index(center_card: CenterCard) -> usize {
+    match center_card {
+        CenterCard::First => 0,
+        CenterCard::Second => 1,
+        CenterCard::Third => 2,
+    }
+}
+
+//The roles printed on the cards. Everything else about a role lives in its
+//`RoleDef` in `ROLE_DEFS`, so adding a role means adding a variant here and an
+//entry there.
+#[derive(Clone,Copy, Debug, PartialEq, Eq,PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum Role {
-    //TODO before adding expansions, it should be made much easier to add a role.
     Werewolf,
     Minion,
     Robber,
@@ -72,48 +328,213 @@ pub enum Role {
     Villager,
     Tanner,
     Hunter,
-    DoppelWerewolf(Participant),
-    DoppelMinion(Participant),
-    DoppelRobber(Participant),
-    DoppelMason(Participant),
-    DoppelSeer(Participant),
-    DoppelTroublemaker(Participant),
-    DoppelDrunk(Participant),
-    DoppelInsomniac(Participant),
-    DoppelVillager(Participant),
-    DoppelTanner(Participant),
-    DoppelHunter(Participant),
+    Doppelganger,
 }
 use Role::*;
 
-//2 werewolves, 3 villagers, 2 masons and 1 everytthing else
-const ROLE_CARDS_AVAILABLE: u8 = 2 + 3 + 2 + 9;
-
-pub fn get_doppel_role(role: Role, participant: Participant) -> Role {
-    match role {
-        Werewolf => DoppelWerewolf(participant),
-        Minion => DoppelMinion(participant),
-        Robber => DoppelRobber(participant),
-        Mason => DoppelMason(participant),
-        Seer => DoppelSeer(participant),
-        Troublemaker => DoppelTroublemaker(participant),
-        Drunk => DoppelDrunk(participant),
-        Insomniac => DoppelInsomniac(participant),
-        Villager => DoppelVillager(participant),
-        Tanner => DoppelTanner(participant),
-        Hunter => DoppelHunter(participant),
-        DoppelWerewolf(_) => DoppelWerewolf(participant),
-        DoppelMinion(_) => DoppelMinion(participant),
-        DoppelRobber(_) => DoppelRobber(participant),
-        DoppelMason(_) => DoppelMason(participant),
-        DoppelSeer(_) => DoppelSeer(participant),
-        DoppelTroublemaker(_) => DoppelTroublemaker(participant),
-        DoppelDrunk(_) => DoppelDrunk(participant),
-        DoppelInsomniac(_) => DoppelInsomniac(participant),
-        DoppelVillager(_) => DoppelVillager(participant),
-        DoppelTanner(_) => DoppelTanner(participant),
-        DoppelHunter(_) => DoppelHunter(participant),
-    }
+#[derive(Clone,Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Team {
+    Village,
+    Werewolf,
+    Tanner,
+}
+
+//what a role does when it wakes up
+#[derive(Clone,Copy, Debug, PartialEq, Eq, Hash)]
+pub enum NightAction {
+    //doesn't wake up at all
+    Sleep,
+    CopyAnotherCard,
+    SeeOtherWerewolves,
+    SeeWerewolves,
+    SeeOtherMasons,
+    ViewPlayerOrTwoCenter,
+    SwapWithPlayerAndView,
+    SwapTwoOthers,
+    SwapWithCenter,
+    ViewOwnCard,
+}
+
+#[derive(Debug)]
+pub struct RoleDef {
+    pub role: Role,
+    pub name: &'static str,
+    //"a " or "an ", for putting the name in a sentence
+    pub article: &'static str,
+    pub team: Team,
+    //the number the role is called at in the official night script,
+    //if it wakes up at all
+    pub wake_order: Option<u8>,
+    pub night_action: NightAction,
+    //how many of the card come in the box
+    pub card_limit: u8,
+    //how many cards are added or removed at once. A lone Mason isn't much use.
+    pub group_size: u8,
+    pub doppel_wake: DoppelWake,
+}
+
+//when a Doppelganger that copied the role does the role's night action
+#[derive(Clone,Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DoppelWake {
+    //the role doesn't do anything at night
+    Never,
+    //along with everyone else with the role, the way the Werewolves all wake up together
+    WithOriginal,
+    //straight after copying it
+    Immediately,
+    //in a step of its own, right after the original
+    AfterOriginal,
+}
+
+pub const ROLE_COUNT: usize = 12;
+
+//Indexed by `Role as usize`, so this has to stay in the same order as `Role`.
+pub const ROLE_DEFS: [RoleDef; ROLE_COUNT] = [
+    RoleDef {
+        role: Werewolf,
+        name: "Werewolf",
+        article: "a ",
+        team: Team::Werewolf,
+        wake_order: Some(2),
+        night_action: NightAction::SeeOtherWerewolves,
+        card_limit: 2,
+        group_size: 1,
+        doppel_wake: DoppelWake::WithOriginal,
+    },
+    RoleDef {
+        role: Minion,
+        name: "Minion",
+        article: "a ",
+        team: Team::Werewolf,
+        wake_order: Some(3),
+        night_action: NightAction::SeeWerewolves,
+        card_limit: 1,
+        group_size: 1,
+        doppel_wake: DoppelWake::AfterOriginal,
+    },
+    RoleDef {
+        role: Robber,
+        name: "Robber",
+        article: "a ",
+        team: Team::Village,
+        wake_order: Some(6),
+        night_action: NightAction::SwapWithPlayerAndView,
+        card_limit: 1,
+        group_size: 1,
+        doppel_wake: DoppelWake::Immediately,
+    },
+    RoleDef {
+        role: Mason,
+        name: "Mason",
+        article: "a ",
+        team: Team::Village,
+        wake_order: Some(4),
+        night_action: NightAction::SeeOtherMasons,
+        card_limit: 2,
+        group_size: 2,
+        doppel_wake: DoppelWake::WithOriginal,
+    },
+    RoleDef {
+        role: Seer,
+        name: "Seer",
+        article: "a ",
+        team: Team::Village,
+        wake_order: Some(5),
+        night_action: NightAction::ViewPlayerOrTwoCenter,
+        card_limit: 1,
+        group_size: 1,
+        doppel_wake: DoppelWake::Immediately,
+    },
+    RoleDef {
+        role: Troublemaker,
+        name: "Troublemaker",
+        article: "a ",
+        team: Team::Village,
+        wake_order: Some(7),
+        night_action: NightAction::SwapTwoOthers,
+        card_limit: 1,
+        group_size: 1,
+        doppel_wake: DoppelWake::Immediately,
+    },
+    RoleDef {
+        role: Drunk,
+        name: "Drunk",
+        article: "a ",
+        team: Team::Village,
+        wake_order: Some(8),
+        night_action: NightAction::SwapWithCenter,
+        card_limit: 1,
+        group_size: 1,
+        doppel_wake: DoppelWake::Immediately,
+    },
+    RoleDef {
+        role: Insomniac,
+        name: "Insomniac",
+        article: "an ",
+        team: Team::Village,
+        wake_order: Some(9),
+        night_action: NightAction::ViewOwnCard,
+        card_limit: 1,
+        group_size: 1,
+        doppel_wake: DoppelWake::AfterOriginal,
+    },
+    RoleDef {
+        role: Villager,
+        name: "Villager",
+        article: "a ",
+        team: Team::Village,
+        wake_order: None,
+        night_action: NightAction::Sleep,
+        card_limit: 3,
+        group_size: 1,
+        doppel_wake: DoppelWake::Never,
+    },
+    RoleDef {
+        role: Tanner,
+        name: "Tanner",
+        article: "a ",
+        team: Team::Tanner,
+        wake_order: None,
+        night_action: NightAction::Sleep,
+        card_limit: 1,
+        group_size: 1,
+        doppel_wake: DoppelWake::Never,
+    },
+    RoleDef {
+        role: Hunter,
+        name: "Hunter",
+        article: "a ",
+        team: Team::Village,
+        wake_order: None,
+        night_action: NightAction::Sleep,
+        card_limit: 1,
+        group_size: 1,
+        doppel_wake: DoppelWake::Never,
+    },
+    RoleDef {
+        role: Doppelganger,
+        name: "Doppelganger",
+        article: "a ",
+        //only until they copy someone
+        team: Team::Village,
+        wake_order: Some(1),
+        night_action: NightAction::CopyAnotherCard,
+        card_limit: 1,
+        group_size: 1,
+        doppel_wake: DoppelWake::Never,
+    },
+];
+
+pub fn role_def(role: Role) -> &'static RoleDef {
+    let def = &ROLE_DEFS[role as usize];
+
+    debug_assert_eq!(def.role, role);
+
+    def
+}
+
+pub fn all_roles() -> Vec<Role> {
+    ROLE_DEFS.iter().map(|def| def.role).collect()
 }
 
 impl fmt::Display for Role {
@@ -126,33 +547,7 @@ impl fmt::Display for Role {
 //o for "only the name"?
 impl fmt::Octal for Role {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f,
-               "{}",
-               match *self {
-                   Werewolf => "Werewolf",
-                   Minion => "Minion",
-                   Mason => "Mason",
-                   Robber => "Robber",
-                   Seer => "Seer",
-                   Troublemaker => "Troublemaker",
-                   Drunk => "Drunk",
-                   Insomniac => "Insomniac",
-                   Villager => "Villager",
-                   Tanner => "Tanner",
-                   Hunter => "Hunter",
-                   //We'll assume don't know what the doppelganger copied in the general case
-                   DoppelWerewolf(_) |
-                   DoppelMinion(_) |
-                   DoppelMason(_) |
-                   DoppelRobber(_) |
-                   DoppelSeer(_) |
-                   DoppelTroublemaker(_) |
-                   DoppelDrunk(_) |
-                   DoppelInsomniac(_) |
-                   DoppelVillager(_) |
-                   DoppelTanner(_) |
-                   DoppelHunter(_) => "Doppelganger",
-               })
+        write!(f, "{}", role_def(*self).name)
     }
 }
 
@@ -160,119 +555,79 @@ impl fmt::Octal for Role {
 //b fo "before the name"
 impl fmt::Binary for Role {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f,
-               "{}",
-               match *self {
-                   Insomniac => "an ",
-                   Werewolf |
-                   Minion |
-                   Mason |
-                   Robber |
-                   Seer |
-                   Troublemaker |
-                   Drunk |
-                   Villager |
-                   Tanner |
-                   Hunter |
-                   DoppelWerewolf(_) |
-                   DoppelMinion(_) |
-                   DoppelMason(_) |
-                   DoppelRobber(_) |
-                   DoppelSeer(_) |
-                   DoppelTroublemaker(_) |
-                   DoppelDrunk(_) |
-                   DoppelInsomniac(_) |
-                   DoppelVillager(_) |
-                   DoppelTanner(_) |
-                   DoppelHunter(_) => "a ",
-               })
+        write!(f, "{}", role_def(*self).article)
     }
 }
 
-pub fn full_role_string(role: Role) -> String {
-    let role_name = match role {
-        DoppelWerewolf(_) => "Doppel-Werewolf".to_string(),
-        DoppelMinion(_) => "Doppel-Minion".to_string(),
-        DoppelMason(_) => "Doppel-Mason".to_string(),
-        DoppelRobber(_) => "Doppel-Robber".to_string(),
-        DoppelSeer(_) => "Doppel-Seer".to_string(),
-        DoppelTroublemaker(_) => "Doppel-Troublemaker".to_string(),
-        DoppelDrunk(_) => "Doppel-Drunk".to_string(),
-        DoppelInsomniac(_) => "Doppel-Insomniac".to_string(),
-        DoppelVillager(_) => "Doppel-Villager".to_string(),
-        DoppelTanner(_) => "Doppel-Tanner".to_string(),
-        DoppelHunter(_) => "Doppel-Hunter".to_string(),
+//A card as it currently is during a game. Once the Doppelganger wakes up it
+//becomes a copy of someone else's role, and follows that role's rules from then on.
+#[derive(Clone,Copy, Debug, PartialEq, Eq,PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Card {
+    Plain(Role),
+    //who the Doppelganger copied, and the role they had
+    Doppel(Participant, Role),
+}
 
-        _ => format!("{:o}", role),
-    };
+impl Card {
+    //the role whose rules the card follows
+    pub fn role(&self) -> Role {
+        match *self {
+            Card::Plain(role) |
+            Card::Doppel(_, role) => role,
+        }
+    }
+
+    //what is printed on the card, which is all anyone looking at it sees
+    pub fn face(&self) -> Role {
+        match *self {
+            Card::Plain(role) => role,
+            Card::Doppel(_, _) => Doppelganger,
+        }
+    }
+
+    pub fn is_doppel(&self) -> bool {
+        self.face() == Doppelganger
+    }
 
-    format!("{:b}{}", role, role_name)
+    pub fn def(&self) -> &'static RoleDef {
+        role_def(self.role())
+    }
 }
 
-#[derive(Clone,Copy, PartialEq, Debug)]
-pub struct RoleSpec {
-    pub villager1: bool,
-    pub villager2: bool,
-    pub villager3: bool,
-    pub werewolf1: bool,
-    pub werewolf2: bool,
-    pub seer: bool,
-    pub robber: bool,
-    pub troublemaker: bool,
-    pub tanner: bool,
-    pub drunk: bool,
-    pub hunter: bool,
-    pub masons: bool,
-    pub insomniac: bool,
-    pub minion: bool,
-    pub doppelganger: bool,
-}
-
-macro_rules! add_role{
-    ($result:expr, $flag:expr, $role:expr) => {
-        if $flag {
-            $result.push($role);
-        }
+impl fmt::Display for Card {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.face())
     }
 }
 
-macro_rules! bool_to_int {
-    ($($int: expr), +) =>  {
-        0 $( + if $int {
-            1
-        } else {
-            0
-        })+
+pub fn full_role_string(card: Card) -> String {
+    match card {
+        Card::Plain(role) => format!("{}", role),
+        Card::Doppel(_, role) => format!("a Doppel-{:o}", role),
     }
 }
 
+#[derive(Clone,Copy, PartialEq, Debug, Serialize, Deserialize)]
+pub struct RoleSpec {
+    //indexed by `Role as usize`, like `ROLE_DEFS`
+    counts: [u8; ROLE_COUNT],
+}
 
 //2 cpu players minimum, 1 for the player and 3 for the center
 const MINIMUM_CARDS : usize = 2 + 1 + 3;
 
+//the most players the board has room for
+pub const MAXIMUM_CPU_PLAYERS : u32 = 9;
+
 impl RoleSpec {
     pub fn get_role_vector(&self) -> Vec<Role> {
-
         let mut result = Vec::new();
 
-        add_role!(result, self.villager1, Villager);
-        add_role!(result, self.villager2, Villager);
-        add_role!(result, self.villager3, Villager);
-        add_role!(result, self.werewolf1, Werewolf);
-        add_role!(result, self.werewolf2, Werewolf);
-        add_role!(result, self.seer, Seer);
-        add_role!(result, self.robber, Robber);
-        add_role!(result, self.troublemaker, Troublemaker);
-        add_role!(result, self.tanner, Tanner);
-        add_role!(result, self.drunk, Drunk);
-        add_role!(result, self.hunter, Hunter);
-        //only either 0 or 2 masons
-        add_role!(result, self.masons, Mason);
-        add_role!(result, self.masons, Mason);
-        add_role!(result, self.insomniac, Insomniac);
-        add_role!(result, self.minion, Minion);
-        //DoppelVillager(Player) represents the Doppelganger card
-        add_role!(result, self.doppelganger, DoppelVillager(Player));
+        for def in ROLE_DEFS.iter() {
+            for _ in 0..self.counts[def.role as usize] {
+                result.push(def.role);
+            }
+        }
 
         while result.len() < MINIMUM_CARDS {
             result.insert(0, Villager);
@@ -294,346 +649,281 @@ impl RoleSpec {
     }
 
     pub fn get_count(&self, role: &Role) -> u32 {
-        match *role {
-            Werewolf => bool_to_int!(self.werewolf1, self.werewolf2),
-            Minion => bool_to_int!(self.minion),
-            Robber => bool_to_int!(self.robber),
-            //only either 0 or 2 masons
-            Mason => bool_to_int!(self.masons) * 2,
-            Seer => bool_to_int!(self.seer),
-            Troublemaker => bool_to_int!(self.troublemaker),
-            Drunk => bool_to_int!(self.drunk),
-            Insomniac => bool_to_int!(self.insomniac),
-            Villager => bool_to_int!(self.villager1, self.villager2, self.villager3),
-            Tanner => bool_to_int!(self.tanner),
-            Hunter => bool_to_int!(self.hunter),
-            DoppelWerewolf(_) |
-            DoppelMinion(_) |
-            DoppelRobber(_) |
-            DoppelMason(_) |
-            DoppelSeer(_) |
-            DoppelTroublemaker(_) |
-            DoppelDrunk(_) |
-            DoppelInsomniac(_) |
-            DoppelVillager(_) |
-            DoppelTanner(_) |
-            DoppelHunter(_) => bool_to_int!(self.doppelganger),
-        }
+        self.counts[*role as usize] as u32
     }
 
     pub fn add(&mut self, role: &Role) {
-        match *role {
-            Werewolf => {
-                if self.werewolf1 {
-                    self.werewolf2 = true;
-                } else {
-                    self.werewolf1 = true;
-                }
-            }
-            Minion => {
-                self.minion = true;
-            }
-            Robber => {
-                self.robber = true;
-            }
-            //only either 0 or 2 masons
-            Mason => {
-                self.masons = true;
-            }
-            Seer => {
-                self.seer = true;
-            }
-            Troublemaker => {
-                self.troublemaker = true;
-            }
-            Drunk => {
-                self.drunk = true;
-            }
-            Insomniac => {
-                self.insomniac = true;
-            }
-            Villager => {
-                if self.villager1 {
-                    if self.villager2 {
-                        self.villager3 = true;
-                    } else {
-                        self.villager2 = true;
-                    }
-                } else {
-                    self.villager1 = true;
-                }
-            }
-            Tanner => {
-                self.tanner = true;
-            }
-            Hunter => {
-                self.hunter = true;
-            }
-            DoppelWerewolf(_) |
-            DoppelMinion(_) |
-            DoppelRobber(_) |
-            DoppelMason(_) |
-            DoppelSeer(_) |
-            DoppelTroublemaker(_) |
-            DoppelDrunk(_) |
-            DoppelInsomniac(_) |
-            DoppelVillager(_) |
-            DoppelTanner(_) |
-            DoppelHunter(_) => {
-                self.doppelganger = true;
-            }
-        }
+        let def = role_def(*role);
+        let count = &mut self.counts[*role as usize];
+
+        *count = std::cmp::min(def.card_limit, *count + def.group_size);
     }
 
     pub fn can_add(&mut self, role: &Role) -> bool {
         let cpu_player_count = self.get_cpu_player_count(None);
+        let def = role_def(*role);
 
-        if cpu_player_count >= 9 {
-            return false;
-        }
-
-        match *role {
-            Werewolf => {
-                !self.werewolf2 || !self.werewolf1
-            }
-            Minion => {
-                !self.minion
-            }
-            Robber => {
-                !self.robber
-            }
-            Mason =>  !self.masons && cpu_player_count < 8,
-            Seer => {
-                !self.seer
-            }
-            Troublemaker => {
-                !self.troublemaker
-            }
-            Drunk => {
-                !self.drunk
-            }
-            Insomniac => {
-                !self.insomniac
-            }
-            Villager => {
-                !self.villager3 || !self.villager2 || !self.villager1
-            }
-            Tanner => {
-                !self.tanner
-            }
-            Hunter => {
-                !self.hunter
-            }
-            DoppelWerewolf(_) |
-            DoppelMinion(_) |
-            DoppelRobber(_) |
-            DoppelMason(_) |
-            DoppelSeer(_) |
-            DoppelTroublemaker(_) |
-            DoppelDrunk(_) |
-            DoppelInsomniac(_) |
-            DoppelVillager(_) |
-            DoppelTanner(_) |
-            DoppelHunter(_) => {
-                !self.doppelganger
-            }
-            }
+        cpu_player_count + def.group_size as u32 <= MAXIMUM_CPU_PLAYERS &&
+        self.counts[*role as usize] + def.group_size <= def.card_limit
     }
 
     pub fn remove(&mut self, role: &Role) {
         if self.can_remove(role) {
-        match *role {
-            Werewolf => {
-                if self.werewolf2 {
-                    self.werewolf2 = false;
-                } else {
-                    self.werewolf1 = false;
-                }
-            }
-            Minion => {
-                self.minion = false;
-            }
-            Robber => {
-                self.robber = false;
-            }
-            Mason =>  {self.masons = false;}
-            Seer => {
-                self.seer = false;
-            }
-            Troublemaker => {
-                self.troublemaker = false;
-            }
-            Drunk => {
-                self.drunk = false;
-            }
-            Insomniac => {
-                self.insomniac = false;
-            }
-            Villager => {
-                if self.villager3 {
-                    self.villager3 = false;
-                } else {
-                    if self.villager2 {
-                        self.villager2 = false;
-                    } else {
-                        self.villager1 = false;
-                    }
-                }
-            }
-            Tanner => {
-                self.tanner = false;
-            }
-            Hunter => {
-                self.hunter = false;
-            }
-            DoppelWerewolf(_) |
-            DoppelMinion(_) |
-            DoppelRobber(_) |
-            DoppelMason(_) |
-            DoppelSeer(_) |
-            DoppelTroublemaker(_) |
-            DoppelDrunk(_) |
-            DoppelInsomniac(_) |
-            DoppelVillager(_) |
-            DoppelTanner(_) |
-            DoppelHunter(_) => {
-                self.doppelganger = false;
-            }
+            self.counts[*role as usize] -= role_def(*role).group_size;
         }
-    };
     }
 
     pub fn can_remove(&mut self, role: &Role) -> bool {
         let cpu_player_count = self.get_cpu_player_count(None);
+        let def = role_def(*role);
+
+        cpu_player_count >= 2 + def.group_size as u32 &&
+        self.counts[*role as usize] >= def.group_size
+    }
+
+    //The spec with exactly `roles` in it, as long as it's one that `add` and
+    //`remove` could have got to.
+    pub fn from_roles(roles: &[Role]) -> Option<RoleSpec> {
+        let cpu_player_count = (roles.len() as u32).saturating_sub(3 + 1);
 
-        if cpu_player_count < 3 {
-            return false;
+        if roles.len() < MINIMUM_CARDS || cpu_player_count > MAXIMUM_CPU_PLAYERS {
+            return None;
         }
 
-        match *role {
-            Werewolf => {
-                 self.werewolf2 || self.werewolf1
-            }
-            Minion => {
-                self.minion
-            }
-            Robber => {
-                self.robber
-            }
-            Mason =>  self.masons && cpu_player_count >= 4,
-            Seer => {
-                self.seer
-            }
-            Troublemaker => {
-                self.troublemaker
-            }
-            Drunk => {
-                self.drunk
-            }
-            Insomniac => {
-                self.insomniac
-            }
-            Villager => {
-                self.villager3 || self.villager2 ||self.villager1
-            }
-            Tanner => {
-                self.tanner
-            }
-            Hunter => {
-                self.hunter
-            }
-            DoppelWerewolf(_) |
-            DoppelMinion(_) |
-            DoppelRobber(_) |
-            DoppelMason(_) |
-            DoppelSeer(_) |
-            DoppelTroublemaker(_) |
-            DoppelDrunk(_) |
-            DoppelInsomniac(_) |
-            DoppelVillager(_) |
-            DoppelTanner(_) |
-            DoppelHunter(_) => {
-                self.doppelganger
-            }
+        let mut counts = [0; ROLE_COUNT];
+
+        for &role in roles.iter() {
+            counts[role as usize] += 1;
+        }
+
+        let fits = ROLE_DEFS.iter().all(|def| {
+            let count = counts[def.role as usize];
+
+            count <= def.card_limit && count % def.group_size == 0
+        });
+
+        if fits { Some(RoleSpec { counts }) } else { None }
+    }
+}
+
+//How a cpu player goes about the game. Everything else about a profile lives
+//in its `ProfileDef` in `PROFILE_DEFS`, like `Role` and `ROLE_DEFS`.
+#[derive(Clone,Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CpuProfile {
+    Naive,
+    //how cpu players played before they had profiles
+    #[default]
+    Cautious,
+    Bluffer,
+    Truthful,
+    Hard,
 }
+use CpuProfile::*;
+
+#[derive(Debug)]
+pub struct ProfileDef {
+    pub profile: CpuProfile,
+    pub name: &'static str,
+    //whether they lie when they're on the Werewolf team, or the Tanner
+    pub lies: bool,
+    //whether their lies are about doing something at night, rather than just
+    //being a Villager
+    pub bold_lies: bool,
+    //whether they'll pretend to be the Seer and point at someone
+    pub accuses: bool,
+    //whether they vote by working out who is likely to be a Werewolf, see
+    //`deduction`, rather than only on what they know for sure
+    pub deduces: bool,
+    //whether they sometimes hide what they know on the village team, to
+    //tempt a Werewolf into claiming their role
+    pub bluffs: bool,
+    //whether they play out how the game might go before making a choice,
+    //which takes a while, so they're never picked at random
+    pub searches: bool,
+}
+
+pub const PROFILE_COUNT: usize = 5;
+
+//Indexed by `CpuProfile as usize`, so this has to stay in the same order as `CpuProfile`.
+pub const PROFILE_DEFS: [ProfileDef; PROFILE_COUNT] = [
+    //easy to catch out, and doesn't think very hard about the vote
+    ProfileDef {
+        profile: Naive,
+        name: "Naive",
+        lies: true,
+        bold_lies: false,
+        accuses: false,
+        deduces: false,
+        bluffs: false,
+        searches: false,
+    },
+    ProfileDef {
+        profile: Cautious,
+        name: "Cautious",
+        lies: true,
+        bold_lies: true,
+        accuses: false,
+        deduces: true,
+        bluffs: false,
+        searches: false,
+    },
+    //an aggressive liar, who tries to get someone else voted for, and
+    //bluffs even on the village team
+    ProfileDef {
+        profile: Bluffer,
+        name: "Bluffer",
+        lies: true,
+        bold_lies: true,
+        accuses: true,
+        deduces: true,
+        bluffs: true,
+        searches: false,
+    },
+    //never lies, even on the Werewolf team
+    ProfileDef {
+        profile: Truthful,
+        name: "Truthful",
+        lies: false,
+        bold_lies: false,
+        accuses: false,
+        deduces: true,
+        bluffs: false,
+        searches: false,
+    },
+    //lies like Cautious, but makes each choice by trying them all out,
+    //see `state_manipulation::search`
+    ProfileDef {
+        profile: Hard,
+        name: "Hard",
+        lies: true,
+        bold_lies: true,
+        accuses: false,
+        deduces: true,
+        bluffs: false,
+        searches: true,
+    },
+];
+
+pub fn profile_def(profile: CpuProfile) -> &'static ProfileDef {
+    let def = &PROFILE_DEFS[profile as usize];
+
+    debug_assert_eq!(def.profile, profile);
+
+    def
+}
+
+pub fn all_profiles() -> Vec<CpuProfile> {
+    PROFILE_DEFS.iter().map(|def| def.profile).collect()
+}
+
+impl fmt::Display for CpuProfile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", profile_def(*self).name)
+    }
+}
+
+impl Rand for CpuProfile {
+    fn rand<R: Rng>(rng: &mut R) -> Self {
+        let ordinary: Vec<&ProfileDef> = PROFILE_DEFS.iter().filter(|def| !def.searches).collect();
+
+        ordinary[rng.gen_range(0, ordinary.len())].profile
     }
 }
 
+//Everything a cpu player decides for themselves. The engine asks the seat's
+//strategy whenever there's a choice to make, and applies the rules to the
+//answer itself, so a strategy can look at anything in the `State` but should
+//only change `state.rng`. `state_manipulation::cpu::ProfileStrategy` is how
+//a seat plays by default.
+pub trait CpuStrategy {
+    //who the Doppelganger copies, `None` to copy nobody
+    fn copy_target(&self, state: &mut State, doppelganger: Participant) -> Option<Participant>;
+
+    fn seer_choice(&self, state: &mut State, seer: Participant) -> Option<SeerView>;
+
+    fn robber_target(&self, state: &mut State, robber: Participant) -> Option<Participant>;
+
+    fn troublemaker_targets(&self,
+                            state: &mut State,
+                            troublemaker: Participant)
+                            -> Option<(Participant, Participant)>;
+
+    fn drunk_target(&self, state: &mut State, drunk: Participant) -> CenterCard;
+
+    //what they say they are when the discussion starts
+    fn claim(&self, state: &mut State, participant: Participant) -> Option<Claim>;
+
+    //what they say next in the discussion, `None` once they're done talking
+    fn respond(&self, state: &mut State, participant: Participant) -> Option<StatementKind>;
+
+    //who they vote for, and why, which they own up to once the game is over
+    fn vote(&self, state: &mut State, participant: Participant) -> (Participant, VoteReason);
+}
+
+//what the Seer looks at
+#[derive(Clone,Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum SeerView {
+    Player(Participant),
+    Center(CenterPair),
+}
+
 impl Default for RoleSpec {
     fn default() -> RoleSpec {
-        RoleSpec {
-            werewolf1: true,
-            werewolf2: true,
-            villager1: true,
-            villager2: true,
-            villager3: false,
-            seer: true,
-            robber: true,
-            troublemaker: true,
-            tanner: false,
-            drunk: false,
-            hunter: false,
-            masons: false,
-            insomniac: false,
-            minion: false,
-            doppelganger: false,
-        }
+        let mut counts = [0; ROLE_COUNT];
+
+        counts[Werewolf as usize] = 2;
+        counts[Villager as usize] = 2;
+        counts[Seer as usize] = 1;
+        counts[Robber as usize] = 1;
+        counts[Troublemaker as usize] = 1;
+
+        RoleSpec { counts }
     }
 }
 
 impl Rand for RoleSpec {
     fn rand<R: Rng>(rng: &mut R) -> Self {
         //TODO Insomniac is pointless if there isn't at last one role that moves card around
-        let cpu_player_count = rng.gen_range(2, 10);
+        let cpu_player_count = rng.gen_range(2, MAXIMUM_CPU_PLAYERS + 1);
         // 3 in the center and 1 for the player
-        let mut roles_needed = 3 + 1 + cpu_player_count;
-        let mut deck_size = ROLE_CARDS_AVAILABLE;
+        let mut roles_needed = (3 + 1 + cpu_player_count) as usize;
 
-        let masons = roles_needed > ROLE_CARDS_AVAILABLE - 2 || rng.gen::<bool>();
-
-        if masons {
-            roles_needed -= 2;
-        }
-        deck_size -= 2;
+        let mut counts = [0; ROLE_COUNT];
 
         //always at least on werewolf
-        let werewolf1 = true;
+        counts[Werewolf as usize] = 1;
         roles_needed -= 1;
-        deck_size -= 1;
 
-        let difference = deck_size - roles_needed;
+        //every single card left in the box, which the rest of the roles are drawn from
+        let mut deck: Vec<Role> = Vec::new();
+        for def in ROLE_DEFS.iter().filter(|def| def.group_size == 1) {
+            for _ in counts[def.role as usize]..def.card_limit {
+                deck.push(def.role);
+            }
+        }
 
-        let deck_iter = std::iter::repeat(true)
-            .take(roles_needed as usize)
-            .chain(std::iter::repeat(false).take(difference as usize));
+        //groups go in all at once, so decide on those first
+        for def in ROLE_DEFS.iter().filter(|def| def.group_size > 1) {
+            let group_size = def.group_size as usize;
 
-        let mut deck: Vec<bool> = deck_iter.collect();
+            if group_size <= roles_needed &&
+               (roles_needed > deck.len() || rng.gen::<bool>()) {
+                counts[def.role as usize] = def.group_size;
+                roles_needed -= group_size;
+            }
+        }
 
         rng.shuffle(&mut deck);
 
-        RoleSpec {
-            werewolf1,
-            werewolf2: deck.pop().unwrap_or(false),
-            seer: deck.pop().unwrap_or(false),
-            robber: deck.pop().unwrap_or(false),
-            troublemaker: deck.pop().unwrap_or(false),
-            tanner: deck.pop().unwrap_or(false),
-            drunk: deck.pop().unwrap_or(false),
-            hunter: deck.pop().unwrap_or(false),
-            masons,
-            insomniac: deck.pop().unwrap_or(false),
-            minion: deck.pop().unwrap_or(false),
-            doppelganger: deck.pop().unwrap_or(false),
-            //not 100% sure but I think handling masons causes the deck to
-            //to run out sometimes. If so, it should run out on villagers.
-            villager1: deck.pop().unwrap_or(false),
-            villager2: deck.pop().unwrap_or(false),
-            villager3: deck.pop().unwrap_or(false),
+        for &role in deck.iter().take(roles_needed) {
+            counts[role as usize] += 1;
         }
+
+        RoleSpec { counts }
     }
 }
 
-#[derive(Clone,Copy, PartialEq, Debug)]
+#[derive(Clone,Copy, PartialEq, Debug, Serialize, Deserialize)]
 pub enum Turn {
     Ready,
     SeeRole(bool),
@@ -667,41 +957,116 @@ pub enum Turn {
 use Turn::*;
 
 impl Turn {
-    pub fn next(&self) -> Turn {
-        match *self {
-            //we only need the (*)Reveal states when the player is the (*)
-            Ready => SeeRole(false),
-            SeeRole(_) => DoppelSeerTurn,
-            DoppelSeerTurn => DoppelRobberTurn,
-            DoppelSeerRevealOne(_) => DoppelSeerTurn.next(),
-            DoppelSeerRevealTwo(_) => DoppelSeerTurn.next(),
-            DoppelRobberTurn => DoppelTroublemakerTurn,
-            DoppelRobberReveal => DoppelRobberTurn.next(),
-            DoppelTroublemakerSecondChoice(_) => DoppelTroublemakerTurn.next(),
-            DoppelTroublemakerTurn => DoppelDrunkTurn,
-            DoppelDrunkTurn => DoppelMinionTurn,
-            DoppelMinionTurn => Werewolves,
-            Werewolves => MinionTurn,
-            MinionTurn => MasonTurn,
-            MasonTurn => SeerTurn,
-            SeerTurn => RobberTurn,
-            SeerRevealOne(_) => SeerTurn.next(),
-            SeerRevealTwo(_) => SeerTurn.next(),
-            RobberTurn => TroublemakerTurn,
-            RobberReveal => RobberTurn.next(),
-            TroublemakerSecondChoice(_) => TroublemakerTurn.next(),
-            TroublemakerTurn => DrunkTurn,
-            DrunkTurn => InsomniacTurn,
-            InsomniacTurn => DoppelInsomniacTurn,
-            DoppelInsomniacTurn => BeginDiscussion,
-            BeginDiscussion => Discuss,
-            Discuss => Vote,
-            Vote => Resolution,
-            Resolution => Ready,
+    //which step of the night the turn is part of, if any
+    pub fn night_step(&self) -> Option<NightStep> {
+        let (role, doppel) = match *self {
+            DoppelSeerTurn |
+            DoppelSeerRevealOne(_) |
+            DoppelSeerRevealTwo(_) => (Seer, true),
+            DoppelRobberTurn | DoppelRobberReveal => (Robber, true),
+            DoppelTroublemakerTurn |
+            DoppelTroublemakerSecondChoice(_) => (Troublemaker, true),
+            DoppelDrunkTurn => (Drunk, true),
+            DoppelMinionTurn => (Minion, true),
+            Werewolves => (Werewolf, false),
+            MinionTurn => (Minion, false),
+            MasonTurn => (Mason, false),
+            SeerTurn | SeerRevealOne(_) | SeerRevealTwo(_) => (Seer, false),
+            RobberTurn | RobberReveal => (Robber, false),
+            TroublemakerTurn | TroublemakerSecondChoice(_) => (Troublemaker, false),
+            DrunkTurn => (Drunk, false),
+            InsomniacTurn => (Insomniac, false),
+            DoppelInsomniacTurn => (Insomniac, true),
+            Ready | SeeRole(_) | BeginDiscussion | Discuss | Vote | Resolution => return None,
+        };
+
+        Some(NightStep {
+            role,
+            doppel,
+        })
+    }
+
+    //The turn that a step of the night starts on. The Doppelganger's own step
+    //doesn't get one since the copying happens in `SeeRole`.
+    pub fn for_night_step(step: NightStep) -> Option<Turn> {
+        match (step.role, step.doppel) {
+            (Seer, true) => Some(DoppelSeerTurn),
+            (Robber, true) => Some(DoppelRobberTurn),
+            (Troublemaker, true) => Some(DoppelTroublemakerTurn),
+            (Drunk, true) => Some(DoppelDrunkTurn),
+            (Minion, true) => Some(DoppelMinionTurn),
+            (Insomniac, true) => Some(DoppelInsomniacTurn),
+            (Werewolf, false) => Some(Werewolves),
+            (Minion, false) => Some(MinionTurn),
+            (Mason, false) => Some(MasonTurn),
+            (Seer, false) => Some(SeerTurn),
+            (Robber, false) => Some(RobberTurn),
+            (Troublemaker, false) => Some(TroublemakerTurn),
+            (Drunk, false) => Some(DrunkTurn),
+            (Insomniac, false) => Some(InsomniacTurn),
+            _ => None,
         }
     }
 }
 
+//One role being called during the night.
+#[derive(Clone,Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct NightStep {
+    pub role: Role,
+    //whether it's the Doppelganger doing the copied role's action
+    pub doppel: bool,
+}
+
+impl fmt::Display for NightStep {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.doppel {
+            write!(f, "Doppel-{:o}", self.role)
+        } else {
+            write!(f, "{:o}", self.role)
+        }
+    }
+}
+
+//Every role in the deck gets called in the order of their official wake-up
+//numbers, even when the card ends up in the center, so the script doesn't give
+//away which cards are in play.
+pub fn night_script(roles: &[Role]) -> Vec<NightStep> {
+    let doppelganger = if roles.contains(&Doppelganger) {
+        role_def(Doppelganger).wake_order
+    } else {
+        None
+    };
+
+    let mut steps = Vec::new();
+
+    for def in ROLE_DEFS.iter().filter(|def| roles.contains(&def.role)) {
+        if let Some(order) = def.wake_order {
+            steps.push(((order, 0, 0),
+                        NightStep {
+                            role: def.role,
+                            doppel: false,
+                        }));
+
+            if let Some(doppel_order) = doppelganger {
+                let doppel_step = NightStep {
+                    role: def.role,
+                    doppel: true,
+                };
+
+                match def.doppel_wake {
+                    DoppelWake::Immediately => steps.push(((doppel_order, 1, order), doppel_step)),
+                    DoppelWake::AfterOriginal => steps.push(((order, 1, 0), doppel_step)),
+                    DoppelWake::Never | DoppelWake::WithOriginal => {}
+                }
+            }
+        }
+    }
+
+    steps.sort_by_key(|&(key, _)| key);
+
+    steps.into_iter().map(|(_, step)| step).collect()
+}
+
 pub trait AllValues {
     fn all_values() -> Vec<Self> where Self: std::marker::Sized;
 }
@@ -730,7 +1095,7 @@ macro_rules! all_values_rand_impl {
     )*)
 }
 
-#[derive(PartialEq, Eq,PartialOrd, Ord, Clone,Copy, Debug)]
+#[derive(PartialEq, Eq,PartialOrd, Ord, Clone,Copy, Debug, Serialize, Deserialize)]
 pub enum CenterPair {
     FirstSecond,
     FirstThird,
@@ -759,7 +1124,7 @@ impl fmt::Display for CenterPair {
     }
 }
 
-#[derive(PartialEq, Eq,PartialOrd, Ord, Clone,Copy, Debug)]
+#[derive(PartialEq, Eq,PartialOrd, Ord, Clone,Copy, Debug, Serialize, Deserialize)]
 pub enum CenterCard {
     First,
     Second,
@@ -788,7 +1153,7 @@ impl fmt::Display for CenterCard {
     }
 }
 
-#[derive(Clone,Copy, PartialEq, Eq, Debug, Hash, PartialOrd, Ord)]
+#[derive(Clone,Copy, PartialEq, Eq, Debug, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum Participant {
     Player,
     Cpu(usize),
@@ -807,11 +1172,11 @@ impl fmt::Display for Participant {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Knowledge {
     pub known_werewolves: HashSet<Participant>,
     pub known_villagers: HashSet<Participant>,
-    pub role: Role,
+    pub role: Card,
     pub true_claim: Claim,
     //TODO is this needed/used?
     //Is it filled maximumlly?
@@ -821,26 +1186,32 @@ pub struct Knowledge {
     //TODO should these account for the possibity of a /Doppel(Minion|Tanner)/ ?
     pub known_minion: Option<Participant>,
     pub known_tanner: Option<Participant>,
-    pub robber_swap: Option<(Participant, Participant, Role)>,
-    pub troublemaker_swap: Option<(Participant, Participant)>,
-    pub drunk_swap: Option<(Participant, CenterCard)>,
-    pub insomniac_peek: bool,
+    //who seems to be telling the truth, or lying, going by what they claimed
+    pub trusted: HashSet<Participant>,
+    pub liars: HashSet<Participant>,
+    //pairs who both claimed a role there's only one of, so one of them is lying
+    pub contested: Vec<(Participant, Participant)>,
+    //who someone they believe has pointed at as a Werewolf
+    pub suspects: HashSet<Participant>,
+    //what the people they believe said they saw on someone's card. Unlike the
+    //`known_` fields this is only hearsay, so it's worked out again whenever
+    //anyone says anything.
+    pub heard_werewolves: HashSet<Participant>,
+    pub heard_villagers: HashSet<Participant>,
 }
 
 impl Knowledge {
-    pub fn new(role: Role, participant: Participant) -> Self {
+    pub fn new(role: Card, participant: Participant) -> Self {
 
         let (known_minion, known_tanner) = match role {
-            Minion => (Some(participant), None),
-            Tanner => (None, Some(participant)),
+            Card::Plain(Minion) => (Some(participant), None),
+            Card::Plain(Tanner) => (None, Some(participant)),
             _ => (None, None),
         };
 
         let true_claim = match role {
-            DoppelVillager(p) => DoppelSimple(p, Villager),
-            DoppelTanner(p) => DoppelSimple(p, Tanner),
-            DoppelHunter(p) => DoppelSimple(p, Hunter),
-            _ => Simple(role),
+            Card::Plain(r) => Simple(r),
+            Card::Doppel(p, r) => DoppelSimple(p, r),
         };
 
         Knowledge {
@@ -851,15 +1222,127 @@ impl Knowledge {
             known_non_active: HashSet::new(),
             known_minion,
             known_tanner,
-            robber_swap: None,
-            troublemaker_swap: None,
-            drunk_swap: None,
-            insomniac_peek: false,
+            trusted: HashSet::new(),
+            liars: HashSet::new(),
+            contested: Vec::new(),
+            suspects: HashSet::new(),
+            heard_werewolves: HashSet::new(),
+            heard_villagers: HashSet::new(),
+        }
+    }
+
+    //Updates what the actor of the event knows. Knowledge is whatever
+    //`Knowledge::new` starts with plus each of these in order.
+    pub fn learn(&mut self, event: &NightEvent) {
+        match *event {
+            NightEvent::Copied { actor: _, target, role } => {
+                self.role = Card::Doppel(target, role);
+                self.true_claim = DoppelSimple(target, role);
+            }
+            NightEvent::SawAwake { actor: _, target, card } => {
+                if card.role() == Werewolf {
+                    self.known_werewolves.insert(target);
+                } else {
+                    self.known_villagers.insert(target);
+                }
+            }
+            NightEvent::AwakeAlone { actor: _, role } => {
+                //whoever else would have woken up must be in the center
+                self.known_non_active.insert(role);
+            }
+            NightEvent::ViewedPlayer { actor: _, target, card } => {
+                if card.role() == Werewolf {
+                    self.known_villagers.remove(&target);
+                    self.known_werewolves.insert(target);
+                } else if card == Card::Plain(Minion) {
+                    self.known_villagers.remove(&target);
+                    self.known_werewolves.remove(&target);
+                    self.known_minion = Some(target);
+                } else if card == Card::Plain(Tanner) {
+                    self.known_villagers.remove(&target);
+                    self.known_werewolves.remove(&target);
+                    self.known_tanner = Some(target);
+                } else {
+                    self.known_villagers.insert(target);
+                };
+            }
+            NightEvent::ViewedCenter { actor: _, center: _, card } => {
+                self.known_non_active.insert(card.face());
+            }
+            NightEvent::Robbed { actor: _, target: _, card } |
+            NightEvent::Peeked { actor: _, card } => {
+                self.role = card;
+            }
+            NightEvent::Swapped { .. } |
+            NightEvent::TookCenter { .. } => {}
         }
     }
 }
 
-#[derive(PartialEq, Eq,PartialOrd, Ord, Clone,Copy, Debug)]
+//Everything anyone does during the night, kept in `State::night_log` in the
+//order it happened.
+#[derive(Clone,Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum NightEvent {
+    //the Doppelganger copying the role on `target`'s card
+    Copied {
+        actor: Participant,
+        target: Participant,
+        role: Role,
+    },
+    //seeing who else woke up, like the other Werewolves or Masons, or the
+    //Werewolves from the Minion's point of view
+    SawAwake {
+        actor: Participant,
+        target: Participant,
+        card: Card,
+    },
+    //waking up with nobody else who has `role`
+    AwakeAlone { actor: Participant, role: Role },
+    ViewedPlayer {
+        actor: Participant,
+        target: Participant,
+        card: Card,
+    },
+    ViewedCenter {
+        actor: Participant,
+        center: CenterCard,
+        card: Card,
+    },
+    //`card` is the one the Robber took and looked at
+    Robbed {
+        actor: Participant,
+        target: Participant,
+        card: Card,
+    },
+    Swapped {
+        actor: Participant,
+        first: Participant,
+        second: Participant,
+    },
+    TookCenter {
+        actor: Participant,
+        center: CenterCard,
+    },
+    Peeked { actor: Participant, card: Card },
+}
+
+impl NightEvent {
+    pub fn actor(&self) -> Participant {
+        match *self {
+            NightEvent::Copied { actor, .. } |
+            NightEvent::SawAwake { actor, .. } |
+            NightEvent::AwakeAlone { actor, .. } |
+            NightEvent::ViewedPlayer { actor, .. } |
+            NightEvent::ViewedCenter { actor, .. } |
+            NightEvent::Robbed { actor, .. } |
+            NightEvent::Swapped { actor, .. } |
+            NightEvent::TookCenter { actor, .. } |
+            NightEvent::Peeked { actor, .. } => actor,
+        }
+    }
+}
+
+#[derive(PartialEq, Eq,PartialOrd, Ord, Clone,Copy, Debug, Serialize, Deserialize)]
 pub enum Claim {
     Simple(Role),
     DoppelSimple(Participant, Role),
@@ -880,7 +1363,107 @@ pub enum Claim {
 }
 use Claim::*;
 
-#[derive(PartialEq, Eq,PartialOrd, Ord, Clone,Copy, Debug)]
+impl Claim {
+    //what the speaker says was printed on the card they were dealt
+    pub fn face(&self) -> Role {
+        match *self {
+            Simple(role) => role,
+            MasonAction(_) => Mason,
+            RobberAction(_, _) => Robber,
+            SeerRevealOneAction(_, _) |
+            SeerRevealTwoAction(_, _, _) => Seer,
+            TroublemakerAction(_, _) => Troublemaker,
+            InsomniacAction(_) => Insomniac,
+            DrunkAction(_) => Drunk,
+            DoppelSimple(_, _) |
+            DoppelMasonAction(_, _) |
+            DoppelRobberAction(_, _, _) |
+            DoppelSeerRevealOneAction(_, _, _) |
+            DoppelSeerRevealTwoAction(_, _, _, _) |
+            DoppelTroublemakerAction(_, _, _) |
+            DoppelInsomniacAction(_, _) |
+            DoppelDrunkAction(_, _) => Doppelganger,
+        }
+    }
+}
+
+//One thing someone said in the discussion, which goes in rounds.
+#[derive(PartialEq, Eq, Clone,Copy, Debug, Serialize, Deserialize)]
+pub struct Statement {
+    pub round: u8,
+    pub speaker: Participant,
+    pub kind: StatementKind,
+}
+
+impl Statement {
+    //the speaker's story, if this statement tells it
+    pub fn claim(&self) -> Option<Claim> {
+        match self.kind {
+            StatementKind::Opening(claim) |
+            StatementKind::Changed(claim) |
+            StatementKind::Confessed(claim) => Some(claim),
+            _ => None,
+        }
+    }
+}
+
+//Why a cpu voted for who they did. The one they voted for is in `votes`.
+#[derive(PartialEq, Eq, Clone,Copy, Debug, Serialize, Deserialize)]
+pub enum VoteReason {
+    //they saw a Werewolf card in front of them
+    SawWerewolf,
+    //for the Werewolf team, someone they knew wasn't a Werewolf
+    KnewVillager,
+    //their story didn't fit what the voter knew, or they changed it
+    Lied,
+    //someone the voter believed pointed them out
+    Suspected,
+    //going by everything that was said, the likeliest Werewolf
+    Likeliest,
+    //going by everything that was said, the least likely to be a Werewolf
+    LeastLikely,
+    //for the Werewolf team, anyone who wasn't a Werewolf
+    NotAWerewolf,
+    //anyone they had no reason to trust
+    Random,
+    //there was nobody else, so the next seat round
+    NextSeat,
+    //for a Minion, who they said was a Werewolf
+    Framed,
+    //for a Minion, whoever was next in line when the vote was heading for a
+    //Werewolf
+    SplitVotes,
+    //whoever the vote was heading for anyway
+    JoinedIn,
+    //for a Tanner, whoever the fewest other people were voting for
+    FewestVotes,
+    //voting for them won the most games played out from here
+    PlayedOut,
+    //the strategy didn't say
+    Unexplained,
+}
+
+#[derive(PartialEq, Eq, Clone,Copy, Debug, Serialize, Deserialize)]
+pub enum StatementKind {
+    //the first thing they said
+    Opening(Claim),
+    //a different story to the one they told before
+    Changed(Claim),
+    //sticking to their story against someone who contradicted it
+    Countered(Participant),
+    //owning up that what they said before wasn't true
+    Confessed(Claim),
+    //"I think they're a Werewolf"
+    Accused(Participant),
+    //"I believe what they said"
+    Believed(Participant),
+    //"I'm voting for them"
+    VotingFor(Participant),
+    //"if the first one is the role, then the second one is a Werewolf"
+    Reasoned(Participant, Role, Participant),
+}
+
+#[derive(PartialEq, Eq,PartialOrd, Ord, Clone,Copy, Debug, Serialize, Deserialize)]
 pub enum ZeroToTwo<T> {
     Zero,
     One(T),
@@ -964,6 +1547,7 @@ impl Point {
 
 /// Represents a single on-screen point/coordinate pair.
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[repr(C)]
 pub struct Point {
     pub x: i32,
     pub y: i32,
@@ -982,6 +1566,7 @@ impl Point {
 
 /// A 2D size representation.
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[repr(C)]
 pub struct Size {
     pub width: i32,
     pub height: i32,
@@ -1008,6 +1593,7 @@ impl fmt::Display for Size {
 
 /// A rectangle, described by its four corners and a size.
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[repr(C)]
 pub struct Rect {
     /// The top-left corner.
     pub top_left: Point,
@@ -1109,7 +1695,8 @@ impl Rect {
 //input module
 
 /// All pressable keys.
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
+#[repr(u8)]
 pub enum KeyCode {
     A,
     B,
@@ -1254,7 +1841,7 @@ pub enum KeyCode {
 }
 
 /// A single input event.
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
 pub enum Event {
     /// Terminal window closed.
     Close,
@@ -1315,6 +1902,7 @@ pub enum Event {
     ControlReleased,
 }
 
+#[repr(C)]
 pub struct Color {
     pub red: u8,
     pub green: u8,
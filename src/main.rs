@@ -1,6 +1,7 @@
 
 extern crate bear_lib_terminal;
 extern crate common;
+extern crate serde_json;
 
 #[cfg(debug_assertions)]
 extern crate libloading;
@@ -18,6 +19,9 @@ use std::mem;
 
 use common::*;
 
+//the library owns the `State`, all we get is this. See `state_manipulation::ffi`.
+type StateHandle = *mut State;
+
 #[cfg(debug_assertions)]
 const LIB_PATH: &'static str = "./target/debug/libstate_manipulation.so";
 #[cfg(not(debug_assertions))]
@@ -38,24 +42,86 @@ impl Application {
         Application { library: library }
     }
 
-    fn new_state(&self, size: common::Size) -> State {
+    fn new_state(&self, size: common::Size, seed: Option<usize>) -> StateHandle {
         unsafe {
-            let f = self.library.get::<fn(common::Size) -> State>(b"new_state\0").unwrap();
+            let f = self.library
+                .get::<extern "C" fn(i32, i32, bool, u64) -> StateHandle>(b"new_state_handle\0")
+                .unwrap();
 
-            f(size)
+            f(size.width, size.height, seed.is_some(), seed.unwrap_or(0) as u64)
         }
     }
 
     fn update_and_render(&self,
                          platform: &Platform,
-                         state: &mut State,
+                         state: StateHandle,
                          events: &Vec<Event>)
                          -> bool {
+        let events = events_json(events);
+
         unsafe {
             let f = self.library
-                .get::<fn(&Platform, &mut State, &Vec<Event>) -> bool>(b"update_and_render\0")
+                .get::<unsafe extern "C" fn(*const Platform, StateHandle, *const u8, usize)
+                                            -> bool>(b"update_and_render_handle\0")
                 .unwrap();
-            f(platform, state, events)
+            f(platform, state, events.as_ptr(), events.len())
+        }
+    }
+
+    fn drop_state(&self, state: StateHandle) {
+        unsafe {
+            let f = self.library
+                .get::<unsafe extern "C" fn(StateHandle)>(b"drop_state_handle\0")
+                .unwrap();
+
+            f(state)
+        }
+    }
+
+    //the copy is ours, so it outlives the library
+    fn save_state(&self, state: StateHandle) -> Option<Vec<u8>> {
+        unsafe {
+            let save = self.library
+                .get::<unsafe extern "C" fn(StateHandle, *mut usize) -> *mut u8>(b"save_state_handle\0")
+                .unwrap();
+            let free = self.library
+                .get::<unsafe extern "C" fn(*mut u8, usize)>(b"free_saved_state\0")
+                .unwrap();
+
+            let mut len = 0;
+            let buffer = save(state, &mut len);
+
+            if buffer.is_null() {
+                None
+            } else {
+                let result = std::slice::from_raw_parts(buffer, len).to_vec();
+
+                free(buffer, len);
+
+                Some(result)
+            }
+        }
+    }
+
+    fn load_state(&self, saved: &Vec<u8>) -> Option<StateHandle> {
+        unsafe {
+            let f = self.library
+                .get::<unsafe extern "C" fn(*const u8, usize) -> StateHandle>(b"load_state_handle\0")
+                .unwrap();
+
+            let state = f(saved.as_ptr(), saved.len());
+
+            if state.is_null() { None } else { Some(state) }
+        }
+    }
+
+    fn plug_in_bot(&self, state: StateHandle, seat: usize, command: &str) -> bool {
+        unsafe {
+            let f = self.library
+                .get::<unsafe extern "C" fn(StateHandle, usize, *const u8, usize) -> bool>(b"plug_in_bot_handle\0")
+                .unwrap();
+
+            f(state, seat, command.as_ptr(), command.len())
         }
     }
 }
@@ -65,18 +131,39 @@ impl Application {
         Application {}
     }
 
-    fn new_state(&self, size: common::Size) -> State {
-        state_manipulation::new_state(size)
+    fn new_state(&self, size: common::Size, seed: Option<usize>) -> StateHandle {
+        state_manipulation::ffi::new_state_handle(size.width,
+                                                  size.height,
+                                                  seed.is_some(),
+                                                  seed.unwrap_or(0) as u64)
     }
 
     fn update_and_render(&self,
                          platform: &Platform,
-                         state: &mut State,
+                         state: StateHandle,
                          events: &Vec<Event>)
                          -> bool {
-        let mut new_events: Vec<common::Event> =
-            unsafe { events.iter().map(|a| mem::transmute::<Event, common::Event>(*a)).collect() };
-        state_manipulation::update_and_render(platform, state, &mut new_events)
+        let events = events_json(events);
+
+        unsafe {
+            state_manipulation::ffi::update_and_render_handle(platform,
+                                                              state,
+                                                              events.as_ptr(),
+                                                              events.len())
+        }
+    }
+
+    fn drop_state(&self, state: StateHandle) {
+        unsafe { state_manipulation::ffi::drop_state_handle(state) }
+    }
+
+    fn plug_in_bot(&self, state: StateHandle, seat: usize, command: &str) -> bool {
+        unsafe {
+            state_manipulation::ffi::plug_in_bot_handle(state,
+                                                        seat,
+                                                        command.as_ptr(),
+                                                        command.len())
+        }
     }
 }
 
@@ -96,7 +183,10 @@ fn main() {
 
     let mut app = Application::new();
 
-    let mut state = app.new_state(size());
+    let mut state: StateHandle = app.new_state(size(), seed_from_args());
+
+    let bots = bots_from_args();
+    plug_in_bots(&app, state, &bots);
 
     let mut last_modified = if cfg!(debug_assertions) {
         std::fs::metadata(LIB_PATH).unwrap().modified().unwrap()
@@ -138,7 +228,7 @@ fn main() {
 
     let mut events = Vec::new();
 
-    app.update_and_render(&platform, &mut state, &mut events);
+    app.update_and_render(&platform, state, &events);
 
     terminal::refresh();
 
@@ -151,7 +241,7 @@ fn main() {
 
         terminal::clear(None);
 
-        if app.update_and_render(&platform, &mut state, &mut events) {
+        if app.update_and_render(&platform, state, &events) {
             //quit requested
             break;
         }
@@ -161,8 +251,10 @@ fn main() {
         if cfg!(debug_assertions) {
             if let Ok(Ok(modified)) = std::fs::metadata(LIB_PATH).map(|m| m.modified()) {
                 if modified > last_modified {
-                    drop(app);
-                    app = Application::new();
+                    let (new_app, new_state) = reload(app, state);
+                    app = new_app;
+                    state = new_state;
+                    plug_in_bots(&app, state, &bots);
                     last_modified = modified;
                 }
             }
@@ -170,9 +262,98 @@ fn main() {
 
     }
 
+    app.drop_state(state);
+
     terminal::close();
 }
 
+//The new library might lay the `State` out differently, so the old one
+//writes it out and the new one reads it back in (migrating it if need be).
+#[cfg(debug_assertions)]
+fn reload(app: Application, state: StateHandle) -> (Application, StateHandle) {
+    let saved = app.save_state(state);
+    app.drop_state(state);
+
+    //the old library has to be closed first or we'd just get it back again
+    drop(app);
+    let app = Application::new();
+
+    let state = match saved.and_then(|saved| app.load_state(&saved)) {
+        Some(state) => state,
+        None => {
+            println!("couldn't keep the game going through the reload, starting a new one.");
+            app.new_state(size(), None)
+        }
+    };
+
+    (app, state)
+}
+#[cfg(not(debug_assertions))]
+fn reload(app: Application, state: StateHandle) -> (Application, StateHandle) {
+    (app, state)
+}
+
+//`--seed 1234` starts with the same deal as the last time that seed was used
+fn seed_from_args() -> Option<usize> {
+    let mut args = std::env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        if arg == "--seed" {
+            let seed = args.next().and_then(|s| s.parse::<usize>().ok());
+
+            if seed.is_none() {
+                println!("--seed needs a non-negative whole number, ignoring it.");
+            }
+
+            return seed;
+        }
+    }
+
+    None
+}
+
+//`--bot 2=./my_bot --some-flag` has a program play cpu seat 2, see
+//`state_manipulation::bot`. This can be given once for each seat.
+fn bots_from_args() -> Vec<(usize, String)> {
+    let mut bots = Vec::new();
+    let mut args = std::env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        if arg == "--bot" {
+            let bot = args.next().and_then(|bot| {
+                let mut parts = bot.splitn(2, '=');
+
+                match (parts.next().and_then(|s| s.parse::<usize>().ok()), parts.next()) {
+                    (Some(seat), Some(command)) => Some((seat, command.to_owned())),
+                    _ => None,
+                }
+            });
+
+            match bot {
+                Some(bot) => bots.push(bot),
+                None => println!("--bot needs a seat number and a command, like 2=./my_bot, ignoring it."),
+            }
+        }
+    }
+
+    bots
+}
+
+fn plug_in_bots(app: &Application, state: StateHandle, bots: &Vec<(usize, String)>) {
+    for &(seat, ref command) in bots.iter() {
+        app.plug_in_bot(state, seat, command);
+    }
+}
+
+//`common::Event` is laid out like bear_lib_terminal's, but the library might
+//have been built from a newer `common`, so the events go over as json.
+fn events_json(events: &[Event]) -> Vec<u8> {
+    let events: Vec<common::Event> =
+        unsafe { events.iter().map(|&e| mem::transmute::<Event, common::Event>(e)).collect() };
+
+    serde_json::to_vec(&events).unwrap_or_else(|_| b"[]".to_vec())
+}
+
 fn clear(area: Option<common::Rect>) {
     unsafe { terminal::clear(mem::transmute::<Option<common::Rect>, Option<Rect>>(area)) };
 }
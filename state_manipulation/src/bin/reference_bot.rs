@@ -0,0 +1,39 @@
+//About the simplest bot there can be, see `state_manipulation::bot`. It picks
+//the first choice it's given, tells the truth, and says nothing else.
+//
+//`--slow` makes it never answer, so the game has to give up on it.
+
+extern crate serde_json;
+
+use serde_json::Value;
+
+use std::io::{self, BufRead, Write};
+
+fn main() {
+    let slow = std::env::args().any(|arg| arg == "--slow");
+
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+    let mut stdout = stdout.lock();
+
+    for line in stdin.lock().lines() {
+        let question: Value = match line.ok().and_then(|l| serde_json::from_str(&l).ok()) {
+            Some(question) => question,
+            None => break,
+        };
+
+        if slow {
+            continue;
+        }
+
+        let answer = match question["ask"].as_str() {
+            Some("Claim") => question["knowledge"]["true_claim"].clone(),
+            Some("Respond") => Value::Null,
+            _ => question["choices"][0].clone(),
+        };
+
+        if writeln!(stdout, "{}", answer).and_then(|_| stdout.flush()).is_err() {
+            break;
+        }
+    }
+}
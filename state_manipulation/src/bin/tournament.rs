@@ -0,0 +1,487 @@
+//Plays lots of games with nobody at the keyboard and reports how well each
+//cpu strategy did, so a change to how the cpu players think can be measured.
+//
+//    tournament [--games N] [--seed N] [--roles Werewolf,Werewolf,Seer,...]
+//               [--seat N=STRATEGY]... [--pool STRATEGY,STRATEGY,...]
+//               [--playouts N] [--budget MILLISECONDS] [--out FILE]
+//
+//A STRATEGY is the name of a profile, like `Cautious` or `Hard`, or
+//`bot:COMMAND` for a program, see `state_manipulation::bot`. `--seat` puts
+//one in cpu seat N for every game, and the rest of the seats take turns with
+//each of the `--pool`, which is every profile except `Hard` unless it's given.
+//`--playouts` and `--budget` are for `Hard`, see `state_manipulation::search`.
+//Without `--roles` each game gets a random `RoleSpec`, the same way a new game
+//does, and game i is dealt with seed `--seed` + i, so a run can be repeated.
+//
+//The player's seat plays like a `Cautious` cpu player, but can't say
+//anything, since the player's claims aren't part of the game yet. So it's
+//left out of every table, and a team only counts in a game if a cpu seat
+//was on it.
+//
+//The results are printed as tables and written to `--out`, `tournament.json`
+//by default, as json.
+
+extern crate common;
+extern crate serde;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde_json;
+extern crate state_manipulation;
+
+use common::*;
+use common::Participant::*;
+
+use state_manipulation::bot::BotStrategy;
+use state_manipulation::cpu;
+use state_manipulation::cpu::ProfileStrategy;
+use state_manipulation::engine::{self, Action};
+use state_manipulation::outcome::{self, Winner};
+use state_manipulation::search::{self, SearchStrategy};
+
+use std::fs::File;
+use std::io::Write;
+use std::rc::Rc;
+use std::time::Duration;
+
+const DEFAULT_GAMES: usize = 1000;
+const DEFAULT_OUT: &str = "tournament.json";
+
+//a game that goes on longer than this has gone wrong somewhere
+const MAX_ACTIONS: usize = 1000;
+
+//everyone starts here, and this is how far one game can move a rating
+const ELO_START: f64 = 1500.0;
+const ELO_K: f64 = 16.0;
+
+struct Options {
+    games: usize,
+    seed: usize,
+    role_spec: Option<RoleSpec>,
+    seats: Vec<(usize, String)>,
+    pool: Vec<String>,
+    playouts: usize,
+    budget: Duration,
+    out: String,
+}
+
+//Something that can sit in a cpu seat.
+struct Entrant {
+    name: String,
+    //the seat's profile, which is what a bot falls back on
+    profile: CpuProfile,
+    strategy: Option<Rc<dyn CpuStrategy>>,
+}
+
+#[derive(Clone, Default)]
+struct Record {
+    played: u32,
+    won: u32,
+}
+
+impl Record {
+    fn add(&mut self, won: bool) {
+        self.played += 1;
+        if won {
+            self.won += 1;
+        }
+    }
+
+    fn rate(&self) -> f64 {
+        if self.played == 0 {
+            0.0
+        } else {
+            self.won as f64 / self.played as f64
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Row {
+    name: String,
+    played: u32,
+    won: u32,
+    rate: f64,
+    //only for strategies
+    #[serde(skip_serializing_if = "Option::is_none")]
+    elo: Option<f64>,
+}
+
+impl Row {
+    fn new(name: String, record: &Record, elo: Option<f64>) -> Row {
+        Row {
+            name,
+            played: record.played,
+            won: record.won,
+            rate: record.rate(),
+            elo,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Report {
+    games: usize,
+    seed: usize,
+    //`None` if each game had a random `RoleSpec`
+    roles: Option<Vec<String>>,
+    teams: Vec<Row>,
+    starting_roles: Vec<Row>,
+    strategies: Vec<Row>,
+}
+
+fn main() {
+    let options = match parse_args(std::env::args().skip(1).collect()) {
+        Ok(options) => options,
+        Err(e) => {
+            println!("{}", e);
+            println!("usage: tournament [--games N] [--seed N] [--roles ROLE,ROLE,...] \
+                      [--seat N=STRATEGY]... [--pool STRATEGY,...] [--playouts N] \
+                      [--budget MILLISECONDS] [--out FILE]");
+            std::process::exit(1);
+        }
+    };
+
+    let search: Rc<dyn CpuStrategy> = Rc::new(SearchStrategy {
+        budget: options.budget,
+        playouts: options.playouts,
+    });
+
+    let mut entrants: Vec<Entrant> = Vec::new();
+    let entrant_index = |name: &str, entrants: &mut Vec<Entrant>| -> Result<usize, String> {
+        if let Some(index) = entrants.iter().position(|e| e.name == name) {
+            return Ok(index);
+        }
+
+        entrants.push(make_entrant(name, &search)?);
+
+        Ok(entrants.len() - 1)
+    };
+
+    let mut pinned: Vec<(usize, usize)> = Vec::new();
+    let mut pool: Vec<usize> = Vec::new();
+
+    let names = options.seats
+        .iter()
+        .map(|&(seat, ref name)| (Some(seat), name))
+        .chain(options.pool.iter().map(|name| (None, name)));
+
+    for (seat, name) in names {
+        match entrant_index(name, &mut entrants) {
+            Ok(index) => {
+                match seat {
+                    Some(seat) => pinned.push((seat, index)),
+                    None => pool.push(index),
+                }
+            }
+            Err(e) => {
+                println!("{}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    let mut teams = vec![Record::default(); 3];
+    let mut roles = vec![Record::default(); ROLE_COUNT];
+    let mut strategies = vec![Record::default(); entrants.len()];
+    let mut ratings = vec![ELO_START; entrants.len()];
+
+    let mut played = 0;
+
+    for game in 0..options.games {
+        let seed = options.seed.wrapping_add(game);
+
+        let mut state = engine::make_state(false, seed);
+        state.seed = seed;
+        if let Some(role_spec) = options.role_spec {
+            state.role_spec = role_spec;
+        }
+
+        let cpu_count = state.role_spec.get_cpu_player_count(None) as usize;
+
+        let seats: Vec<usize> = (0..cpu_count)
+            .map(|i| match pinned.iter().find(|&&(seat, _)| seat == i) {
+                Some(&(_, index)) => index,
+                None => pool[(game + i) % pool.len()],
+            })
+            .collect();
+
+        for (i, &index) in seats.iter().enumerate() {
+            state.profile_picks[i] = Some(entrants[index].profile);
+
+            if let Some(ref strategy) = entrants[index].strategy {
+                state.cpu_strategies.insert(i, strategy.clone());
+            }
+        }
+
+        if !play(&mut state) {
+            println!("game {} (seed {}) didn't finish, leaving it out", game, seed);
+            continue;
+        }
+
+        let outcome = outcome::resolve(&state);
+
+        let mut present = [false; 3];
+        for result in outcome.results.iter().filter(|result| result.participant != Player) {
+            present[result.role.def().team as usize] = true;
+
+            if let Some(dealt) = state.initial_table.view(result.participant) {
+                roles[dealt.face() as usize].add(result.won);
+            }
+        }
+
+        for (team, record) in teams.iter_mut().enumerate() {
+            if present[team] {
+                record.add(team_won(&outcome, team));
+            }
+        }
+
+        let won: Vec<bool> = (0..cpu_count).map(|i| outcome.won(Cpu(i))).collect();
+
+        for (i, &index) in seats.iter().enumerate() {
+            strategies[index].add(won[i]);
+        }
+
+        rate(&mut ratings, &seats, &won);
+
+        played += 1;
+        if played % 100 == 0 {
+            println!("{} of {} games played", played, options.games);
+        }
+    }
+
+    let report = Report {
+        games: played,
+        seed: options.seed,
+        roles: options.role_spec
+            .map(|spec| spec.get_role_vector().iter().map(|r| format!("{:o}", r)).collect()),
+        teams: ["Village", "Werewolf", "Tanner"]
+            .iter()
+            .zip(teams.iter())
+            .filter(|&(_, record)| record.played > 0)
+            .map(|(name, record)| Row::new(name.to_string(), record, None))
+            .collect(),
+        starting_roles: all_roles()
+            .into_iter()
+            .filter(|&role| roles[role as usize].played > 0)
+            .map(|role| Row::new(format!("{:o}", role), &roles[role as usize], None))
+            .collect(),
+        strategies: entrants.iter()
+            .enumerate()
+            .map(|(i, entrant)| Row::new(entrant.name.clone(), &strategies[i], Some(ratings[i])))
+            .collect(),
+    };
+
+    print_table("Team", &report.teams);
+    print_table("Starting role", &report.starting_roles);
+    print_table("Strategy", &report.strategies);
+
+    let written = serde_json::to_string_pretty(&report)
+        .map_err(|e| e.to_string())
+        .and_then(|json| {
+            File::create(&options.out)
+                .and_then(|mut file| file.write_all(json.as_bytes()))
+                .map_err(|e| e.to_string())
+        });
+
+    match written {
+        Ok(()) => println!("\nwrote {}", options.out),
+        Err(e) => println!("\ncouldn't write {}: {}", options.out, e),
+    }
+}
+
+fn parse_args(args: Vec<String>) -> Result<Options, String> {
+    let mut options = Options {
+        games: DEFAULT_GAMES,
+        seed: 0,
+        role_spec: None,
+        seats: Vec::new(),
+        pool: Vec::new(),
+        playouts: search::DEFAULT_PLAYOUTS,
+        budget: search::DEFAULT_BUDGET,
+        out: DEFAULT_OUT.to_owned(),
+    };
+
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{} needs a value", arg));
+
+        match arg.as_str() {
+            "--games" => options.games = parse_number(&value()?)?,
+            "--seed" => options.seed = parse_number(&value()?)?,
+            "--playouts" => options.playouts = parse_number(&value()?)?,
+            "--budget" => options.budget = Duration::from_millis(parse_number(&value()?)? as u64),
+            "--out" => options.out = value()?,
+            "--roles" => {
+                let roles = value()?
+                    .split(',')
+                    .map(parse_role)
+                    .collect::<Result<Vec<Role>, String>>()?;
+
+                options.role_spec = Some(RoleSpec::from_roles(&roles)
+                    .ok_or_else(|| "those roles aren't a game that can be played".to_owned())?);
+            }
+            "--seat" => {
+                let seat = value()?;
+                let mut parts = seat.splitn(2, '=');
+
+                match (parts.next().and_then(|s| s.parse::<usize>().ok()), parts.next()) {
+                    (Some(seat), Some(name)) => options.seats.push((seat, name.to_owned())),
+                    _ => return Err("--seat needs a seat number and a strategy, like 2=Hard".to_owned()),
+                }
+            }
+            "--pool" => {
+                options.pool = value()?.split(',').map(|name| name.to_owned()).collect();
+            }
+            _ => return Err(format!("don't know what {} means", arg)),
+        }
+    }
+
+    if options.pool.is_empty() {
+        options.pool = all_profiles()
+            .into_iter()
+            .filter(|&profile| !profile_def(profile).searches)
+            .map(|profile| profile.to_string())
+            .collect();
+    }
+
+    Ok(options)
+}
+
+fn parse_number(s: &str) -> Result<usize, String> {
+    s.parse::<usize>().map_err(|_| format!("{} isn't a number", s))
+}
+
+fn parse_role(name: &str) -> Result<Role, String> {
+    all_roles()
+        .into_iter()
+        .find(|role| format!("{:o}", role).eq_ignore_ascii_case(name.trim()))
+        .ok_or_else(|| format!("there's no role called {}", name))
+}
+
+fn make_entrant(name: &str, search: &Rc<dyn CpuStrategy>) -> Result<Entrant, String> {
+    if let Some(command) = name.strip_prefix("bot:") {
+
+        let bot = BotStrategy::spawn(command)
+            .map_err(|e| format!("couldn't start bot \"{}\": {}", command, e))?;
+
+        return Ok(Entrant {
+            name: name.to_owned(),
+            profile: CpuProfile::default(),
+            strategy: Some(Rc::new(bot)),
+        });
+    }
+
+    let profile = all_profiles()
+        .into_iter()
+        .find(|profile| profile.to_string().eq_ignore_ascii_case(name))
+        .ok_or_else(|| format!("there's no strategy called {}", name))?;
+
+    Ok(Entrant {
+        name: profile.to_string(),
+        profile,
+        strategy: if profile_def(profile).searches {
+            Some(search.clone())
+        } else {
+            None
+        },
+    })
+}
+
+//Deals and plays a game through to the `Resolution`, returning whether it
+//got there.
+fn play(state: &mut State) -> bool {
+    if engine::apply(state, Action::Deal).is_err() {
+        return false;
+    }
+
+    for _ in 0..MAX_ACTIONS {
+        if state.turn == Turn::Resolution {
+            return true;
+        }
+
+        let action = match cpu::player_action(state, &ProfileStrategy) {
+            Some(action) => action,
+            None => return false,
+        };
+
+        if engine::apply(state, action).is_err() {
+            return false;
+        }
+    }
+
+    false
+}
+
+fn team_won(outcome: &outcome::GameOutcome, team: usize) -> bool {
+    outcome.winners.iter().any(|&winner| match winner {
+        Winner::Village => team == Team::Village as usize,
+        Winner::Werewolf => team == Team::Werewolf as usize,
+        Winner::Tanner(_) => team == Team::Tanner as usize,
+    })
+}
+
+//Every pair of seats with different strategies counts as a match between
+//those strategies, which the one that won takes, or a draw if they both won
+//or both lost. A game with lots of seats in it moves the ratings about as far
+//as one with a few.
+fn rate(ratings: &mut [f64], seats: &[usize], won: &[bool]) {
+    if seats.len() < 2 {
+        return;
+    }
+
+    let k = ELO_K / (seats.len() - 1) as f64;
+    let mut changes = vec![0.0; ratings.len()];
+
+    for a in 0..seats.len() {
+        for b in a + 1..seats.len() {
+            let (first, second) = (seats[a], seats[b]);
+
+            if first == second {
+                continue;
+            }
+
+            let score = match (won[a], won[b]) {
+                (true, false) => 1.0,
+                (false, true) => 0.0,
+                _ => 0.5,
+            };
+            let expected = 1.0 / (1.0 + 10f64.powf((ratings[second] - ratings[first]) / 400.0));
+
+            changes[first] += k * (score - expected);
+            changes[second] -= k * (score - expected);
+        }
+    }
+
+    for (rating, change) in ratings.iter_mut().zip(changes.iter()) {
+        *rating += change;
+    }
+}
+
+fn print_table(heading: &str, rows: &[Row]) {
+    println!();
+
+    let with_elo = rows.iter().any(|row| row.elo.is_some());
+
+    if with_elo {
+        println!("{:<16}{:>8}{:>8}{:>8}{:>8}", heading, "played", "won", "rate", "elo");
+    } else {
+        println!("{:<16}{:>8}{:>8}{:>8}", heading, "played", "won", "rate");
+    }
+
+    for row in rows.iter() {
+        let rate = format!("{:.1}%", row.rate * 100.0);
+
+        match row.elo {
+            Some(elo) => {
+                println!("{:<16}{:>8}{:>8}{:>8}{:>8.0}",
+                         row.name,
+                         row.played,
+                         row.won,
+                         rate,
+                         elo)
+            }
+            None => println!("{:<16}{:>8}{:>8}{:>8}", row.name, row.played, row.won, rate),
+        }
+    }
+}
@@ -0,0 +1,359 @@
+//Cpu seats played by another program. The program is started when it's put
+//in a seat, and every time the seat has a choice to make it's sent one line
+//of json saying what the seat can see, and has to answer with one line of
+//json. The question looks like this:
+//
+//    {"seat": 2, "ask": "Robber", "dealt": {"Plain": "Robber"},
+//     "knowledge": {...}, "night": [...], "transcript": [...],
+//     "choices": ["Player", {"Cpu": 0}, ...]}
+//
+//`knowledge` is the seat's `Knowledge`, `night` is the `NightEvent`s the seat
+//was the actor of and `transcript` is everything said in the discussion so
+//far. `ask` is one of the `Ask`s below. Where there's a fixed set of answers
+//they're in `choices`, and the answer has to be one of them, or `null` to do
+//nothing if the rules allow it. A `Claim` answers "Claim", a `StatementKind`
+//(or `null` to say nothing more) answers "Respond".
+//
+//If the program takes longer than `timeout` to answer, or stops, it's given
+//up on and the seat's profile takes over, see `ProfileStrategy`. An answer
+//that doesn't fit is ignored the same way, but the program is asked again
+//next time. `src/bin/reference_bot.rs` is about the least a bot can do.
+
+use common::*;
+use common::Participant::*;
+use common::Claim::*;
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json;
+use serde_json::Value;
+
+use std::cell::RefCell;
+use std::io;
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::Duration;
+
+use cpu;
+use cpu::ProfileStrategy;
+use engine::{get_knowledge_copy, get_participants, get_other_participants};
+
+//how long a bot gets to answer before the seat's profile takes over
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_millis(1000);
+
+#[derive(Serialize)]
+enum Ask {
+    Copy,
+    Seer,
+    Robber,
+    Troublemaker,
+    Drunk,
+    Claim,
+    Respond,
+    Vote,
+}
+
+#[derive(Serialize)]
+struct Question<'a> {
+    seat: usize,
+    ask: Ask,
+    dealt: Option<Card>,
+    knowledge: Option<Knowledge>,
+    night: Vec<&'a NightEvent>,
+    transcript: &'a Vec<Statement>,
+    choices: Value,
+}
+
+pub struct BotStrategy {
+    command: String,
+    pub timeout: Duration,
+    connection: RefCell<Option<Connection>>,
+}
+
+struct Connection {
+    child: Child,
+    stdin: ChildStdin,
+    //each line the bot writes, read on another thread so we can stop waiting
+    answers: Receiver<String>,
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+impl BotStrategy {
+    //`command` is the program followed by any arguments, split on whitespace
+    pub fn spawn(command: &str) -> io::Result<BotStrategy> {
+        let mut words = command.split_whitespace();
+        let program = words.next()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "no bot command"))?;
+
+        let mut child = Command::new(program).args(words)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+
+        let stdin = child.stdin.take().expect("stdin was piped");
+        let stdout = child.stdout.take().expect("stdout was piped");
+
+        let (sender, answers) = mpsc::channel();
+
+        thread::spawn(move || for line in BufReader::new(stdout).lines() {
+            match line {
+                Ok(line) => {
+                    if sender.send(line).is_err() {
+                        break;
+                    }
+                }
+                Err(_) => break,
+            }
+        });
+
+        Ok(BotStrategy {
+            command: command.to_owned(),
+            timeout: DEFAULT_TIMEOUT,
+            connection: RefCell::new(Some(Connection {
+                child,
+                stdin,
+                answers,
+            })),
+        })
+    }
+
+    //The bot's answer, or `None` if there wasn't one that made sense. `Some(None)`
+    //is the bot answering `null`.
+    fn ask<T, C>(&self,
+                 state: &State,
+                 seat: Participant,
+                 ask: Ask,
+                 choices: &C)
+                 -> Option<Option<T>>
+        where T: DeserializeOwned,
+              C: Serialize + ?Sized
+    {
+        let index = match seat {
+            Cpu(i) => i,
+            Player => return None,
+        };
+
+        let dealt = match ask {
+            //the Doppelganger copies someone before the cards are all down
+            Ask::Copy => Some(Card::Plain(Role::Doppelganger)),
+            _ => state.initial_table.view(seat),
+        };
+
+        let question = Question {
+            seat: index,
+            ask,
+            dealt,
+            knowledge: get_knowledge_copy(state, seat),
+            night: state.night_log.iter().filter(|e| e.actor() == seat).collect(),
+            transcript: &state.transcript,
+            choices: serde_json::to_value(choices).unwrap_or(Value::Null),
+        };
+
+        let mut connection = self.connection.borrow_mut();
+
+        let answer = match connection.as_mut() {
+            Some(connection) => exchange(connection, &question, self.timeout),
+            None => return None,
+        };
+
+        match answer {
+            Ok(line) => {
+                match serde_json::from_str(&line) {
+                    Ok(answer) => Some(answer),
+                    Err(e) => {
+                        println!("bot \"{}\" gave an answer that doesn't fit: {}", self.command, e);
+
+                        None
+                    }
+                }
+            }
+            Err(e) => {
+                println!("giving up on bot \"{}\": {}", self.command, e);
+                *connection = None;
+
+                None
+            }
+        }
+    }
+
+    //An answer from `choices`. `None` means the profile should pick instead.
+    fn choose<T>(&self,
+                 state: &State,
+                 seat: Participant,
+                 ask: Ask,
+                 choices: &[T])
+                 -> Option<Option<T>>
+        where T: Serialize + DeserializeOwned + PartialEq
+    {
+        match self.ask(state, seat, ask, choices) {
+            Some(Some(answer)) => {
+                if choices.contains(&answer) {
+                    Some(Some(answer))
+                } else {
+                    None
+                }
+            }
+            other => other,
+        }
+    }
+}
+
+fn exchange(connection: &mut Connection,
+            question: &Question,
+            timeout: Duration)
+            -> Result<String, String> {
+    let line = serde_json::to_string(question).map_err(|e| e.to_string())?;
+
+    writeln!(connection.stdin, "{}", line)
+        .and_then(|_| connection.stdin.flush())
+        .map_err(|e| e.to_string())?;
+
+    connection.answers.recv_timeout(timeout).map_err(|e| match e {
+        mpsc::RecvTimeoutError::Timeout => "it took too long to answer".to_owned(),
+        mpsc::RecvTimeoutError::Disconnected => "it stopped".to_owned(),
+    })
+}
+
+impl CpuStrategy for BotStrategy {
+    fn copy_target(&self, state: &mut State, doppelganger: Participant) -> Option<Participant> {
+        let choices = get_other_participants(state, doppelganger);
+
+        self.choose(state, doppelganger, Ask::Copy, &choices)
+            .unwrap_or_else(|| ProfileStrategy.copy_target(state, doppelganger))
+    }
+
+    fn seer_choice(&self, state: &mut State, seer: Participant) -> Option<SeerView> {
+        let mut choices: Vec<SeerView> = get_other_participants(state, seer)
+            .into_iter()
+            .map(SeerView::Player)
+            .collect();
+        choices.extend(CenterPair::all_values().into_iter().map(SeerView::Center));
+
+        self.choose(state, seer, Ask::Seer, &choices)
+            .unwrap_or_else(|| ProfileStrategy.seer_choice(state, seer))
+    }
+
+    fn robber_target(&self, state: &mut State, robber: Participant) -> Option<Participant> {
+        let choices = get_other_participants(state, robber);
+
+        self.choose(state, robber, Ask::Robber, &choices)
+            .unwrap_or_else(|| ProfileStrategy.robber_target(state, robber))
+    }
+
+    fn troublemaker_targets(&self,
+                            state: &mut State,
+                            troublemaker: Participant)
+                            -> Option<(Participant, Participant)> {
+        let others = get_other_participants(state, troublemaker);
+
+        let mut choices = Vec::new();
+        for (i, &first) in others.iter().enumerate() {
+            for &second in others[i + 1..].iter() {
+                choices.push((first, second));
+            }
+        }
+
+        self.choose(state, troublemaker, Ask::Troublemaker, &choices)
+            .unwrap_or_else(|| ProfileStrategy.troublemaker_targets(state, troublemaker))
+    }
+
+    fn drunk_target(&self, state: &mut State, drunk: Participant) -> CenterCard {
+        match self.choose(state, drunk, Ask::Drunk, &CenterCard::all_values()) {
+            Some(Some(center)) => center,
+            //the Drunk has to take a card
+            _ => ProfileStrategy.drunk_target(state, drunk),
+        }
+    }
+
+    fn claim(&self, state: &mut State, participant: Participant) -> Option<Claim> {
+        let participants = get_participants(state);
+
+        match self.ask(state, participant, Ask::Claim, &()) {
+            Some(Some(claim)) => {
+                if names_only(&participants, &claim_names(claim)) {
+                    Some(claim)
+                } else {
+                    ProfileStrategy.claim(state, participant)
+                }
+            }
+            Some(None) => None,
+            None => ProfileStrategy.claim(state, participant),
+        }
+    }
+
+    fn respond(&self, state: &mut State, participant: Participant) -> Option<StatementKind> {
+        let participants = get_participants(state);
+
+        match self.ask(state, participant, Ask::Respond, &()) {
+            Some(Some(kind)) => {
+                if names_only(&participants, &statement_names(kind)) {
+                    Some(kind)
+                } else {
+                    ProfileStrategy.respond(state, participant)
+                }
+            }
+            Some(None) => None,
+            None => ProfileStrategy.respond(state, participant),
+        }
+    }
+
+    fn vote(&self, state: &mut State, voter: Participant) -> (Participant, VoteReason) {
+        let choices = get_other_participants(state, voter);
+
+        match self.choose(state, voter, Ask::Vote, &choices) {
+            Some(Some(vote)) => (vote, VoteReason::Unexplained),
+            _ => ProfileStrategy.vote(state, voter),
+        }
+    }
+}
+
+//a bot could name a seat that isn't at the table
+fn names_only(participants: &[Participant], named: &[Participant]) -> bool {
+    named.iter().all(|p| participants.contains(p))
+}
+
+fn claim_names(claim: Claim) -> Vec<Participant> {
+    match claim {
+        Simple(_) |
+        SeerRevealTwoAction(_, _, _) |
+        InsomniacAction(_) |
+        DrunkAction(_) => Vec::new(),
+        DoppelSimple(p, _) |
+        DoppelSeerRevealTwoAction(p, _, _, _) |
+        DoppelInsomniacAction(p, _) |
+        DoppelDrunkAction(p, _) |
+        RobberAction(p, _) |
+        SeerRevealOneAction(p, _) => vec![p],
+        DoppelRobberAction(p, target, _) |
+        DoppelSeerRevealOneAction(p, target, _) |
+        TroublemakerAction(p, target) => vec![p, target],
+        DoppelTroublemakerAction(p, first, second) => vec![p, first, second],
+        MasonAction(others) => cpu::zero_to_two_vec(others),
+        DoppelMasonAction(p, others) => {
+            let mut named = cpu::zero_to_two_vec(others);
+            named.push(p);
+            named
+        }
+    }
+}
+
+fn statement_names(kind: StatementKind) -> Vec<Participant> {
+    match kind {
+        StatementKind::Opening(claim) |
+        StatementKind::Changed(claim) |
+        StatementKind::Confessed(claim) => claim_names(claim),
+        StatementKind::Countered(p) |
+        StatementKind::Accused(p) |
+        StatementKind::Believed(p) |
+        StatementKind::VotingFor(p) => vec![p],
+        StatementKind::Reasoned(p, _, werewolf) => vec![p, werewolf],
+    }
+}
@@ -0,0 +1,1833 @@
+//How the cpu players decide what to do. The engine asks a seat's
+//`CpuStrategy` for a choice whenever a cpu player has one to make, then
+//applies the rules itself.
+
+use common::*;
+use common::Role::*;
+use common::Participant::*;
+use common::Claim::*;
+use common::Card::*;
+use common::CenterPair::*;
+use common::ZeroToTwo::*;
+
+use rand::Rng;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+use deduction;
+use deduction::Chances;
+use search::SearchStrategy;
+use engine::{Action, available_actions, get_knowledge_copy, get_profile, get_participants,
+             get_other_participants, is_werewolf, is_minion, is_tanner};
+
+//The strategy `participant` plays by: whatever was put in their seat,
+//otherwise the one their profile gives them.
+pub fn strategy(state: &State, participant: Participant) -> Rc<dyn CpuStrategy> {
+    let plugged_in = match participant {
+        Cpu(i) => state.cpu_strategies.get(&i).cloned(),
+        Player => None,
+    };
+
+    plugged_in.unwrap_or_else(|| if profile_def(get_profile(state, participant)).searches {
+        Rc::new(SearchStrategy::default())
+    } else {
+        Rc::new(ProfileStrategy)
+    })
+}
+
+//Plays the way the seat's `CpuProfile` says to, see `NIGHT_POLICIES` and
+//`PROFILE_DEFS`.
+pub struct ProfileStrategy;
+
+impl CpuStrategy for ProfileStrategy {
+    fn copy_target(&self, state: &mut State, doppelganger: Participant) -> Option<Participant> {
+        let knowledge = get_knowledge_copy(state, doppelganger)
+            .unwrap_or_else(|| Knowledge::new(Plain(Doppelganger), doppelganger));
+
+        (night_policy(state, doppelganger).copy)(state, doppelganger, &knowledge)
+    }
+
+    fn seer_choice(&self, state: &mut State, seer: Participant) -> Option<SeerView> {
+        let knowledge = get_knowledge_copy(state, seer)?;
+
+        (night_policy(state, seer).seer)(state, seer, &knowledge)
+    }
+
+    fn robber_target(&self, state: &mut State, robber: Participant) -> Option<Participant> {
+        let knowledge = get_knowledge_copy(state, robber)?;
+
+        (night_policy(state, robber).robber)(state, robber, &knowledge)
+    }
+
+    fn troublemaker_targets(&self,
+                            state: &mut State,
+                            troublemaker: Participant)
+                            -> Option<(Participant, Participant)> {
+        let knowledge = get_knowledge_copy(state, troublemaker)?;
+
+        (night_policy(state, troublemaker).troublemaker)(state, troublemaker, &knowledge)
+    }
+
+    fn drunk_target(&self, state: &mut State, drunk: Participant) -> CenterCard {
+        let knowledge = get_knowledge_copy(state, drunk)
+            .unwrap_or_else(|| Knowledge::new(Plain(Drunk), drunk));
+
+        (night_policy(state, drunk).drunk)(state, drunk, &knowledge)
+    }
+
+    fn claim(&self, state: &mut State, participant: Participant) -> Option<Claim> {
+        make_cpu_claim(state, participant)
+    }
+
+    fn respond(&self, state: &mut State, participant: Participant) -> Option<StatementKind> {
+        respond(state, participant)
+    }
+
+    fn vote(&self, state: &mut State, voter: Participant) -> (Participant, VoteReason) {
+        let knowledge = get_knowledge_copy(state, voter)
+            .unwrap_or_else(|| state.player_knowledge.clone());
+
+        if profile_def(get_profile(state, voter)).deduces {
+            if holds_tanner(state, voter, &knowledge) {
+                return tanner_vote(state, voter);
+            }
+
+            if is_minion(knowledge.role) {
+                if let Some(vote) = minion_vote(state, voter, &knowledge) {
+                    return vote;
+                }
+            }
+        }
+
+        let chances = if profile_def(get_profile(state, voter)).deduces {
+            let sample_seed = state.rng.gen::<usize>();
+
+            deduction::werewolf_chances(state, voter, sample_seed)
+        } else {
+            None
+        };
+
+        get_vote(voter,
+                 get_participants(state),
+                 &knowledge,
+                 chances.as_ref(),
+                 &mut state.rng)
+    }
+}
+
+//What the player would do if `strategy` was playing their seat, for games
+//nobody is at the keyboard for. The player can't claim anything, so this only
+//covers the night and the vote, and acknowledges everything else.
+pub fn player_action(state: &mut State, strategy: &dyn CpuStrategy) -> Option<Action> {
+    let available = available_actions(state, Player);
+
+    let wanted = match state.turn {
+        Turn::SeeRole(true) => strategy.copy_target(state, Player).map(Action::CopyCard),
+        Turn::DoppelSeerTurn | Turn::SeerTurn => {
+            Some(match strategy.seer_choice(state, Player) {
+                Some(SeerView::Player(p)) => Action::ViewPlayer(p),
+                Some(SeerView::Center(pair)) => Action::ViewCenter(pair),
+                None => Action::Skip,
+            })
+        }
+        Turn::DoppelRobberTurn | Turn::RobberTurn => {
+            Some(strategy.robber_target(state, Player).map(Action::Rob).unwrap_or(Action::Skip))
+        }
+        Turn::DoppelTroublemakerTurn | Turn::TroublemakerTurn => {
+            Some(strategy.troublemaker_targets(state, Player)
+                .map(|(first, _)| Action::PickFirst(first))
+                .unwrap_or(Action::Skip))
+        }
+        //the first pick was the strategy's, the second is anyone else's
+        Turn::DoppelTroublemakerSecondChoice(_) |
+        Turn::TroublemakerSecondChoice(_) => {
+            let seconds: Vec<Action> = available.iter()
+                .cloned()
+                .filter(|&action| action != Action::Back)
+                .collect();
+
+            state.rng.choose(&seconds).cloned()
+        }
+        Turn::DoppelDrunkTurn | Turn::DrunkTurn => {
+            Some(Action::TakeCenter(strategy.drunk_target(state, Player)))
+        }
+        Turn::Vote => Some(Action::VoteFor(strategy.vote(state, Player).0)),
+        _ => None,
+    };
+
+    wanted.filter(|action| available.contains(action)).or_else(|| available.first().cloned())
+}
+
+//How a profile makes its choices during the night.
+struct NightPolicy {
+    profile: CpuProfile,
+    copy: fn(&mut State, Participant, &Knowledge) -> Option<Participant>,
+    seer: fn(&mut State, Participant, &Knowledge) -> Option<SeerView>,
+    robber: fn(&mut State, Participant, &Knowledge) -> Option<Participant>,
+    troublemaker: fn(&mut State, Participant, &Knowledge) -> Option<(Participant, Participant)>,
+    drunk: fn(&mut State, Participant, &Knowledge) -> CenterCard,
+}
+
+//Indexed by `CpuProfile as usize`, like `PROFILE_DEFS`.
+const NIGHT_POLICIES: [NightPolicy; PROFILE_COUNT] = [
+    //picks at random, like every cpu player used to
+    NightPolicy {
+        profile: CpuProfile::Naive,
+        copy: random_copy,
+        seer: random_seer,
+        robber: random_robber,
+        troublemaker: random_troublemaker,
+        drunk: random_drunk,
+    },
+    NightPolicy {
+        profile: CpuProfile::Cautious,
+        copy: copy_a_speaker,
+        seer: careful_seer,
+        robber: team_robber,
+        troublemaker: suspicious_troublemaker,
+        drunk: first_drunk,
+    },
+    //goes after whatever will give it something to say, and doesn't mind
+    //a gamble
+    NightPolicy {
+        profile: CpuProfile::Bluffer,
+        copy: copy_the_player,
+        seer: careful_seer,
+        robber: speaker_robber,
+        troublemaker: suspicious_troublemaker,
+        drunk: random_drunk,
+    },
+    NightPolicy {
+        profile: CpuProfile::Truthful,
+        copy: copy_a_speaker,
+        seer: careful_seer,
+        robber: honest_robber,
+        troublemaker: suspicious_troublemaker,
+        drunk: first_drunk,
+    },
+    //only for when the search has nothing to go on, see `search`, so it
+    //plays like Cautious
+    NightPolicy {
+        profile: CpuProfile::Hard,
+        copy: copy_a_speaker,
+        seer: careful_seer,
+        robber: team_robber,
+        troublemaker: suspicious_troublemaker,
+        drunk: first_drunk,
+    },
+];
+
+fn night_policy(state: &State, participant: Participant) -> &'static NightPolicy {
+    let profile = get_profile(state, participant);
+    let policy = &NIGHT_POLICIES[profile as usize];
+
+    debug_assert_eq!(policy.profile, profile);
+
+    policy
+}
+
+//Nobody knows anything before the Doppelganger wakes up, so there's nothing
+//to go on but chance.
+fn random_copy(state: &mut State, doppelganger: Participant, _: &Knowledge) -> Option<Participant> {
+    let other_participants = get_other_participants(state, doppelganger);
+
+    state.rng.choose(&other_participants).cloned()
+}
+
+//A cpu player's card can be checked against what they go on to claim, so
+//copying one gives the Doppelganger someone it can vouch for or catch out.
+fn copy_a_speaker(state: &mut State, doppelganger: Participant, knowledge: &Knowledge) -> Option<Participant> {
+    let speakers: Vec<Participant> = get_other_participants(state, doppelganger)
+        .into_iter()
+        .filter(|&p| p != Player)
+        .collect();
+
+    if speakers.is_empty() {
+        random_copy(state, doppelganger, knowledge)
+    } else {
+        state.rng.choose(&speakers).cloned()
+    }
+}
+
+//The player never says what they are, so nobody can contradict whatever the
+//Doppelganger goes on to say about them.
+fn copy_the_player(state: &mut State, doppelganger: Participant, knowledge: &Knowledge) -> Option<Participant> {
+    if doppelganger == Player {
+        copy_a_speaker(state, doppelganger, knowledge)
+    } else {
+        Some(Player)
+    }
+}
+
+fn random_seer(state: &mut State, seer: Participant, _: &Knowledge) -> Option<SeerView> {
+    let look_at_two = state.rng.gen::<bool>();
+
+    if look_at_two {
+        Some(SeerView::Center(state.rng.gen::<CenterPair>()))
+    } else {
+        let other_participants = get_other_participants(state, seer);
+
+        state.rng.choose(&other_participants).map(|&chosen| SeerView::Player(chosen))
+    }
+}
+
+fn random_robber(state: &mut State, robber: Participant, _: &Knowledge) -> Option<Participant> {
+    let other_participants = get_other_participants(state, robber);
+
+    state.rng.choose(&other_participants).cloned()
+}
+
+fn random_troublemaker(state: &mut State,
+                       troublemaker: Participant,
+                       _: &Knowledge)
+                       -> Option<(Participant, Participant)> {
+    let mut other_participants = get_other_participants(state, troublemaker);
+    state.rng.shuffle(&mut other_participants);
+
+    if let (Some(first_choice), Some(second_choice)) =
+        (other_participants.pop(), other_participants.pop()) {
+        Some((first_choice, second_choice))
+    } else {
+        None
+    }
+}
+
+fn random_drunk(state: &mut State, _: Participant, _: &Knowledge) -> CenterCard {
+    state.rng.gen::<CenterCard>()
+}
+
+//Nobody can have seen a center card before the Drunk wakes up, but two Drunks
+//that take the same one only bring one card out of the center between them,
+//and the second gets the first's old card, which is on the village team. So a
+//careful Drunk always takes the first.
+fn first_drunk(_: &mut State, _: Participant, _: &Knowledge) -> CenterCard {
+    CenterCard::First
+}
+
+//The other players whose cards `participant` hasn't seen, and so knows
+//nothing about.
+fn unknown_players(state: &State, participant: Participant, knowledge: &Knowledge) -> Vec<Participant> {
+    let teams = known_teams(state, participant, knowledge);
+
+    get_other_participants(state, participant)
+        .into_iter()
+        .filter(|p| !teams.contains_key(p))
+        .collect()
+}
+
+//The team of each other player `participant` knows the card of, from seeing
+//it or from what they've been told.
+fn known_teams(state: &State, participant: Participant, knowledge: &Knowledge) -> HashMap<Participant, Team> {
+    let dealt = state.initial_table.view(participant).unwrap_or(knowledge.role);
+    let (seen, _) = seen_faces(participant, dealt, &state.night_log);
+
+    let mut teams: HashMap<Participant, Team> = seen.into_iter()
+        .map(|(p, face)| (p, role_def(face).team))
+        .collect();
+
+    for &p in knowledge.known_villagers.iter() {
+        teams.insert(p, Team::Village);
+    }
+    for &p in knowledge.known_werewolves.iter().chain(knowledge.known_minion.iter()) {
+        teams.insert(p, Team::Werewolf);
+    }
+    if let Some(p) = knowledge.known_tanner {
+        teams.insert(p, Team::Tanner);
+    }
+
+    teams.remove(&participant);
+
+    teams
+}
+
+//The cards in the deck `participant` hasn't seen on anyone.
+fn unseen_roles(state: &State, participant: Participant, knowledge: &Knowledge) -> Vec<Role> {
+    let dealt = state.initial_table.view(participant).unwrap_or(knowledge.role);
+    let (seen, _) = seen_faces(participant, dealt, &state.night_log);
+
+    let mut left = state.role_spec.get_role_vector();
+    for &face in seen.values() {
+        if let Some(index) = left.iter().position(|&r| r == face) {
+            left.remove(index);
+        }
+    }
+
+    left
+}
+
+//A player who doesn't wake up could have any of the cards that don't, while
+//the center always rules out two. So when most of what's left sleeps through
+//the night, a look at a player will probably just find a Villager.
+fn careful_seer(state: &mut State, seer: Participant, knowledge: &Knowledge) -> Option<SeerView> {
+    let unknown = unknown_players(state, seer, knowledge);
+    let left = unseen_roles(state, seer, knowledge);
+
+    let sleepers = left.iter().filter(|&&r| role_def(r).wake_order.is_none()).count();
+
+    if unknown.is_empty() || sleepers * 2 >= left.len() {
+        Some(SeerView::Center(state.rng.gen::<CenterPair>()))
+    } else {
+        state.rng.choose(&unknown).map(|&chosen| SeerView::Player(chosen))
+    }
+}
+
+//A Robber ends up on the team of whoever it robs, so it only robs someone
+//whose card keeps it on the team it's on. On the village team it robs
+//someone it knows nothing about, to find something out, and leaves anyone it
+//knows is on another team alone. On any other team it only robs a teammate it
+//knows about, since anyone else is likely to be a villager.
+fn team_robber(state: &mut State, robber: Participant, knowledge: &Knowledge) -> Option<Participant> {
+    let others = get_other_participants(state, robber);
+
+    rob_for_team(state, robber, knowledge, &others)
+}
+
+//Robs a cpu player if it can, so it knows a card that it'll hear a claim
+//about, and so has something to say in the discussion.
+fn speaker_robber(state: &mut State, robber: Participant, knowledge: &Knowledge) -> Option<Participant> {
+    let speakers: Vec<Participant> = get_other_participants(state, robber)
+        .into_iter()
+        .filter(|&p| p != Player)
+        .collect();
+
+    rob_for_team(state, robber, knowledge, &speakers)
+        .or_else(|| team_robber(state, robber, knowledge))
+}
+
+//Never lies, so a card off the village team would leave it nothing to say
+//but the truth. It keeps its own card when whatever it would take is more
+//likely than not to be one of those.
+fn honest_robber(state: &mut State, robber: Participant, knowledge: &Knowledge) -> Option<Participant> {
+    let left = unseen_roles(state, robber, knowledge);
+    let off_village = left.iter().filter(|&&r| role_def(r).team != Team::Village).count();
+
+    if off_village * 2 > left.len() {
+        None
+    } else {
+        team_robber(state, robber, knowledge)
+    }
+}
+
+fn rob_for_team(state: &mut State,
+                robber: Participant,
+                knowledge: &Knowledge,
+                targets: &[Participant])
+                -> Option<Participant> {
+    let team = role_def(knowledge.role.role()).team;
+    let teams = known_teams(state, robber, knowledge);
+
+    let unknown: Vec<Participant> = targets.iter()
+        .cloned()
+        .filter(|p| !teams.contains_key(p))
+        .collect();
+    let teammates: Vec<Participant> = targets.iter()
+        .cloned()
+        .filter(|p| teams.get(p) == Some(&team))
+        .collect();
+
+    if team == Team::Village && !unknown.is_empty() {
+        state.rng.choose(&unknown).cloned()
+    } else {
+        state.rng.choose(&teammates).cloned()
+    }
+}
+
+//Moves a Werewolf it knows about, if any, off their card, and otherwise swaps
+//the players it knows least about. Players it knows are on the village team
+//are left alone, and so are two Werewolves, since swapping them does nothing.
+fn suspicious_troublemaker(state: &mut State,
+                           troublemaker: Participant,
+                           knowledge: &Knowledge)
+                           -> Option<(Participant, Participant)> {
+    let mut suspects: Vec<Participant> = get_other_participants(state, troublemaker)
+        .into_iter()
+        .filter(|p| knowledge.known_werewolves.contains(p))
+        .collect();
+    let mut unknown = unknown_players(state, troublemaker, knowledge);
+    let mut others: Vec<Participant> = get_other_participants(state, troublemaker)
+        .into_iter()
+        .filter(|p| !suspects.contains(p) && !unknown.contains(p))
+        .collect();
+
+    state.rng.shuffle(&mut suspects);
+    state.rng.shuffle(&mut unknown);
+    state.rng.shuffle(&mut others);
+
+    let first_choice = suspects.pop().or_else(|| unknown.pop());
+    let second_choice = unknown.pop().or_else(|| others.pop());
+
+    if let (Some(first_choice), Some(second_choice)) = (first_choice, second_choice) {
+        Some((first_choice, second_choice))
+    } else {
+        random_troublemaker(state, troublemaker, knowledge)
+    }
+}
+
+pub fn make_cpu_claim(state: &mut State, participant: Participant) -> Option<Claim> {
+    if participant == Player {
+        return None;
+    }
+
+    //the copy is needed so we can get random numbers below
+    let possible_knowledge = get_knowledge_copy(state, participant);
+
+    let profile = profile_def(get_profile(state, participant));
+
+    if let Some(knowledge) = possible_knowledge {
+        let claim = if !profile.lies {
+            knowledge.true_claim
+        } else if is_werewolf(knowledge.role) {
+            attempt_not_to_be_picked(state, participant, &knowledge, profile)
+        } else if is_minion(knowledge.role) {
+            minion_claim(state, participant, &knowledge, profile)
+        } else if holds_tanner(state, participant, &knowledge) {
+            tanner_claim(state, participant, &knowledge, profile)
+        } else if is_tanner(knowledge.role) {
+            //someone else has the Tanner card now, so there's nothing to hide
+            knowledge.true_claim
+        } else {
+            village_bluff(state, &knowledge, profile).unwrap_or(knowledge.true_claim)
+        };
+
+        Some(claim)
+    } else {
+        None
+    }
+}
+
+//Sometimes a village player who learned something keeps it to themselves at
+//first, so a Werewolf might claim their role and get caught out when they
+//come clean. See `reconsider_claim`.
+fn village_bluff(state: &mut State,
+                 knowledge: &Knowledge,
+                 profile: &ProfileDef)
+                 -> Option<Claim> {
+    if !profile.bluffs || !state.rng.gen::<bool>() {
+        return None;
+    }
+
+    let roles = state.role_spec.get_role_vector();
+    let robber_unclaimed = roles.contains(&Robber) &&
+                           !state.claims.values().any(|claim| claim.face() == Robber);
+
+    match knowledge.true_claim {
+        SeerRevealOneAction(_, _) |
+        SeerRevealTwoAction(_, _, _) if roles.contains(&Villager) => Some(Simple(Villager)),
+        //they did swap someone, just not the way they say
+        TroublemakerAction(first, _) if robber_unclaimed => {
+            Some(RobberAction(first, if roles.contains(&Villager) { Villager } else { Seer }))
+        }
+        TroublemakerAction(_, _) if roles.contains(&Villager) => Some(Simple(Villager)),
+        _ => None,
+    }
+}
+
+//The Minion wins if anyone but a Werewolf dies, so a Minion bold enough to
+//claim a night action points the village at someone plausible instead: as
+//the Troublemaker who swapped a Werewolf someone has pointed at with them, or
+//as a Seer who saw them as one, if they're the kind to point. With no
+//Werewolves shown to them, anyone dying will do, so the frame is all there is.
+fn minion_claim(state: &mut State,
+                participant: Participant,
+                knowledge: &Knowledge,
+                profile: &ProfileDef)
+                -> Claim {
+    if !profile.bold_lies {
+        return Simple(Villager);
+    }
+
+    let roles = state.role_spec.get_role_vector();
+    let troublemaker_unclaimed = roles.contains(&Troublemaker) &&
+                                 !state.claims.values().any(|c| c.face() == Troublemaker);
+    let exposed = exposed_werewolf(state, participant, knowledge);
+
+    if let Some(framed) = framing_target(state, participant, knowledge) {
+        match exposed {
+            Some(werewolf) if troublemaker_unclaimed => {
+                return TroublemakerAction(werewolf, framed);
+            }
+            _ => {}
+        }
+
+        if profile.accuses && roles.contains(&Seer) {
+            return SeerRevealOneAction(framed, Werewolf);
+        }
+    }
+
+    attempt_not_to_be_picked(state, participant, knowledge, profile)
+}
+
+//a Werewolf the Minion saw that someone else has already pointed at
+fn exposed_werewolf(state: &State, participant: Participant, knowledge: &Knowledge) -> Option<Participant> {
+    let mut teammates: Vec<Participant> = knowledge.known_werewolves
+        .iter()
+        .filter(|&&p| p != participant)
+        .cloned()
+        .collect();
+    teammates.sort();
+
+    teammates.into_iter().find(|&werewolf| {
+        challenged(state, werewolf) ||
+        state.claims
+            .iter()
+            .any(|(&p, &c)| p != participant && named_face(c, werewolf) == Some(Werewolf))
+    })
+}
+
+//Who the village would most readily believe is a Werewolf, out of everyone
+//`participant` knows isn't one: someone already under suspicion, or with no
+//story that could clear them, rather than anyone someone has vouched for.
+fn framing_target(state: &mut State,
+                  participant: Participant,
+                  knowledge: &Knowledge)
+                  -> Option<Participant> {
+    let vouched_for = |p: Participant| {
+        state.transcript.iter().any(|s| s.speaker != p && s.kind == StatementKind::Believed(p)) ||
+        state.claims.iter().any(|(&speaker, &claim)| {
+            speaker != p &&
+            match claim {
+                MasonAction(named) |
+                DoppelMasonAction(_, named) => zero_to_two_vec(named).contains(&p),
+                _ => named_face(claim, p).map(|r| r != Werewolf).unwrap_or(false),
+            }
+        })
+    };
+
+    let scored: Vec<(Participant, i32)> = get_other_participants(state, participant)
+        .into_iter()
+        .filter(|p| !knowledge.known_werewolves.contains(p))
+        .map(|p| {
+            let mut score = 0;
+
+            if challenged(state, p) {
+                score += 2;
+            }
+            match state.claims.get(&p) {
+                None | Some(&Simple(_)) => score += 1,
+                _ => {}
+            }
+            if vouched_for(p) {
+                score -= 2;
+            }
+
+            (p, score)
+        })
+        .collect();
+
+    let best = scored.iter().map(|&(_, score)| score).max()?;
+    let candidates: Vec<Participant> = scored.iter()
+        .filter(|&&(_, score)| score == best)
+        .map(|&(p, _)| p)
+        .collect();
+
+    state.rng.choose(&candidates).cloned()
+}
+
+//who `participant`'s story puts a Werewolf card on, if they're not a teammate
+fn framed_by(state: &State, participant: Participant, knowledge: &Knowledge) -> Option<Participant> {
+    let teammate = |p: Participant| knowledge.known_werewolves.contains(&p);
+
+    match state.claims.get(&participant) {
+        Some(&SeerRevealOneAction(target, Werewolf)) if !teammate(target) => Some(target),
+        Some(&TroublemakerAction(first, second)) if teammate(first) => Some(second),
+        Some(&TroublemakerAction(first, second)) if teammate(second) => Some(first),
+        _ => None,
+    }
+}
+
+//Whether `participant` has the Tanner card now, as far as they know. The
+//Robber saw what they took, and the Insomniac what they ended up with.
+//Otherwise whoever was dealt it goes by the discussion, and takes it that
+//it's gone once someone they don't take for a liar says they robbed them or
+//swapped their card.
+pub fn holds_tanner(state: &State, participant: Participant, knowledge: &Knowledge) -> bool {
+    let mut holding = is_tanner(knowledge.role);
+
+    for event in state.night_log.iter().filter(|e| e.actor() == participant) {
+        match *event {
+            NightEvent::Robbed { card, .. } => holding = is_tanner(card),
+            NightEvent::Peeked { card, .. } => return is_tanner(card),
+            _ => {}
+        }
+    }
+
+    let moved = state.claims.iter().any(|(&speaker, &claim)| {
+        speaker != participant && !knowledge.liars.contains(&speaker) &&
+        match claim {
+            RobberAction(target, _) |
+            DoppelRobberAction(_, target, _) => target == participant,
+            TroublemakerAction(first, second) |
+            DoppelTroublemakerAction(_, first, second) => first == participant || second == participant,
+            _ => false,
+        }
+    });
+
+    holding && !moved
+}
+
+//The Tanner wins by being voted out, so they want to seem to be lying
+//without ever being caught at it. Given the chance they claim a role there's
+//only one of that someone has already claimed, so the village has to choose
+//who to believe, and otherwise they lie the way a Werewolf would.
+fn tanner_claim(state: &mut State,
+                participant: Participant,
+                knowledge: &Knowledge,
+                profile: &ProfileDef)
+                -> Claim {
+    let roles = state.role_spec.get_role_vector();
+    let one_of_a_kind = |role: Role| roles.iter().filter(|&&r| r == role).count() == 1;
+
+    let mut claimed: Vec<(Participant, Role)> = state.claims
+        .iter()
+        .map(|(&p, &c)| (p, c.face()))
+        .filter(|&(p, face)| {
+            p != participant && one_of_a_kind(face) && face != Doppelganger &&
+            role_def(face).team == Team::Village
+        })
+        .collect();
+    claimed.sort();
+
+    let contests: Vec<Claim> = claimed.into_iter()
+        .map(|(p, face)| match face {
+            //a Seer who says the other one is just a Villager
+            Seer if profile.bold_lies && roles.contains(&Villager) => {
+                SeerRevealOneAction(p, Villager)
+            }
+            face => Simple(face),
+        })
+        .collect();
+
+    if let Some(&contest) = state.rng.choose(&contests) {
+        return contest;
+    }
+
+    attempt_not_to_be_picked(state, participant, knowledge, profile)
+}
+
+//who has said anything against what `participant` said, or about them
+fn pointed_at_by(state: &State, participant: Participant) -> Vec<Participant> {
+    let mut speakers: Vec<Participant> = state.transcript
+        .iter()
+        .filter(|s| match s.kind {
+            StatementKind::Countered(p) |
+            StatementKind::Accused(p) |
+            StatementKind::VotingFor(p) => p == participant,
+            _ => false,
+        })
+        .map(|s| s.speaker)
+        .collect();
+    speakers.dedup();
+
+    speakers
+}
+
+fn changed_story(state: &State, participant: Participant) -> bool {
+    state.transcript.iter().any(|s| {
+        s.speaker == participant && matches!(s.kind, StatementKind::Changed(_))
+    })
+}
+
+//What `participant` says next in the discussion, if anything. Standing up
+//for their story comes first, see `defend_claim`, then what they make of
+//everyone else, see `give_opinion`.
+pub fn respond(state: &mut State, participant: Participant) -> Option<StatementKind> {
+    let knowledge = get_knowledge_copy(state, participant)?;
+    let profile = profile_def(get_profile(state, participant));
+
+    if let Some(&claim) = state.claims.get(&participant) {
+        if let Some(kind) = defend_claim(state, participant, &knowledge, profile, claim) {
+            return Some(kind);
+        }
+    }
+
+    give_opinion(state, participant, &knowledge, profile)
+}
+
+//A village player who bluffed comes clean once the bluff is challenged, or
+//once someone claims the role they were really dealt, since then it has done
+//its job. Liars change their story the first time it's contradicted, unless
+//they're bold enough to stick to it. Anyone else sticks to their story,
+//answering each person who contradicts it once.
+fn defend_claim(state: &mut State,
+                participant: Participant,
+                knowledge: &Knowledge,
+                profile: &ProfileDef,
+                claim: Claim)
+                -> Option<StatementKind> {
+    let contradiction = contradicted_by(state, participant, claim);
+
+    if claim == knowledge.true_claim {
+        return contradiction.map(StatementKind::Countered);
+    }
+
+    if holds_tanner(state, participant, knowledge) {
+        return tanner_defence(state, participant, knowledge, profile, claim, contradiction);
+    }
+
+    //the Tanner card went to someone else, so the lie isn't doing them any good
+    if is_tanner(knowledge.role) {
+        return Some(StatementKind::Confessed(knowledge.true_claim));
+    }
+
+    if !is_werewolf(knowledge.role) && !is_minion(knowledge.role) {
+        let bait_taken = state.claims
+            .iter()
+            .any(|(&p, &other)| p != participant && other.face() == knowledge.true_claim.face());
+
+        return if contradiction.is_some() || bait_taken {
+            Some(StatementKind::Confessed(knowledge.true_claim))
+        } else {
+            None
+        };
+    }
+
+    let other = contradiction?;
+
+    let changed_before = changed_story(state, participant);
+    let accused = state.claims
+        .get(&other)
+        .and_then(|&c| named_face(c, participant))
+        .map(|role| role_def(role).team != Team::Village)
+        .unwrap_or(false);
+
+    if profile.bold_lies && !profile.accuses && !changed_before && !accused {
+        if let Some(new_claim) = plausable_lie(state, participant, knowledge, profile) {
+            if new_claim.face() != claim.face() {
+                return Some(StatementKind::Changed(new_claim));
+            }
+        }
+    }
+
+    Some(StatementKind::Countered(other))
+}
+
+//Anyone who changes their story gets taken for a liar, so a Tanner bold
+//enough changes theirs once: to something else the first time they're
+//contradicted, or if nobody does, to the same role with a detail or two
+//that doesn't match what they said before. After that they stick to it.
+fn tanner_defence(state: &mut State,
+                  participant: Participant,
+                  knowledge: &Knowledge,
+                  profile: &ProfileDef,
+                  claim: Claim,
+                  contradiction: Option<Participant>)
+                  -> Option<StatementKind> {
+    if profile.bold_lies && !changed_story(state, participant) {
+        let slips: Vec<(Claim, f64)> = get_lies(state, participant, knowledge, profile)
+            .into_iter()
+            .filter(|&(lie, _)| lie != claim && (contradiction.is_some() || lie.face() == claim.face()))
+            .collect();
+
+        if let Some(new_claim) = choose_weighted(&slips, &mut state.rng) {
+            return Some(StatementKind::Changed(new_claim));
+        }
+    }
+
+    contradiction.map(StatementKind::Countered)
+}
+
+//The first thing `participant` thinks about someone else that they haven't
+//said yet. The village team says what they know and what follows from other
+//people's claims. The Werewolf team only backs each other up, and points at
+//someone else if they're the kind to.
+fn give_opinion(state: &State,
+                participant: Participant,
+                knowledge: &Knowledge,
+                profile: &ProfileDef)
+                -> Option<StatementKind> {
+    let opinions = if is_werewolf(knowledge.role) || is_minion(knowledge.role) {
+        werewolf_team_opinions(state, participant, knowledge, profile)
+    } else if holds_tanner(state, participant, knowledge) {
+        tanner_opinions(state, participant, profile)
+    } else {
+        village_opinions(state, participant, knowledge, profile)
+    };
+
+    opinions.into_iter()
+        .find(|&kind| !state.transcript.iter().any(|s| s.speaker == participant && s.kind == kind))
+}
+
+fn village_opinions(state: &State,
+                    participant: Participant,
+                    knowledge: &Knowledge,
+                    profile: &ProfileDef)
+                    -> Vec<StatementKind> {
+    let mut opinions = Vec::new();
+
+    let others = |set: &HashSet<Participant>| {
+        let mut vec: Vec<Participant> = set.iter().filter(|&&p| p != participant).cloned().collect();
+        vec.sort();
+        vec
+    };
+    let werewolves = others(&knowledge.known_werewolves);
+    let liars = others(&knowledge.liars);
+
+    if profile.deduces {
+        if let Some(dealt) = state.initial_table.view(participant) {
+            let (seen, _) = seen_faces(participant, dealt, &state.night_log);
+
+            let mut claims: Vec<(Participant, Claim)> = state.claims
+                .iter()
+                .filter(|&(&p, _)| p != participant)
+                .map(|(&p, &c)| (p, c))
+                .collect();
+            claims.sort();
+
+            for (speaker, claim) in claims {
+                if let Some(werewolf) = moved_werewolf(speaker, claim, &seen) {
+                    if werewolf != speaker && werewolf != participant {
+                        opinions.push(StatementKind::Reasoned(speaker, claim.face(), werewolf));
+                    }
+                }
+            }
+        }
+    }
+
+    for &p in werewolves.iter().chain(liars.iter()) {
+        opinions.push(StatementKind::Accused(p));
+    }
+
+    for p in others(&knowledge.trusted) {
+        if !knowledge.liars.contains(&p) && challenged(state, p) {
+            opinions.push(StatementKind::Believed(p));
+        }
+    }
+
+    if let Some(&p) = werewolves.first().or(liars.first()) {
+        opinions.push(StatementKind::VotingFor(p));
+    }
+
+    opinions
+}
+
+fn werewolf_team_opinions(state: &State,
+                          participant: Participant,
+                          knowledge: &Knowledge,
+                          profile: &ProfileDef)
+                          -> Vec<StatementKind> {
+    let mut opinions = Vec::new();
+
+    if !profile.bold_lies {
+        return opinions;
+    }
+
+    let mut teammates: Vec<Participant> = knowledge.known_werewolves
+        .iter()
+        .filter(|&&p| p != participant)
+        .cloned()
+        .collect();
+    teammates.sort();
+
+    for &p in teammates.iter() {
+        if challenged(state, p) {
+            opinions.push(StatementKind::Believed(p));
+        }
+    }
+
+    //a Minion backs up their own story
+    if is_minion(knowledge.role) {
+        if let Some(framed) = framed_by(state, participant, knowledge) {
+            opinions.push(StatementKind::Accused(framed));
+            opinions.push(StatementKind::VotingFor(framed));
+        }
+    }
+
+    if profile.accuses {
+        //whoever is in the way first, otherwise someone who is safe to blame
+        let mut targets: Vec<Participant> = state.transcript
+            .iter()
+            .filter_map(|s| match s.kind {
+                StatementKind::Countered(p) |
+                StatementKind::Accused(p) |
+                StatementKind::VotingFor(p) if p == participant || teammates.contains(&p) => {
+                    Some(s.speaker)
+                }
+                _ => None,
+            })
+            .collect();
+
+        let mut villagers: Vec<Participant> = knowledge.known_villagers.iter().cloned().collect();
+        villagers.sort();
+        targets.extend(villagers);
+
+        if let Some(&target) = targets.iter()
+            .find(|&&p| p != participant && !teammates.contains(&p)) {
+            opinions.push(StatementKind::Accused(target));
+            opinions.push(StatementKind::VotingFor(target));
+        }
+    }
+
+    opinions
+}
+
+//The Tanner stands up for whoever else is under suspicion, and turns on
+//whoever points at them, which is just what a Werewolf would do.
+fn tanner_opinions(state: &State, participant: Participant, profile: &ProfileDef) -> Vec<StatementKind> {
+    let mut opinions = Vec::new();
+
+    if !profile.bold_lies {
+        return opinions;
+    }
+
+    let accusers = pointed_at_by(state, participant);
+
+    let mut suspects: Vec<Participant> = state.claims
+        .keys()
+        .cloned()
+        .filter(|&p| p != participant && !accusers.contains(&p) && challenged(state, p))
+        .collect();
+    suspects.sort();
+
+    if let Some(&suspect) = suspects.first() {
+        opinions.push(StatementKind::Believed(suspect));
+    }
+
+    if let Some(&accuser) = accusers.iter().find(|&&p| p != participant) {
+        opinions.push(StatementKind::Accused(accuser));
+    }
+
+    opinions
+}
+
+//whether anyone has spoken up against what `participant` said
+fn challenged(state: &State, participant: Participant) -> bool {
+    state.transcript.iter().any(|s| {
+        s.speaker != participant &&
+        match s.kind {
+            StatementKind::Countered(p) |
+            StatementKind::Accused(p) |
+            StatementKind::VotingFor(p) => p == participant,
+            _ => false,
+        }
+    })
+}
+
+//Who would have a Werewolf card now if `speaker` swapped the cards `claim`
+//says they did, going by the faces in `seen`.
+fn moved_werewolf(speaker: Participant,
+                  claim: Claim,
+                  seen: &HashMap<Participant, Role>)
+                  -> Option<Participant> {
+    let (first, second) = match claim {
+        RobberAction(target, _) => (speaker, target),
+        TroublemakerAction(first, second) => (first, second),
+        _ => return None,
+    };
+
+    let werewolf = |p: Participant| seen.get(&p) == Some(&Werewolf);
+
+    match (werewolf(first), werewolf(second)) {
+        (true, false) => Some(second),
+        (false, true) => Some(first),
+        _ => None,
+    }
+}
+
+//The first other speaker whose story clashes with `participant`'s claim, and
+//who they haven't already answered: someone who says they were dealt
+//something else, who claims the same one-of-a-kind role, who stood up to
+//them, or who called them a Werewolf.
+fn contradicted_by(state: &State, participant: Participant, claim: Claim) -> Option<Participant> {
+    let roles = state.role_spec.get_role_vector();
+    let one_of_a_kind = |role: Role| roles.iter().filter(|&&r| r == role).count() == 1;
+
+    let answered = |other: Participant| {
+        state.transcript
+            .iter()
+            .any(|s| s.speaker == participant && s.kind == StatementKind::Countered(other))
+    };
+
+    let pointed_at = |other: Participant| {
+        state.transcript
+            .iter()
+            .any(|s| s.speaker == other && s.kind == StatementKind::Accused(participant))
+    };
+
+    let mut others: Vec<(Participant, Claim)> = state.claims
+        .iter()
+        .filter(|&(&p, _)| p != participant)
+        .map(|(&p, &c)| (p, c))
+        .collect();
+    others.sort();
+
+    others.into_iter()
+        .find(|&(other, other_claim)| {
+            let named_otherwise = named_face(other_claim, participant)
+                .map(|role| role != claim.face())
+                .unwrap_or(false);
+            let same_role = other_claim.face() == claim.face() && one_of_a_kind(claim.face());
+            let stood_up_to = state.transcript
+                .iter()
+                .any(|s| s.speaker == other && s.kind == StatementKind::Countered(participant));
+
+            (named_otherwise || same_role || stood_up_to || pointed_at(other)) && !answered(other)
+        })
+        .map(|(other, _)| other)
+}
+
+//what `claim` says `target` was dealt, if it says anything about them
+fn named_face(claim: Claim, target: Participant) -> Option<Role> {
+    match claim {
+        SeerRevealOneAction(p, role) |
+        DoppelSeerRevealOneAction(_, p, role) |
+        RobberAction(p, role) |
+        DoppelRobberAction(_, p, role) if p == target => Some(role),
+        _ => None,
+    }
+}
+
+fn plausable_lie(state: &mut State,
+                 participant: Participant,
+                 knowledge: &Knowledge,
+                 profile: &ProfileDef)
+                 -> Option<Claim> {
+    let lies = get_lies(state, participant, knowledge, profile);
+
+    choose_weighted(&lies, &mut state.rng)
+}
+
+fn attempt_not_to_be_picked(state: &mut State,
+                            participant: Participant,
+                            knowledge: &Knowledge,
+                            profile: &ProfileDef)
+                            -> Claim {
+    if !profile.bold_lies {
+        return Simple(Villager);
+    }
+
+    plausable_lie(state, participant, knowledge, profile).unwrap_or(Simple(Villager))
+}
+
+//Every lie `participant` could tell, with how much they like it. Roles that
+//have all been claimed already are left out while there's anything else to
+//say, and so is anything that doesn't fit what a teammate, or someone
+//covering for them, already said. Teammates are the Werewolves this player
+//knows about.
+pub fn get_lies(state: &State,
+                participant: Participant,
+                knowledge: &Knowledge,
+                profile: &ProfileDef)
+                -> Vec<(Claim, f64)> {
+    let roles = state.role_spec.get_role_vector();
+    let count = |role: Role| roles.iter().filter(|&&r| r == role).count();
+
+    let dealt = state.initial_table.view(participant).unwrap_or(knowledge.role);
+    let (seen, seen_center) = seen_faces(participant, dealt, &state.night_log);
+
+    let others = get_other_participants(state, participant);
+    let is_teammate = |p: Participant| knowledge.known_werewolves.contains(&p);
+
+    let mut claims: Vec<(Participant, Claim)> = state.claims
+        .iter()
+        .filter(|&(&p, _)| p != participant)
+        .map(|(&p, &c)| (p, c))
+        .collect();
+    claims.sort();
+
+    let claimed_face = |p: Participant| {
+        claims.iter().find(|&&(speaker, _)| speaker == p).map(|&(_, claim)| claim.face())
+    };
+
+    //what to say someone else's card was: what it was seen to be, or what they
+    //said it was. Nobody gets named as being on the Werewolf team by accident.
+    let village = |role: Role| role_def(role).team == Team::Village && role != Doppelganger;
+    let guess = if count(Villager) > 0 { Villager } else { Seer };
+    let cover_role = |p: Participant| {
+        seen.get(&p)
+            .cloned()
+            .filter(|&r| village(r))
+            .or_else(|| claimed_face(p).filter(|&r| village(r)))
+            .unwrap_or(guess)
+    };
+
+    //someone already said what this player was dealt, so go along with it
+    let said_about_us: Option<Role> = claims.iter()
+        .filter_map(|&(_, claim)| match claim {
+            SeerRevealOneAction(target, role) |
+            DoppelSeerRevealOneAction(_, target, role) |
+            RobberAction(target, role) |
+            DoppelRobberAction(_, target, role) if target == participant => Some(role),
+            _ => None,
+        })
+        .find(|&role| village(role));
+    let mason_claims: Vec<(Participant, Vec<Participant>)> = claims.iter()
+        .filter_map(|&(speaker, claim)| match claim {
+            MasonAction(named) |
+            DoppelMasonAction(_, named) => Some((speaker, zero_to_two_vec(named))),
+            _ => None,
+        })
+        .collect();
+    let named_as_mason = mason_claims.iter().any(|(_, named)| named.contains(&participant));
+
+    let mut faces: Vec<Role> = roles.iter().cloned().filter(|&r| village(r)).collect();
+    faces.dedup();
+
+    let taken = |face: Role| {
+        let claimed = claims.iter().filter(|&&(_, c)| c.face() == face).count();
+        let seen_elsewhere = seen.iter().filter(|&(&p, &r)| p != participant && r == face).count();
+
+        claimed + seen_elsewhere >= count(face)
+    };
+
+    if named_as_mason {
+        faces.retain(|&face| face == Mason);
+    } else if let Some(role) = said_about_us.filter(|&role| !taken(role)) {
+        faces.retain(|&face| face == role);
+    } else {
+        let open: Vec<Role> = faces.iter().cloned().filter(|&face| !taken(face)).collect();
+
+        if open.is_empty() {
+            //every role is spoken for, so contest someone, as long as it's
+            //not a teammate
+            faces.retain(|&face| !claims.iter().any(|&(p, c)| is_teammate(p) && c.face() == face));
+        } else {
+            faces = open;
+        }
+    }
+
+    let mut result = Vec::new();
+
+    for &face in faces.iter() {
+        //nobody can be holding a card that was seen in the center
+        let weight = if seen_center.iter().any(|&(_, r)| r == face) {
+            4.0
+        } else {
+            1.0
+        };
+
+        let mut options: Vec<(Claim, f64)> = Vec::new();
+
+        match face {
+            Seer => {
+                for &target in others.iter() {
+                    options.push((SeerRevealOneAction(target, cover_role(target)),
+                                  if is_teammate(target) { 2.0 } else { 1.0 }));
+
+                    if profile.accuses && !is_teammate(target) &&
+                       knowledge.known_minion != Some(target) &&
+                       claimed_face(target) != Some(Seer) {
+                        options.push((SeerRevealOneAction(target, Werewolf), 3.0));
+                    }
+                }
+
+                for &pair in [FirstSecond, FirstThird, SecondThird].iter() {
+                    let (first, second) = center_cards(pair);
+                    let known = |center: CenterCard| {
+                        seen_center.iter().find(|&&(c, _)| c == center).map(|&(_, r)| r)
+                    };
+
+                    //only worth it if at least one of them is really there
+                    if known(first).is_some() || known(second).is_some() {
+                        options.push((SeerRevealTwoAction(pair,
+                                                          known(first).unwrap_or(guess),
+                                                          known(second).unwrap_or(guess)),
+                                      2.0));
+                    }
+                }
+            }
+            Robber => {
+                for &target in others.iter() {
+                    let role = cover_role(target);
+
+                    if role != Robber {
+                        options.push((RobberAction(target, role), 1.0));
+                    }
+                }
+            }
+            Troublemaker => {
+                for i in 0..others.len() {
+                    for j in i + 1..others.len() {
+                        options.push((TroublemakerAction(others[i], others[j]), 1.0));
+                    }
+                }
+            }
+            Drunk => {
+                for &center in [CenterCard::First, CenterCard::Second, CenterCard::Third].iter() {
+                    options.push((DrunkAction(center), 1.0));
+                }
+            }
+            Insomniac => {
+                let robbed = claims.iter().any(|&(_, claim)| match claim {
+                    RobberAction(target, _) |
+                    DoppelRobberAction(_, target, _) => target == participant,
+                    _ => false,
+                });
+
+                options.push((InsomniacAction(if robbed { Robber } else { Insomniac }), 1.0));
+            }
+            Mason => {
+                if named_as_mason {
+                    //the Masons all saw each other, so name back whoever named us
+                    let mut masons: Vec<Participant> = mason_claims.iter()
+                        .filter(|&(_, named)| named.contains(&participant))
+                        .map(|&(speaker, _)| speaker)
+                        .collect();
+                    masons.sort();
+
+                    if let Some(named) = vec_to_zero_to_two(&masons) {
+                        options.push((MasonAction(named), 1.0));
+                    }
+                } else if mason_claims.is_empty() {
+                    //two Werewolves can vouch for each other as the Masons
+                    for &teammate in others.iter() {
+                        if is_teammate(teammate) &&
+                           claimed_face(teammate).map(|r| r == Mason).unwrap_or(true) {
+                            options.push((MasonAction(One(teammate)), 3.0));
+                        }
+                    }
+
+                    options.push((MasonAction(Zero), 1.0));
+                }
+            }
+            _ => {
+                options.push((Simple(face), 1.0));
+            }
+        }
+
+        //each role gets the same say, however many ways there are to claim it
+        let total: f64 = options.iter().map(|&(_, w)| w).sum();
+
+        for (claim, w) in options {
+            result.push((claim, weight * w / total));
+        }
+    }
+
+    result
+}
+
+pub fn zero_to_two_vec(named: ZeroToTwo<Participant>) -> Vec<Participant> {
+    match named {
+        Zero => Vec::new(),
+        One(p) => vec![p],
+        Two(p1, p2) => vec![p1, p2],
+    }
+}
+
+fn vec_to_zero_to_two(named: &[Participant]) -> Option<ZeroToTwo<Participant>> {
+    match named.len() {
+        0 => Some(Zero),
+        1 => Some(One(named[0])),
+        2 => Some(Two(named[0], named[1])),
+        _ => None,
+    }
+}
+
+fn center_cards(pair: CenterPair) -> (CenterCard, CenterCard) {
+    match pair {
+        FirstSecond => (CenterCard::First, CenterCard::Second),
+        FirstThird => (CenterCard::First, CenterCard::Third),
+        SecondThird => (CenterCard::Second, CenterCard::Third),
+    }
+}
+
+pub fn choose_weighted<T: Copy, R: Rng>(options: &[(T, f64)], rng: &mut R) -> Option<T> {
+    let total: f64 = options.iter().map(|&(_, w)| w).sum();
+
+    if options.is_empty() || total <= 0.0 {
+        return None;
+    }
+
+    let mut remaining = rng.gen::<f64>() * total;
+
+    for &(thing, weight) in options.iter() {
+        if remaining < weight {
+            return Some(thing);
+        }
+
+        remaining -= weight;
+    }
+
+    options.last().map(|&(thing, _)| thing)
+}
+
+pub fn get_vote(participant: Participant,
+                participants: Vec<Participant>,
+                knowledge: &Knowledge,
+                chances: Option<&Chances>,
+                rng: &mut GameRng)
+                -> (Participant, VoteReason) {
+    if let Some(chances) = chances {
+        if let Some(vote) = vote_by_chances(participant, &participants, knowledge, chances, rng) {
+            return vote;
+        }
+    }
+
+    let (filtered, reason): (Vec<Participant>, VoteReason) = if is_werewolf(knowledge.role) ||
+                                                                 knowledge.role == Plain(Minion) {
+        let mut vec: Vec<Participant> = knowledge.known_villagers
+            .iter()
+            .copied()
+            .collect();
+        vec.sort();
+        if let Some(&villager) = rng.choose(&vec) {
+            return (villager, VoteReason::KnewVillager);
+        }
+
+        (participants.iter()
+             .filter(|p| **p != participant && !knowledge.known_werewolves.contains(p))
+             .cloned()
+             .collect(),
+         VoteReason::NotAWerewolf)
+
+
+    } else {
+        let mut vec: Vec<Participant> = knowledge.known_werewolves
+            .iter()
+            .copied()
+            .collect();
+        vec.sort();
+        if let Some(&werewolf) = rng.choose(&vec) {
+            return (werewolf, VoteReason::SawWerewolf);
+        }
+
+        let mut heard: Vec<Participant> = knowledge.heard_werewolves
+            .iter()
+            .filter(|&&p| p != participant)
+            .cloned()
+            .collect();
+        heard.sort();
+        if let Some(&werewolf) = rng.choose(&heard) {
+            return (werewolf, VoteReason::Suspected);
+        }
+
+        let mut liars: Vec<Participant> = knowledge.liars
+            .iter()
+            .filter(|&&p| p != participant)
+            .cloned()
+            .collect();
+        liars.sort();
+        if let Some(&liar) = rng.choose(&liars) {
+            return (liar, VoteReason::Lied);
+        }
+
+        let mut suspects: Vec<Participant> = knowledge.suspects
+            .iter()
+            .filter(|&&p| p != participant)
+            .cloned()
+            .collect();
+        suspects.sort();
+        if let Some(&suspect) = rng.choose(&suspects) {
+            return (suspect, VoteReason::Suspected);
+        }
+
+        (participants.iter()
+             .filter(|p| {
+                 **p != participant && !knowledge.known_villagers.contains(p) &&
+                 !knowledge.heard_villagers.contains(p) &&
+                 !knowledge.trusted.contains(p)
+             })
+             .cloned()
+             .collect(),
+         VoteReason::Random)
+    };
+
+    if let Some(&p) = rng.choose(&filtered) {
+        return (p, reason);
+    }
+
+    //TODO do process of elimination with known_non_active. (can this happen earlier?)
+
+    //vote clockwise
+    let next = *(match participant {
+                     Player => participants.first(),
+                     Cpu(i) => participants.get(i + 1),
+                 })
+                .unwrap_or(&Player);
+
+    (next, VoteReason::NextSeat)
+}
+
+//A Minion goes by where the discussion says the votes are heading. If that's
+//one of the Werewolves they saw, they vote for whoever is next, to split the
+//village. Otherwise they join in, since anyone else dying will do, and if
+//nobody is heading anywhere they stick to whoever they framed.
+fn minion_vote(state: &State,
+               voter: Participant,
+               knowledge: &Knowledge)
+               -> Option<(Participant, VoteReason)> {
+    let teammate = |p: Participant| knowledge.known_werewolves.contains(&p);
+    let expected = expected_votes(state, voter);
+
+    let most = expected.first().map(|&(_, count)| count).unwrap_or(0);
+    let werewolf_leads = expected.iter().any(|&(p, count)| count == most && teammate(p));
+
+    let framed = framed_by(state, voter, knowledge).map(|p| (p, VoteReason::Framed));
+
+    if werewolf_leads {
+        expected.iter()
+            .find(|&&(p, _)| !teammate(p))
+            .map(|&(p, _)| (p, VoteReason::SplitVotes))
+            .or(framed)
+    } else {
+        expected.first()
+            .map(|&(p, _)| (p, VoteReason::JoinedIn))
+            .or(framed)
+    }
+}
+
+//The Tanner can't vote for themselves, so they vote for whoever the fewest
+//people are heading for, to take as few votes away from themselves as they can.
+fn tanner_vote(state: &mut State, voter: Participant) -> (Participant, VoteReason) {
+    let expected = expected_votes(state, voter);
+    let count = |p: Participant| {
+        expected.iter().find(|&&(e, _)| e == p).map(|&(_, count)| count).unwrap_or(0)
+    };
+
+    let others = get_other_participants(state, voter);
+    let fewest = others.iter().map(|&p| count(p)).min().unwrap_or(0);
+    let candidates: Vec<Participant> = others.into_iter().filter(|&p| count(p) == fewest).collect();
+
+    let vote = state.rng.choose(&candidates).cloned().unwrap_or(Player);
+
+    (vote, VoteReason::FewestVotes)
+}
+
+//How many people said they were voting for each participant, or pointed at
+//them if they didn't say, other than `voter`, most first.
+fn expected_votes(state: &State, voter: Participant) -> Vec<(Participant, usize)> {
+    let mut speakers: Vec<Participant> = state.claims.keys().cloned().filter(|&p| p != voter).collect();
+    speakers.sort();
+
+    let mut counts: Vec<(Participant, usize)> = Vec::new();
+
+    for speaker in speakers {
+        let said = |wanted: fn(StatementKind) -> Option<Participant>| {
+            state.transcript
+                .iter()
+                .rev()
+                .filter(|s| s.speaker == speaker)
+                .filter_map(|s| wanted(s.kind))
+                .next()
+        };
+
+        let target = said(|kind| match kind {
+                StatementKind::VotingFor(p) => Some(p),
+                _ => None,
+            })
+            .or_else(|| {
+                said(|kind| match kind {
+                    StatementKind::Accused(p) => Some(p),
+                    _ => None,
+                })
+            })
+            .or_else(|| {
+                state.claims.get(&speaker).and_then(|&claim| match claim {
+                    SeerRevealOneAction(p, Werewolf) |
+                    DoppelSeerRevealOneAction(_, p, Werewolf) => Some(p),
+                    _ => None,
+                })
+            });
+
+        match target {
+            Some(p) if p != voter && p != speaker => {
+                match counts.iter().position(|&(c, _)| c == p) {
+                    Some(i) => counts[i].1 += 1,
+                    None => counts.push((p, 1)),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    counts.sort_by(|&(a, a_count), &(b, b_count)| b_count.cmp(&a_count).then(a.cmp(&b)));
+
+    counts
+}
+
+//chances closer together than this are treated as the same
+const CHANCE_EPSILON: f64 = 1e-9;
+
+//how much more (or less) likely to be voted for someone is for having lied
+//(or told the truth) as far as the voter can tell
+const BELIEF_WEIGHT: f64 = 0.25;
+
+fn belief_suspicion(knowledge: &Knowledge, participant: Participant) -> f64 {
+    let contested = knowledge.contested
+        .iter()
+        .filter(|&&(a, b)| a == participant || b == participant)
+        .count();
+
+    if knowledge.liars.contains(&participant) {
+        BELIEF_WEIGHT
+    } else if knowledge.trusted.contains(&participant) {
+        -BELIEF_WEIGHT
+    } else if contested > 0 || knowledge.suspects.contains(&participant) {
+        BELIEF_WEIGHT / 2.0
+    } else {
+        0.0
+    }
+}
+
+//The village team votes for whoever is most likely to be a Werewolf, and the
+//Werewolf team for whoever is least likely to be one. Someone dealt a
+//Werewolf who has probably been swapped since is on the village team now.
+fn vote_by_chances(participant: Participant,
+                   participants: &[Participant],
+                   knowledge: &Knowledge,
+                   chances: &Chances,
+                   rng: &mut GameRng)
+                   -> Option<(Participant, VoteReason)> {
+    let werewolf_team = match chances.get(&participant) {
+        Some(&chance) => chance >= 0.5 || knowledge.role == Plain(Minion),
+        None => is_werewolf(knowledge.role) || knowledge.role == Plain(Minion),
+    };
+
+    let scored: Vec<(Participant, f64)> = participants.iter()
+        .filter(|&&p| p != participant)
+        .filter_map(|&p| {
+            chances.get(&p).map(|&chance| {
+                let suspicion = chance + belief_suspicion(knowledge, p);
+
+                (p, if werewolf_team { -suspicion } else { suspicion })
+            })
+        })
+        .collect();
+
+    let best = scored.iter().fold(None, |best: Option<f64>, &(_, score)| match best {
+        Some(b) if b >= score => Some(b),
+        _ => Some(score),
+    });
+
+    best.and_then(|best| {
+        let candidates: Vec<Participant> = scored.iter()
+            .filter(|&&(_, score)| score >= best - CHANCE_EPSILON)
+            .map(|&(p, _)| p)
+            .collect();
+
+        let reason = if werewolf_team {
+            VoteReason::LeastLikely
+        } else {
+            VoteReason::Likeliest
+        };
+
+        rng.choose(&candidates).map(|&p| (p, reason))
+    })
+}
+
+//Works out who `listener` believes, going by whether what each speaker
+//claimed fits what the listener saw during the night, then hears what the
+//people they believe saw. Anyone who changed their story is taken for a
+//liar, and anyone the people they believe point at becomes a suspect. This
+//starts over after everything anyone says, since a later claim can change
+//how an earlier one looks.
+pub fn hear_claims(listener: Participant,
+                   knowledge: &mut Knowledge,
+                   dealt: Card,
+                   night_log: &[NightEvent],
+                   claims: &HashMap<Participant, Claim>,
+                   transcript: &[Statement],
+                   role_spec: &RoleSpec) {
+    let (seen, seen_center) = seen_faces(listener, dealt, night_log);
+
+    let roles = role_spec.get_role_vector();
+    let one_of_a_kind = |role: Role| roles.iter().filter(|&&r| r == role).count() == 1;
+
+    let mut claims: Vec<(Participant, Claim)> = claims.iter()
+        .filter(|&(&p, _)| p != listener)
+        .map(|(&p, &c)| (p, c))
+        .collect();
+    claims.sort();
+
+    knowledge.trusted.clear();
+    knowledge.liars.clear();
+    knowledge.contested.clear();
+
+    for &(speaker, claim) in claims.iter() {
+        match check_claim(listener, dealt, speaker, claim, &seen, &seen_center, &one_of_a_kind) {
+            Some(true) => {
+                knowledge.trusted.insert(speaker);
+            }
+            Some(false) => {
+                knowledge.liars.insert(speaker);
+            }
+            None => {}
+        }
+    }
+
+    //an honest story doesn't need changing
+    for statement in transcript.iter().filter(|s| s.speaker != listener) {
+        if let StatementKind::Changed(_) = statement.kind {
+            knowledge.trusted.remove(&statement.speaker);
+            knowledge.liars.insert(statement.speaker);
+        }
+    }
+
+    //someone they believe vouching for someone else
+    for statement in transcript.iter() {
+        if let StatementKind::Believed(p) = statement.kind {
+            if p != listener && knowledge.trusted.contains(&statement.speaker) &&
+               !knowledge.liars.contains(&p) {
+                knowledge.trusted.insert(p);
+            }
+        }
+    }
+
+    for i in 0..claims.len() {
+        for j in i + 1..claims.len() {
+            let ((a, a_claim), (b, b_claim)) = (claims[i], claims[j]);
+            let face = a_claim.face();
+
+            if face != b_claim.face() || !one_of_a_kind(face) {
+                continue;
+            }
+
+            if knowledge.trusted.contains(&a) {
+                knowledge.liars.insert(b);
+            } else if knowledge.trusted.contains(&b) {
+                knowledge.liars.insert(a);
+            } else if !knowledge.liars.contains(&a) && !knowledge.liars.contains(&b) {
+                knowledge.contested.push((a, b));
+            }
+        }
+    }
+
+    knowledge.suspects.clear();
+
+    let claimed = |p: Participant| claims.iter().find(|&&(c, _)| c == p).map(|&(_, claim)| claim);
+
+    for statement in transcript.iter().filter(|s| s.speaker != listener) {
+        let suspect = match statement.kind {
+            StatementKind::Accused(p) |
+            StatementKind::VotingFor(p) if knowledge.trusted.contains(&statement.speaker) => Some(p),
+            //it only follows if the one they reasoned about isn't lying
+            StatementKind::Reasoned(p, role, werewolf) if !knowledge.liars.contains(&statement.speaker) &&
+                                                          !knowledge.liars.contains(&p) &&
+                                                          claimed(p).map(|c| c.face()) ==
+                                                          Some(role) => Some(werewolf),
+            _ => None,
+        };
+
+        if let Some(p) = suspect {
+            if p != listener {
+                knowledge.suspects.insert(p);
+            }
+        }
+    }
+
+    knowledge.heard_werewolves.clear();
+    knowledge.heard_villagers.clear();
+
+    for &(speaker, claim) in claims.iter() {
+        if !knowledge.trusted.contains(&speaker) || knowledge.liars.contains(&speaker) {
+            continue;
+        }
+
+        match claim {
+            SeerRevealOneAction(target, role) |
+            DoppelSeerRevealOneAction(_, target, role) if target != listener => {
+                hear_face(knowledge, target, role);
+            }
+            _ => {}
+        }
+    }
+}
+
+//The faces of the cards the listener saw during the night, before anyone
+//could have moved them: their own, and any they looked at or woke up with.
+fn seen_faces(listener: Participant,
+              dealt: Card,
+              night_log: &[NightEvent])
+              -> (HashMap<Participant, Role>, Vec<(CenterCard, Role)>) {
+    let mut seen = HashMap::new();
+    let mut seen_center = Vec::new();
+
+    seen.insert(listener, dealt.face());
+
+    for event in night_log.iter().filter(|e| e.actor() == listener) {
+        match *event {
+            NightEvent::Copied { target, role, .. } => {
+                seen.insert(target, role);
+            }
+            NightEvent::SawAwake { target, card, .. } |
+            NightEvent::ViewedPlayer { target, card, .. } |
+            NightEvent::Robbed { target, card, .. } => {
+                seen.insert(target, card.face());
+            }
+            NightEvent::ViewedCenter { center, card, .. } => {
+                seen_center.push((center, card.face()));
+            }
+            _ => {}
+        }
+    }
+
+    (seen, seen_center)
+}
+
+//`Some(true)` if the claim fits what the listener saw in a way a guess
+//probably wouldn't, `Some(false)` if it can't be true, `None` if the
+//listener can't tell.
+fn check_claim<F>(listener: Participant,
+                  dealt: Card,
+                  speaker: Participant,
+                  claim: Claim,
+                  seen: &HashMap<Participant, Role>,
+                  seen_center: &[(CenterCard, Role)],
+                  one_of_a_kind: &F)
+                  -> Option<bool>
+    where F: Fn(Role) -> bool
+{
+    let face = claim.face();
+
+    //they weren't dealt what they say they were
+    if let Some(&seen_face) = seen.get(&speaker) {
+        if seen_face != face {
+            return Some(false);
+        }
+    }
+
+    //or it's somewhere else
+    if one_of_a_kind(face) &&
+       (seen.iter().any(|(&p, &r)| p != speaker && r == face) ||
+        seen_center.iter().any(|&(_, r)| r == face)) {
+        return Some(false);
+    }
+
+    match claim {
+        SeerRevealOneAction(target, role) |
+        DoppelSeerRevealOneAction(_, target, role) |
+        RobberAction(target, role) |
+        DoppelRobberAction(_, target, role) => seen.get(&target).map(|&r| r == role),
+        SeerRevealTwoAction(pair, role1, role2) |
+        DoppelSeerRevealTwoAction(_, pair, role1, role2) => {
+            let (first, second) = center_cards(pair);
+
+            let checked: Vec<bool> = [(first, role1), (second, role2)]
+                .iter()
+                .filter_map(|&(center, role)| {
+                    seen_center.iter().find(|&&(c, _)| c == center).map(|&(_, r)| r == role)
+                })
+                .collect();
+
+            if checked.is_empty() {
+                None
+            } else {
+                Some(checked.iter().all(|&matched| matched))
+            }
+        }
+        MasonAction(others) |
+        DoppelMasonAction(_, others) => {
+            let named = zero_to_two_vec(others);
+
+            if dealt.role() == Mason {
+                //the Masons all saw each other
+                Some(seen.contains_key(&speaker))
+            } else if named.contains(&listener) {
+                Some(false)
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+//A Minion or a Tanner isn't a Werewolf, but they're not on the village side
+//either, so hearing about one doesn't clear anybody.
+fn hear_face(knowledge: &mut Knowledge, target: Participant, role: Role) {
+    if role == Werewolf {
+        knowledge.heard_villagers.remove(&target);
+        knowledge.heard_werewolves.insert(target);
+    } else if role != Minion && role != Tanner && !knowledge.heard_werewolves.contains(&target) {
+        knowledge.heard_villagers.insert(target);
+    }
+}
@@ -0,0 +1,872 @@
+//Working out who is likely to be a Werewolf from one participant's point of
+//view. Every way the cards could have been dealt, and everything the other
+//roles could have done with them during the night, is played out. The ones
+//that don't match what the participant saw are thrown away, and the rest are
+//weighed by how well they fit what everyone has claimed since. If there are
+//too many to go through, a random selection of them is used instead.
+
+use common::*;
+use common::Role::*;
+use common::Card::*;
+use common::Participant::*;
+use common::Claim::*;
+use common::CenterPair::*;
+use common::ZeroToTwo::*;
+
+use rand::Rng;
+use std::collections::HashMap;
+
+//how likely each participant is to be holding a Werewolf card once the
+//night is over
+pub type Chances = HashMap<Participant, f64>;
+
+//more possible worlds than this and they are sampled instead
+const EXACT_LIMIT: f64 = 20000.0;
+const SAMPLE_ATTEMPTS: usize = 20000;
+
+//How likely a claim is to be made in a world where it's false, compared to
+//one where it's true. The village team has no reason to lie, so if they did
+//the world is probably wrong. The Werewolves, the Minion and the Tanner
+//could say anything, so what they say doesn't tell us much either way.
+const VILLAGE_LIE: f64 = 0.02;
+const LIAR_CLAIM: f64 = 0.05;
+
+//Everything the participant doing the deducing knows for certain.
+struct Facts<'a> {
+    observer: Participant,
+    player_count: usize,
+    night_script: &'a [NightStep],
+    //what the observer saw during the night, in order
+    seen: Vec<NightEvent>,
+    claims: &'a HashMap<Participant, Claim>,
+}
+
+//One way the night could have gone.
+struct World {
+    //the cards once the Doppelganger has copied, then at the end of the night
+    initial: Vec<Card>,
+    cards: Vec<Card>,
+    events: Vec<NightEvent>,
+    //the cards as they were when someone other than the observer woke up as
+    //a Seer. Which cards they looked at doesn't change anything, so rather
+    //than trying each of them, a claim is checked against all of them.
+    seer_views: Vec<(Participant, Vec<Card>)>,
+    //how many of `Facts::seen` have happened so far
+    seen_count: usize,
+}
+
+pub fn werewolf_chances(state: &State, observer: Participant, sample_seed: usize) -> Option<Chances> {
+    let initial_card = state.initial_table.view(observer)?;
+
+    let facts = Facts {
+        observer,
+        player_count: state.table.cpu_cards().len() + 1,
+        night_script: &state.night_script,
+        seen: state.night_log.iter().filter(|e| e.actor() == observer).cloned().collect(),
+        claims: &state.claims,
+    };
+
+    let mut remaining = state.role_spec.get_role_vector();
+    match remaining.iter().position(|&r| r == initial_card.face()) {
+        Some(index) => {
+            remaining.remove(index);
+        }
+        None => return None,
+    }
+
+    let observer_seat = seat(observer);
+
+    let mut totals = vec![0.0; facts.player_count];
+    let mut total_weight = 0.0;
+
+    {
+        let mut add_world = |world: &World, weight: f64| {
+            let weight = weight * claims_likelihood(&facts, world);
+
+            for (total, card) in totals.iter_mut().zip(world.cards.iter()) {
+                if card.role() == Werewolf {
+                    *total += weight;
+                }
+            }
+            total_weight += weight;
+        };
+
+        if world_estimate(&facts, &remaining) <= EXACT_LIMIT {
+            let mut counts = role_counts(&remaining);
+            let mut deal = Vec::new();
+
+            for_each_deal(&mut counts, &mut deal, remaining.len(), &mut |deal| {
+                let mut deal = deal.to_vec();
+                deal.insert(observer_seat, initial_card.face());
+
+                let mut odometer = Odometer::new();
+                loop {
+                    if let Some(world) = simulate(&facts, &deal, &mut odometer) {
+                        add_world(&world, odometer.weight());
+                    }
+
+                    if !odometer.advance() {
+                        break;
+                    }
+                }
+            });
+        } else {
+            let mut rng = GameRng::new(sample_seed);
+
+            for _ in 0..SAMPLE_ATTEMPTS {
+                let mut deal = remaining.clone();
+                rng.shuffle(&mut deal);
+                deal.insert(observer_seat, initial_card.face());
+
+                //sampled worlds already turn up as often as they should
+                if let Some(world) = simulate(&facts, &deal, &mut RandomChoices(&mut rng)) {
+                    add_world(&world, 1.0);
+                }
+            }
+        }
+    }
+
+    if total_weight <= 0.0 {
+        return None;
+    }
+
+    Some((0..facts.player_count)
+        .map(|s| (participant(s), totals[s] / total_weight))
+        .collect())
+}
+
+//Up to `count` ways the cards could have been dealt that fit what `observer`
+//has seen so far, with how well each fits what's been claimed. The cards are
+//as they were once the Doppelganger copied, the player's first, then the cpu
+//players' in order, then the center cards in order. Only the part of the
+//night before the current turn is played out, so the observer can ask in the
+//middle of their own turn.
+pub fn sample_deals(state: &State,
+                    observer: Participant,
+                    sample_seed: usize,
+                    count: usize)
+                    -> Vec<(Vec<Card>, f64)> {
+    let initial_card = match state.initial_table.view(observer) {
+        Some(card) => card,
+        None => return Vec::new(),
+    };
+
+    //whatever was claimed before the night belongs to the last game
+    let (night_script, no_claims): (Vec<NightStep>, _) = match state.turn {
+        Turn::Ready | Turn::SeeRole(_) => (Vec::new(), true),
+        turn => {
+            match turn.night_step() {
+                Some(step) => {
+                    (state.night_script.iter().cloned().take_while(|&s| s != step).collect(),
+                     true)
+                }
+                None => (state.night_script.clone(), false),
+            }
+        }
+    };
+    let empty = HashMap::new();
+
+    let facts = Facts {
+        observer,
+        player_count: state.table.cpu_cards().len() + 1,
+        night_script: &night_script,
+        seen: state.night_log.iter().filter(|e| e.actor() == observer).cloned().collect(),
+        claims: if no_claims { &empty } else { &state.claims },
+    };
+
+    let mut remaining = state.role_spec.get_role_vector();
+    match remaining.iter().position(|&r| r == initial_card.face()) {
+        Some(index) => {
+            remaining.remove(index);
+        }
+        None => return Vec::new(),
+    }
+
+    let observer_seat = seat(observer);
+    let mut rng = GameRng::new(sample_seed);
+    let mut result = Vec::new();
+
+    for _ in 0..SAMPLE_ATTEMPTS {
+        if result.len() >= count {
+            break;
+        }
+
+        let mut deal = remaining.clone();
+        rng.shuffle(&mut deal);
+        deal.insert(observer_seat, initial_card.face());
+
+        if let Some(world) = simulate(&facts, &deal, &mut RandomChoices(&mut rng)) {
+            let weight = claims_likelihood(&facts, &world);
+
+            result.push((world.initial, weight));
+        }
+    }
+
+    result
+}
+
+fn seat(participant: Participant) -> usize {
+    match participant {
+        Player => 0,
+        Cpu(i) => i + 1,
+    }
+}
+
+fn participant(seat: usize) -> Participant {
+    if seat == 0 { Player } else { Cpu(seat - 1) }
+}
+
+fn center_seat(facts: &Facts, center: CenterCard) -> usize {
+    facts.player_count +
+    match center {
+        CenterCard::First => 0,
+        CenterCard::Second => 1,
+        CenterCard::Third => 2,
+    }
+}
+
+fn center_card(index: usize) -> CenterCard {
+    match index {
+        0 => CenterCard::First,
+        1 => CenterCard::Second,
+        _ => CenterCard::Third,
+    }
+}
+
+fn role_counts(roles: &[Role]) -> Vec<(Role, usize)> {
+    let mut result: Vec<(Role, usize)> = Vec::new();
+
+    for &role in roles.iter() {
+        match result.iter().position(|&(r, _)| r == role) {
+            Some(i) => result[i].1 += 1,
+            None => result.push((role, 1)),
+        }
+    }
+
+    result
+}
+
+//every distinct order the roles could be dealt out in
+fn for_each_deal<F>(counts: &mut Vec<(Role, usize)>, deal: &mut Vec<Role>, len: usize, f: &mut F)
+    where F: FnMut(&[Role])
+{
+    if deal.len() == len {
+        f(deal);
+        return;
+    }
+
+    for i in 0..counts.len() {
+        if counts[i].1 > 0 {
+            counts[i].1 -= 1;
+            deal.push(counts[i].0);
+
+            for_each_deal(counts, deal, len, f);
+
+            deal.pop();
+            counts[i].1 += 1;
+        }
+    }
+}
+
+//An upper bound on how many worlds `simulate` would be run on.
+fn world_estimate(facts: &Facts, remaining: &[Role]) -> f64 {
+    let mut deals = factorial(remaining.len());
+    for &(_, count) in role_counts(remaining).iter() {
+        deals /= factorial(count);
+    }
+
+    let others = (facts.player_count - 1) as f64;
+
+    //the Seer, Robber and Troublemaker could also have done nothing
+    let mut histories = 1.0;
+    for step in facts.night_script.iter() {
+        histories *= match step.role {
+            Seer => 2.0,
+            Robber => others + 1.0,
+            Troublemaker => others * (others - 1.0) / 2.0 + 1.0,
+            Drunk => 3.0,
+            //who they copy
+            Doppelganger => others,
+            _ => 1.0,
+        };
+    }
+
+    deals * histories
+}
+
+fn factorial(n: usize) -> f64 {
+    (1..n + 1).fold(1.0, |acc, i| acc * i as f64)
+}
+
+//Picks what someone did, when that isn't known.
+trait Choices {
+    //which of `count` things they did
+    fn choose(&mut self, count: usize) -> usize;
+
+    //whether they did nothing, when that was one of `options` things they
+    //could have done
+    fn skipped(&mut self, options: usize) -> bool;
+}
+
+//Goes through every combination of choices, one `simulate` at a time.
+struct Odometer {
+    //the choice made and how likely each choice was
+    choices: Vec<(usize, Vec<f64>)>,
+    position: usize,
+}
+
+impl Odometer {
+    fn new() -> Self {
+        Odometer {
+            choices: Vec::new(),
+            position: 0,
+        }
+    }
+
+    //how likely the current combination is, if everyone chose at random
+    fn weight(&self) -> f64 {
+        self.choices
+            .iter()
+            .take(self.position)
+            .fold(1.0, |acc, &(chosen, ref chances)| acc * chances[chosen])
+    }
+
+    //returns false once every combination has been tried
+    fn advance(&mut self) -> bool {
+        self.choices.truncate(self.position);
+        self.position = 0;
+
+        while let Some((chosen, chances)) = self.choices.pop() {
+            if chosen + 1 < chances.len() {
+                self.choices.push((chosen + 1, chances));
+                return true;
+            }
+        }
+
+        false
+    }
+
+    fn pick(&mut self, chances: Vec<f64>) -> usize {
+        let position = self.position;
+        self.position += 1;
+
+        if position < self.choices.len() {
+            self.choices[position].0
+        } else {
+            self.choices.push((0, chances));
+            0
+        }
+    }
+}
+
+impl Choices for Odometer {
+    fn choose(&mut self, count: usize) -> usize {
+        self.pick(vec![1.0 / count as f64; count])
+    }
+
+    fn skipped(&mut self, options: usize) -> bool {
+        let skip = 1.0 / options as f64;
+
+        self.pick(vec![skip, 1.0 - skip]) == 0
+    }
+}
+
+struct RandomChoices<'a, R: Rng + 'a>(&'a mut R);
+
+impl<'a, R: Rng> Choices for RandomChoices<'a, R> {
+    fn choose(&mut self, count: usize) -> usize {
+        self.0.gen_range(0, count)
+    }
+
+    fn skipped(&mut self, options: usize) -> bool {
+        self.0.gen_range(0, options) == 0
+    }
+}
+
+//Plays out the night the way `engine` does, returning `None` as soon as the
+//observer would have seen something they didn't.
+fn simulate<C: Choices>(facts: &Facts, deal: &[Role], choices: &mut C) -> Option<World> {
+    let n = facts.player_count;
+
+    let mut world = World {
+        initial: Vec::new(),
+        cards: deal.iter().map(|&r| Plain(r)).collect(),
+        events: Vec::new(),
+        seer_views: Vec::new(),
+        seen_count: 0,
+    };
+
+    if let Some(doppel) = (0..n).find(|&s| world.cards[s] == Plain(Doppelganger)) {
+        let target = if participant(doppel) == facts.observer {
+            match own_choice(facts, |e| match *e {
+                NightEvent::Copied { target, .. } => Some(target),
+                _ => None,
+            }) {
+                Some(target) => seat(target),
+                None => return None,
+            }
+        } else {
+            choose_other(n, doppel, choices)
+        };
+
+        let role = world.cards[target].role();
+        world.cards[doppel] = Doppel(participant(target), role);
+
+        if !happen(facts,
+                   &mut world,
+                   NightEvent::Copied {
+                       actor: participant(doppel),
+                       target: participant(target),
+                       role,
+                   }) {
+            return None;
+        }
+    }
+
+    world.initial = world.cards.clone();
+
+    for step in facts.night_script.iter() {
+        if !play_step(facts, &mut world, *step, choices) {
+            return None;
+        }
+    }
+
+    if world.seen_count == facts.seen.len() {
+        Some(world)
+    } else {
+        None
+    }
+}
+
+fn play_step<C: Choices>(facts: &Facts, world: &mut World, step: NightStep, choices: &mut C) -> bool {
+    let n = facts.player_count;
+
+    match step.role {
+        Werewolf => {
+            let werewolves = holders(world, n, |c| c.role() == Werewolf);
+
+            for &werewolf in werewolves.iter() {
+                if !see_awake(facts, world, werewolf, &werewolves) {
+                    return false;
+                }
+            }
+        }
+        Minion => {
+            if let Some(minion) = actor(world, n, step) {
+                let werewolves = holders(world, n, |c| c.role() == Werewolf);
+
+                return see_awake(facts, world, minion, &werewolves);
+            }
+        }
+        Mason => {
+            let masons = holders(world, n, |c| c.role() == Mason);
+
+            for &mason in masons.iter() {
+                let ok = if masons.len() == 1 {
+                    happen(facts,
+                           world,
+                           NightEvent::AwakeAlone {
+                               actor: participant(mason),
+                               role: Mason,
+                           })
+                } else {
+                    see_awake(facts, world, mason, &masons)
+                };
+
+                if !ok {
+                    return false;
+                }
+            }
+        }
+        Seer => {
+            if let Some(seer) = actor(world, n, step) {
+                if participant(seer) == facts.observer {
+                    let viewed: Vec<NightEvent> = facts.seen
+                        .iter()
+                        .filter(|e| {
+                            matches!(**e,
+                                     NightEvent::ViewedPlayer { .. } |
+                                     NightEvent::ViewedCenter { .. })
+                        })
+                        .cloned()
+                        .collect();
+
+                    for event in viewed {
+                        let event = match event {
+                            NightEvent::ViewedPlayer { actor, target, .. } => {
+                                NightEvent::ViewedPlayer {
+                                    actor,
+                                    target,
+                                    card: world.cards[seat(target)],
+                                }
+                            }
+                            NightEvent::ViewedCenter { actor, center, .. } => {
+                                NightEvent::ViewedCenter {
+                                    actor,
+                                    center,
+                                    card: world.cards[center_seat(facts, center)],
+                                }
+                            }
+                            other => other,
+                        };
+
+                        if !happen(facts, world, event) {
+                            return false;
+                        }
+                    }
+                } else if !choices.skipped(n - 1 + CenterPair::all_values().len() + 1) {
+                    let cards = world.cards.clone();
+                    world.seer_views.push((participant(seer), cards));
+                }
+            }
+        }
+        Robber => {
+            if let Some(robber) = actor(world, n, step) {
+                let target = if participant(robber) == facts.observer {
+                    match own_choice(facts, |e| match *e {
+                        NightEvent::Robbed { target, .. } => Some(target),
+                        _ => None,
+                    }) {
+                        Some(target) => seat(target),
+                        None => return true,
+                    }
+                } else if choices.skipped(n) {
+                    return true;
+                } else {
+                    choose_other(n, robber, choices)
+                };
+
+                world.cards.swap(robber, target);
+                let card = world.cards[robber];
+
+                return happen(facts,
+                              world,
+                              NightEvent::Robbed {
+                                  actor: participant(robber),
+                                  target: participant(target),
+                                  card,
+                              });
+            }
+        }
+        Troublemaker => {
+            if let Some(troublemaker) = actor(world, n, step) {
+                let (first, second) = if participant(troublemaker) == facts.observer {
+                    match own_choice(facts, |e| match *e {
+                        NightEvent::Swapped { first, second, .. } => Some((first, second)),
+                        _ => None,
+                    }) {
+                        Some((first, second)) => (seat(first), seat(second)),
+                        None => return true,
+                    }
+                } else if choices.skipped((n - 1) * (n - 2) / 2 + 1) {
+                    return true;
+                } else {
+                    choose_other_pair(n, troublemaker, choices)
+                };
+
+                world.cards.swap(first, second);
+
+                return happen(facts,
+                              world,
+                              NightEvent::Swapped {
+                                  actor: participant(troublemaker),
+                                  first: participant(first),
+                                  second: participant(second),
+                              });
+            }
+        }
+        Drunk => {
+            if let Some(drunk) = actor(world, n, step) {
+                let center = if participant(drunk) == facts.observer {
+                    match own_choice(facts, |e| match *e {
+                        NightEvent::TookCenter { center, .. } => Some(center),
+                        _ => None,
+                    }) {
+                        Some(center) => center,
+                        None => return false,
+                    }
+                } else {
+                    center_card(choices.choose(3))
+                };
+
+                world.cards.swap(drunk, center_seat(facts, center));
+
+                return happen(facts,
+                              world,
+                              NightEvent::TookCenter {
+                                  actor: participant(drunk),
+                                  center,
+                              });
+            }
+        }
+        Insomniac => {
+            if let Some(insomniac) = actor(world, n, step) {
+                let card = world.cards[insomniac];
+
+                return happen(facts,
+                              world,
+                              NightEvent::Peeked {
+                                  actor: participant(insomniac),
+                                  card,
+                              });
+            }
+        }
+        _ => {}
+    }
+
+    true
+}
+
+//Who wakes up for `step`. Like `engine`, the Seer is whoever has the card at
+//the time, the other roles are whoever was dealt it, and a Doppelganger is
+//whoever has the Doppelganger card when the step comes around.
+fn actor(world: &World, n: usize, step: NightStep) -> Option<usize> {
+    (0..n).find(|&s| if step.doppel {
+        match world.cards[s] {
+            Doppel(_, role) => role == step.role,
+            _ => false,
+        }
+    } else if step.role == Seer {
+        world.cards[s] == Plain(Seer)
+    } else {
+        world.initial[s] == Plain(step.role)
+    })
+}
+
+fn holders<F>(world: &World, n: usize, f: F) -> Vec<usize>
+    where F: Fn(&Card) -> bool
+{
+    (0..n).filter(|&s| f(&world.cards[s])).collect()
+}
+
+fn see_awake(facts: &Facts, world: &mut World, actor: usize, seen: &[usize]) -> bool {
+    for &target in seen.iter().filter(|&&s| s != actor) {
+        let card = world.cards[target];
+
+        if !happen(facts,
+                   world,
+                   NightEvent::SawAwake {
+                       actor: participant(actor),
+                       target: participant(target),
+                       card,
+                   }) {
+            return false;
+        }
+    }
+
+    true
+}
+
+fn choose_other<C: Choices>(n: usize, chooser: usize, choices: &mut C) -> usize {
+    let chosen = choices.choose(n - 1);
+
+    if chosen >= chooser { chosen + 1 } else { chosen }
+}
+
+fn choose_other_pair<C: Choices>(n: usize, chooser: usize, choices: &mut C) -> (usize, usize) {
+    let others: Vec<usize> = (0..n).filter(|&s| s != chooser).collect();
+
+    let mut pairs = Vec::new();
+    for i in 0..others.len() {
+        for j in i + 1..others.len() {
+            pairs.push((others[i], others[j]));
+        }
+    }
+
+    pairs[choices.choose(pairs.len())]
+}
+
+fn own_choice<F, T>(facts: &Facts, f: F) -> Option<T>
+    where F: Fn(&NightEvent) -> Option<T>
+{
+    facts.seen.iter().filter_map(f).next()
+}
+
+//returns false if the observer would have seen something else
+fn happen(facts: &Facts, world: &mut World, event: NightEvent) -> bool {
+    if event.actor() == facts.observer {
+        match facts.seen.get(world.seen_count) {
+            Some(seen) if same_event(seen, &event) => {
+                world.seen_count += 1;
+            }
+            _ => return false,
+        }
+    }
+
+    world.events.push(event);
+
+    true
+}
+
+fn same_event(a: &NightEvent, b: &NightEvent) -> bool {
+    match (*a, *b) {
+        (NightEvent::Swapped { actor: actor_a, first: first_a, second: second_a },
+         NightEvent::Swapped { actor: actor_b, first: first_b, second: second_b }) => {
+            actor_a == actor_b &&
+            ((first_a == first_b && second_a == second_b) ||
+             (first_a == second_b && second_a == first_b))
+        }
+        (a, b) => a == b,
+    }
+}
+
+fn claims_likelihood(facts: &Facts, world: &World) -> f64 {
+    let mut result = 1.0;
+
+    for (&speaker, &claim) in facts.claims.iter() {
+        if speaker == facts.observer {
+            continue;
+        }
+
+        let speaker_seat = seat(speaker);
+        if speaker_seat >= facts.player_count {
+            continue;
+        }
+
+        result *= if believed_card(world, speaker, speaker_seat).def().team != Team::Village {
+            LIAR_CLAIM
+        } else if claim_holds(facts, world, speaker, speaker_seat, claim) {
+            1.0
+        } else {
+            VILLAGE_LIE
+        };
+    }
+
+    result
+}
+
+//the card the speaker thinks they have by the time they speak
+fn believed_card(world: &World, speaker: Participant, speaker_seat: usize) -> Card {
+    world.events.iter().fold(world.initial[speaker_seat], |card, event| match *event {
+        NightEvent::Robbed { actor, card, .. } |
+        NightEvent::Peeked { actor, card } if actor == speaker => card,
+        _ => card,
+    })
+}
+
+fn claim_holds(facts: &Facts,
+               world: &World,
+               speaker: Participant,
+               speaker_seat: usize,
+               claim: Claim)
+               -> bool {
+    let initial = world.initial[speaker_seat];
+
+    match claim {
+        Simple(role) => initial == Plain(role),
+        DoppelSimple(p, role) => initial == Doppel(p, role),
+        MasonAction(others) => initial == Plain(Mason) && saw_masons(world, speaker, others),
+        DoppelMasonAction(p, others) => {
+            initial == Doppel(p, Mason) && saw_masons(world, speaker, others)
+        }
+        RobberAction(target, role) => {
+            initial == Plain(Robber) && robbed(world, speaker, target, role)
+        }
+        DoppelRobberAction(p, target, role) => {
+            initial == Doppel(p, Robber) && robbed(world, speaker, target, role)
+        }
+        SeerRevealOneAction(target, role) => {
+            initial == Plain(Seer) && saw(world, speaker, &[(seat(target), role)])
+        }
+        DoppelSeerRevealOneAction(p, target, role) => {
+            initial == Doppel(p, Seer) && saw(world, speaker, &[(seat(target), role)])
+        }
+        SeerRevealTwoAction(pair, role1, role2) => {
+            initial == Plain(Seer) && saw(world, speaker, &center_pair(facts, pair, role1, role2))
+        }
+        DoppelSeerRevealTwoAction(p, pair, role1, role2) => {
+            initial == Doppel(p, Seer) &&
+            saw(world, speaker, &center_pair(facts, pair, role1, role2))
+        }
+        TroublemakerAction(first, second) => {
+            initial == Plain(Troublemaker) && swapped(world, speaker, first, second)
+        }
+        DoppelTroublemakerAction(p, first, second) => {
+            initial == Doppel(p, Troublemaker) && swapped(world, speaker, first, second)
+        }
+        InsomniacAction(role) => initial == Plain(Insomniac) && peeked(world, speaker, role),
+        DoppelInsomniacAction(p, role) => {
+            initial == Doppel(p, Insomniac) && peeked(world, speaker, role)
+        }
+        DrunkAction(center) => initial == Plain(Drunk) && took(world, speaker, center),
+        DoppelDrunkAction(p, center) => initial == Doppel(p, Drunk) && took(world, speaker, center),
+    }
+}
+
+fn saw_masons(world: &World, speaker: Participant, others: ZeroToTwo<Participant>) -> bool {
+    let mut seen: Vec<Participant> = world.events
+        .iter()
+        .filter_map(|e| match *e {
+            NightEvent::SawAwake { actor, target, .. } if actor == speaker => Some(target),
+            _ => None,
+        })
+        .collect();
+    seen.sort();
+
+    let mut claimed = match others {
+        Zero => {
+            return world.events.iter().any(|e| match *e {
+                NightEvent::AwakeAlone { actor, role: Mason } => actor == speaker,
+                _ => false,
+            })
+        }
+        One(p) => vec![p],
+        Two(p1, p2) => vec![p1, p2],
+    };
+    claimed.sort();
+
+    seen == claimed
+}
+
+fn robbed(world: &World, speaker: Participant, target: Participant, role: Role) -> bool {
+    world.events.iter().any(|e| match *e {
+        NightEvent::Robbed { actor, target: t, card } => {
+            actor == speaker && t == target && card.face() == role
+        }
+        _ => false,
+    })
+}
+
+fn center_pair(facts: &Facts, pair: CenterPair, role1: Role, role2: Role) -> [(usize, Role); 2] {
+    let (first, second) = match pair {
+        FirstSecond => (CenterCard::First, CenterCard::Second),
+        FirstThird => (CenterCard::First, CenterCard::Third),
+        SecondThird => (CenterCard::Second, CenterCard::Third),
+    };
+
+    [(center_seat(facts, first), role1), (center_seat(facts, second), role2)]
+}
+
+//whether the speaker, waking up as a Seer, could have seen `faces`
+fn saw(world: &World, speaker: Participant, faces: &[(usize, Role)]) -> bool {
+    world.seer_views.iter().any(|&(seer, ref cards)| {
+        seer == speaker && faces.iter().all(|&(s, role)| s < cards.len() && cards[s].face() == role) &&
+        //a Seer can't look at their own card
+        faces.iter().all(|&(s, _)| s != seat(speaker))
+    })
+}
+
+fn swapped(world: &World, speaker: Participant, first: Participant, second: Participant) -> bool {
+    world.events.iter().any(|e| {
+        same_event(e,
+                   &NightEvent::Swapped {
+                       actor: speaker,
+                       first,
+                       second,
+                   })
+    })
+}
+
+fn peeked(world: &World, speaker: Participant, role: Role) -> bool {
+    world.events.iter().any(|e| match *e {
+        NightEvent::Peeked { actor, card } => actor == speaker && card.face() == role,
+        _ => false,
+    })
+}
+
+fn took(world: &World, speaker: Participant, center: CenterCard) -> bool {
+    world.events.iter().any(|e| match *e {
+        NightEvent::TookCenter { actor, center: c } => actor == speaker && c == center,
+        _ => false,
+    })
+}
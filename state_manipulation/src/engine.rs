@@ -0,0 +1,1500 @@
+//The rules of the game, with no idea how (or if) they are being displayed.
+//Anything that wants to play a game, the terminal ui, a test, a bot, or
+//a script, asks `available_actions` what the player can do then passes
+//one of those to `apply`. The cpu players take their turns in between.
+
+use common::*;
+use common::Role::*;
+use common::Card::*;
+use common::Turn::*;
+use common::Participant::*;
+use common::Claim::*;
+use common::CenterPair::*;
+use common::CenterCard::*;
+use common::ZeroToTwo::*;
+
+use rand::Rng;
+use std::collections::HashMap;
+
+use cpu;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Action {
+    //deal a new game using the current `RoleSpec` and `State::seed`
+    Deal,
+    //deal the most recent game again, see `State::last_deal`
+    Replay,
+    //acknowledge whatever the current turn showed
+    Proceed,
+    //decline an optional night action
+    Skip,
+    CopyCard(Participant),
+    ViewPlayer(Participant),
+    ViewCenter(CenterPair),
+    Rob(Participant),
+    PickFirst(Participant),
+    PickSecond(Participant),
+    //go back to picking the Troublemaker's first player
+    Back,
+    TakeCenter(CenterCard),
+    VoteFor(Participant),
+}
+
+//Things that happened which the player is allowed to know about.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum GameEvent {
+    TurnBegan(Turn),
+    Copied(Participant, Role),
+    SawCard(Participant, Card),
+    SawCenterCard(CenterCard, Card),
+    SwappedCards(Participant, Participant),
+    TookCenterCard(CenterCard),
+    Said(Statement),
+    Voted(Participant, Participant),
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum RuleError {
+    //the action isn't one of `available_actions` for the current turn
+    NotAvailable(Action, Turn),
+}
+
+pub fn make_state(title_screen: bool, seed: usize) -> State {
+    let mut rng = GameRng::new(seed);
+
+    let role_spec = rng.gen::<RoleSpec>();
+
+    let roles = shuffled_roles(&role_spec, &mut rng);
+    let (table, player_knowledge, cpu_knowledge, _) = get_roles_and_knowledge(roles);
+
+    let mut state = State {
+        rng,
+        title_screen,
+        initial_table: table.clone(),
+        table,
+        turn: Ready,
+        player_knowledge,
+        cpu_knowledge,
+        votes: Vec::new(),
+        vote_reasons: Vec::new(),
+        claims: HashMap::new(),
+        transcript: Vec::new(),
+        ui_context: UIContext::new(),
+        night_script: night_script(&role_spec.get_role_vector()),
+        role_spec,
+        show_role_spec: false,
+        night_log: Vec::new(),
+        show_night_log: false,
+        seed,
+        last_deal: None,
+        seed_entry: None,
+        can_continue: false,
+        save_error: None,
+        profile_picks: vec![None; MAXIMUM_CPU_PLAYERS as usize],
+        cpu_profiles: Vec::new(),
+        cpu_strategies: HashMap::new(),
+    };
+
+    record_dealt_copy(&mut state);
+
+    state
+}
+
+//the cpu Doppelganger copies while the cards are dealt, so that goes in the
+//log before anything else happens.
+fn record_dealt_copy(state: &mut State) {
+    let copy = get_cpu_participants(state)
+        .into_iter()
+        .filter_map(|p| match get_role(state, p) {
+            Some(Doppel(target, role)) => Some((p, target, role)),
+            _ => None,
+        })
+        .next();
+
+    if let Some((actor, target, role)) = copy {
+        record(state,
+               NightEvent::Copied {
+                   actor,
+                   target,
+                   role,
+               });
+    }
+}
+
+fn record(state: &mut State, event: NightEvent) {
+    if let Some(knowledge) = get_knowledge_mut(state, event.actor()) {
+        knowledge.learn(&event);
+    }
+
+    state.night_log.push(event);
+}
+
+fn shuffled_roles(role_spec: &RoleSpec, rng: &mut GameRng) -> Vec<Card> {
+    let mut roles: Vec<Card> = role_spec.get_role_vector().into_iter().map(Plain).collect();
+
+    rng.shuffle(&mut roles);
+
+    roles
+}
+
+//Deals `roles` off the end: the last one to the player, the three before
+//that to the center, starting with the first center card, and the rest to
+//the cpu players in order.
+fn get_roles_and_knowledge(roles: Vec<Card>) -> (Table, Knowledge, Vec<Knowledge>, bool) {
+    let mut roles = roles;
+
+    let player = roles.pop().unwrap();
+
+    let table_roles = [roles.pop().unwrap(), roles.pop().unwrap(), roles.pop().unwrap()];
+
+    let cpu_roles = roles;
+
+    let player_knowledge = Knowledge::new(player, Player);
+
+    let cpu_knowledge = cpu_roles.iter()
+        .enumerate()
+        .map(|(i, &role)| Knowledge::new(role, Cpu(i)))
+        .collect();
+
+    (Table::new(player, cpu_roles, table_roles),
+     player_knowledge,
+     cpu_knowledge,
+     player == Plain(Doppelganger))
+}
+
+//Only the player makes choices through here, the cpu players' turns happen
+//inside `apply` and `advance`, so asking about a cpu player gets nothing.
+pub fn available_actions(state: &State, participant: Participant) -> Vec<Action> {
+    if participant != Player || !player_acts(state) {
+        return Vec::new();
+    }
+
+    let cpus = get_cpu_participants(state);
+
+    match state.turn {
+        Ready => {
+            if state.last_deal.is_some() {
+                vec![Action::Deal, Action::Replay]
+            } else {
+                vec![Action::Deal]
+            }
+        }
+        SeeRole(true) => cpus.into_iter().map(Action::CopyCard).collect(),
+        DoppelSeerTurn | SeerTurn => {
+            let mut result: Vec<Action> = cpus.into_iter().map(Action::ViewPlayer).collect();
+            result.extend(CenterPair::all_values().into_iter().map(Action::ViewCenter));
+            result.push(Action::Skip);
+            result
+        }
+        DoppelRobberTurn | RobberTurn => {
+            let mut result: Vec<Action> = cpus.into_iter().map(Action::Rob).collect();
+            result.push(Action::Skip);
+            result
+        }
+        DoppelTroublemakerTurn | TroublemakerTurn => {
+            let mut result: Vec<Action> = cpus.into_iter().map(Action::PickFirst).collect();
+            result.push(Action::Skip);
+            result
+        }
+        DoppelTroublemakerSecondChoice(first_choice) |
+        TroublemakerSecondChoice(first_choice) => {
+            let mut result: Vec<Action> = cpus.into_iter()
+                .filter(|&p| p != first_choice)
+                .map(Action::PickSecond)
+                .collect();
+            result.push(Action::Back);
+            result
+        }
+        DoppelDrunkTurn | DrunkTurn => {
+            CenterCard::all_values().into_iter().map(Action::TakeCenter).collect()
+        }
+        Vote => cpus.into_iter().map(Action::VoteFor).collect(),
+        SeeRole(false) |
+        DoppelSeerRevealOne(_) |
+        DoppelSeerRevealTwo(_) |
+        DoppelRobberReveal |
+        DoppelMinionTurn |
+        Werewolves |
+        MinionTurn |
+        MasonTurn |
+        SeerRevealOne(_) |
+        SeerRevealTwo(_) |
+        RobberReveal |
+        InsomniacTurn |
+        DoppelInsomniacTurn |
+        Discuss |
+        Resolution => vec![Action::Proceed],
+        BeginDiscussion => Vec::new(),
+    }
+}
+
+fn player_acts(state: &State) -> bool {
+    match state.turn {
+        DoppelSeerTurn => is_doppel_seer(&state.table.player()),
+        SeerTurn => is_seer(&state.table.player()),
+        DoppelRobberTurn => is_player_doppel_robber(state),
+        RobberTurn => is_player_robber(state),
+        DoppelTroublemakerTurn => is_player_doppel_troublemaker(state),
+        TroublemakerTurn => is_player_troublemaker(state),
+        DoppelDrunkTurn => is_player_doppel_drunk(state),
+        DrunkTurn => is_player_drunk(state),
+        DoppelMinionTurn => is_player_doppel_minion(state),
+        MinionTurn => is_player_minion(state),
+        Werewolves => is_werewolf(state.table.player()),
+        MasonTurn => is_mason(state.table.player()),
+        InsomniacTurn => is_player_insomniac(state),
+        DoppelInsomniacTurn => is_player_doppel_insomniac(state),
+        BeginDiscussion => false,
+        Ready |
+        SeeRole(_) |
+        DoppelSeerRevealOne(_) |
+        DoppelSeerRevealTwo(_) |
+        DoppelRobberReveal |
+        DoppelTroublemakerSecondChoice(_) |
+        SeerRevealOne(_) |
+        SeerRevealTwo(_) |
+        RobberReveal |
+        TroublemakerSecondChoice(_) |
+        Discuss |
+        Vote |
+        Resolution => true,
+    }
+}
+
+pub fn apply(state: &mut State, action: Action) -> Result<Vec<GameEvent>, RuleError> {
+    if !available_actions(state, Player).contains(&action) {
+        return Err(RuleError::NotAvailable(action, state.turn));
+    }
+
+    let mut events = Vec::new();
+
+    let turn = state.turn;
+    let table_before = state.table.clone();
+    perform(state, action, &mut events);
+    //dealing is the one time the cards are supposed to change
+    if turn != Ready {
+        check_cards_conserved(turn, &table_before, &state.table);
+    }
+    if turn != state.turn {
+        events.push(GameEvent::TurnBegan(state.turn));
+    }
+
+    events.extend(advance(state));
+
+    Ok(events)
+}
+
+//Plays out every turn that the player has no part in, stopping once the
+//player has something to do.
+pub fn advance(state: &mut State) -> Vec<GameEvent> {
+    let mut events = Vec::new();
+
+    while !player_acts(state) {
+        let turn = state.turn;
+        let table_before = state.table.clone();
+        take_cpu_turn(state, &mut events);
+        check_cards_conserved(turn, &table_before, &state.table);
+
+        events.push(GameEvent::TurnBegan(state.turn));
+    }
+
+    events
+}
+
+//The night only visits the steps in `state.night_script`, in order.
+pub fn next_turn(state: &State) -> Turn {
+    match state.turn {
+        Ready => SeeRole(false),
+        SeeRole(_) => first_turn_from(state, 0),
+        BeginDiscussion => Discuss,
+        Discuss => Vote,
+        Vote => Resolution,
+        Resolution => Ready,
+        turn => {
+            match turn.night_step()
+                .and_then(|step| linear_search(&state.night_script, &step)) {
+                Some(index) => first_turn_from(state, index + 1),
+                None => BeginDiscussion,
+            }
+        }
+    }
+}
+
+fn first_turn_from(state: &State, index: usize) -> Turn {
+    state.night_script
+        .iter()
+        .skip(index)
+        .filter_map(|&step| Turn::for_night_step(step))
+        .next()
+        .unwrap_or(BeginDiscussion)
+}
+
+fn check_cards_conserved(turn: Turn, before: &Table, after: &Table) {
+    assert!(before.has_same_cards(after),
+            "{:?} changed the cards on the table from {:?} to {:?}",
+            turn,
+            before.cards(),
+            after.cards());
+}
+
+fn perform(state: &mut State, action: Action, events: &mut Vec<GameEvent>) {
+    match (state.turn, action) {
+        (Ready, Action::Deal) => {
+            deal_and_see_role(state, events);
+        }
+        (Ready, Action::Replay) => {
+            if let Some((seed, role_spec)) = state.last_deal {
+                state.seed = seed;
+                state.role_spec = role_spec;
+            }
+
+            deal_and_see_role(state, events);
+        }
+        (SeeRole(true), Action::CopyCard(p)) => {
+            if let Some(role) = get_role(state, p) {
+                state.table.copy_role(Player, p, role.role());
+
+                record(state,
+                       NightEvent::Copied {
+                           actor: Player,
+                           target: p,
+                           role: role.role(),
+                       });
+
+                events.push(GameEvent::Copied(p, role.role()));
+
+                //the cpu copies are already in the cards as dealt, see `deal_roles`
+                state.initial_table = state.table.clone();
+            }
+
+            state.turn = SeeRole(false);
+        }
+        (DoppelSeerTurn, Action::ViewPlayer(p)) => {
+            view_player(state, Player, p, doppel_reveal_one_turn, events);
+
+            state.turn = DoppelSeerRevealOne(p);
+        }
+        (SeerTurn, Action::ViewPlayer(p)) => {
+            view_player(state, Player, p, reveal_one_turn, events);
+
+            state.turn = SeerRevealOne(p);
+        }
+        (DoppelSeerTurn, Action::ViewCenter(pair)) => {
+            view_center(state, Player, pair, doppel_reveal_two_turn, events);
+
+            state.turn = DoppelSeerRevealTwo(pair);
+        }
+        (SeerTurn, Action::ViewCenter(pair)) => {
+            view_center(state, Player, pair, reveal_two_turn, events);
+
+            state.turn = SeerRevealTwo(pair);
+        }
+        (DoppelRobberTurn, Action::Rob(p)) => {
+            rob(state, Player, p, doppel_robber_action, events);
+
+            state.turn = DoppelRobberReveal;
+        }
+        (RobberTurn, Action::Rob(p)) => {
+            rob(state, Player, p, robber_action, events);
+
+            state.turn = RobberReveal;
+        }
+        (DoppelTroublemakerTurn, Action::PickFirst(p)) => {
+            state.turn = DoppelTroublemakerSecondChoice(p);
+        }
+        (TroublemakerTurn, Action::PickFirst(p)) => {
+            state.turn = TroublemakerSecondChoice(p);
+        }
+        (DoppelTroublemakerSecondChoice(first_choice), Action::PickSecond(p)) => {
+            exchange(state,
+                     Player,
+                     first_choice,
+                     p,
+                     doppel_troublemaker_action,
+                     events);
+
+            state.turn = next_turn(state);
+        }
+        (TroublemakerSecondChoice(first_choice), Action::PickSecond(p)) => {
+            exchange(state, Player, first_choice, p, troublemaker_action, events);
+
+            state.turn = next_turn(state);
+        }
+        (DoppelTroublemakerSecondChoice(_), Action::Back) => {
+            state.turn = DoppelTroublemakerTurn;
+        }
+        (TroublemakerSecondChoice(_), Action::Back) => {
+            state.turn = TroublemakerTurn;
+        }
+        (DoppelDrunkTurn, Action::TakeCenter(card)) => {
+            take_center(state, Player, card, doppel_drunk_action, events);
+
+            state.turn = next_turn(state);
+        }
+        (DrunkTurn, Action::TakeCenter(card)) => {
+            take_center(state, Player, card, drunk_action, events);
+
+            state.turn = next_turn(state);
+        }
+        (Vote, Action::VoteFor(p)) => {
+            collect_votes(state, p, events);
+
+            state.turn = next_turn(state);
+        }
+        (_, Action::Skip) => {
+            state.turn = next_turn(state);
+        }
+        (Discuss, Action::Proceed) => {
+            let said_anything = discussion_round(state, events);
+
+            if !said_anything {
+                state.turn = next_turn(state);
+            }
+        }
+        (_, Action::Proceed) => {
+            proceed(state, events);
+        }
+        _ => {
+            //`available_actions` has already ruled out everything else
+        }
+    }
+}
+
+fn proceed(state: &mut State, events: &mut Vec<GameEvent>) {
+    match state.turn {
+        Werewolves => {
+            werewolves_wake(state, events);
+        }
+        DoppelMinionTurn => {
+            minion_wakes(state, Player, events);
+        }
+        MinionTurn => {
+            minion_wakes(state, Player, events);
+        }
+        MasonTurn => {
+            masons_wake(state, events);
+        }
+        InsomniacTurn => {
+            look_at_own_card(state, Player, insomniac_action, events);
+        }
+        DoppelInsomniacTurn => {
+            look_at_own_card(state, Player, doppel_insomniac_action, events);
+        }
+        Resolution => {
+            //pick the next game's seed now so it can be shown before dealing.
+            state.seed = state.rng.gen::<usize>();
+        }
+        _ => {}
+    }
+
+    state.turn = next_turn(state);
+}
+
+fn take_cpu_turn(state: &mut State, events: &mut Vec<GameEvent>) {
+    match state.turn {
+        DoppelSeerTurn => {
+            cpu_seer_turn(state, is_doppel_seer, doppel_reveal_one_turn, doppel_reveal_two_turn);
+        }
+        SeerTurn => {
+            cpu_seer_turn(state, is_seer, reveal_one_turn, reveal_two_turn);
+        }
+        DoppelRobberTurn => {
+            cpu_robber_turn(state, get_doppel_robber_index, doppel_robber_action);
+        }
+        RobberTurn => {
+            cpu_robber_turn(state, get_robber_index, robber_action);
+        }
+        DoppelTroublemakerTurn => {
+            cpu_troublemaker_turn(state,
+                                  get_doppel_troublemaker_index,
+                                  doppel_troublemaker_action);
+        }
+        TroublemakerTurn => {
+            cpu_troublemaker_turn(state, get_troublemaker_index, troublemaker_action);
+        }
+        DoppelDrunkTurn => {
+            cpu_drunk_turn(state, get_doppel_drunk_index, doppel_drunk_action);
+        }
+        DrunkTurn => {
+            cpu_drunk_turn(state, get_drunk_index, drunk_action);
+        }
+        DoppelMinionTurn => {
+            if let Some(minion_index) = get_doppel_minion_index(state) {
+                minion_wakes(state, Cpu(minion_index), &mut Vec::new());
+            }
+        }
+        MinionTurn => {
+            if let Some(minion_index) = get_minion_index(state) {
+                minion_wakes(state, Cpu(minion_index), &mut Vec::new());
+            }
+        }
+        Werewolves => {
+            werewolves_wake(state, &mut Vec::new());
+        }
+        MasonTurn => {
+            masons_wake(state, &mut Vec::new());
+        }
+        InsomniacTurn => {
+            if let Some(i) = get_insomniac_index(state) {
+                look_at_own_card(state, Cpu(i), insomniac_action, &mut Vec::new());
+            }
+        }
+        DoppelInsomniacTurn => {
+            if let Some(i) = get_doppel_insomniac_index(state) {
+                look_at_own_card(state, Cpu(i), doppel_insomniac_action, &mut Vec::new());
+            }
+        }
+        BeginDiscussion => {
+            begin_discussion(state, events);
+        }
+        _ => {}
+    }
+
+    state.turn = next_turn(state);
+}
+
+fn deal_and_see_role(state: &mut State, events: &mut Vec<GameEvent>) {
+    let player_is_doppel = deal(state);
+
+    events.push(GameEvent::SawCard(Player, state.table.player()));
+
+    state.turn = SeeRole(player_is_doppel);
+}
+
+//returns whether the player was dealt the Doppelganger
+fn deal(state: &mut State) -> bool {
+    state.rng = GameRng::new(state.seed);
+    state.last_deal = Some((state.seed, state.role_spec));
+
+    let roles = shuffled_roles(&state.role_spec, &mut state.rng);
+
+    deal_roles(state, roles)
+}
+
+//Starts a game with `roles` dealt out the way `get_roles_and_knowledge` does,
+//rather than shuffled, for `search` to play out made up deals. A card that's
+//already a `Doppel` stays copied.
+pub fn deal_exactly(state: &mut State, roles: Vec<Card>) {
+    let player_is_doppel = deal_roles(state, roles);
+
+    state.turn = SeeRole(player_is_doppel);
+}
+
+fn deal_roles(state: &mut State, roles: Vec<Card>) -> bool {
+    let (table, player_knowledge, cpu_knowledge, player_is_doppel) =
+        get_roles_and_knowledge(roles);
+
+    state.table = table;
+    state.night_script = night_script(&state.role_spec.get_role_vector());
+    state.night_log.clear();
+    state.player_knowledge = player_knowledge;
+    state.cpu_knowledge = cpu_knowledge;
+
+    let cpu_count = state.cpu_knowledge.len();
+    state.cpu_profiles = (0..cpu_count)
+        .map(|i| match state.profile_picks.get(i) {
+            Some(&Some(profile)) => profile,
+            _ => state.rng.gen::<CpuProfile>(),
+        })
+        .collect();
+
+    cpu_doppel_copies(state);
+    state.initial_table = state.table.clone();
+    record_dealt_copy(state);
+
+    player_is_doppel
+}
+
+//A cpu Doppelganger copies someone as soon as the cards are dealt, so the
+//card it ends up with is on the table from the start of the night.
+fn cpu_doppel_copies(state: &mut State) {
+    let doppelganger = get_cpu_participants(state)
+        .into_iter()
+        .find(|&p| get_role(state, p) == Some(Plain(Doppelganger)));
+
+    if let Some(doppelganger) = doppelganger {
+        if let Some(target) = cpu::strategy(state, doppelganger).copy_target(state, doppelganger) {
+            if let Some(card) = get_role(state, target) {
+                state.table.copy_role(doppelganger, target, card.role());
+            }
+        }
+    }
+}
+
+fn werewolves_wake(state: &mut State, events: &mut Vec<GameEvent>) {
+    let werewolves = get_werewolves(state);
+
+    for &werewolf in werewolves.iter() {
+        record_seen(state, werewolf, &werewolves);
+    }
+
+    push_seen_cards(state, &werewolves, events);
+}
+
+fn minion_wakes(state: &mut State, minion: Participant, events: &mut Vec<GameEvent>) {
+    let werewolves = get_werewolves(state);
+
+    record_seen(state, minion, &werewolves);
+
+    if let Some(knowledge) = get_knowledge_mut(state, minion) {
+        knowledge.known_minion = Some(minion);
+        knowledge.true_claim = Simple(Minion);
+    }
+
+    push_seen_cards(state, &werewolves, events);
+}
+
+fn masons_wake(state: &mut State, events: &mut Vec<GameEvent>) {
+    let masons = get_masons(state);
+
+    for &mason in masons.iter() {
+        let other_masons: Vec<Participant> = masons.iter()
+            .filter(|&&p| p != mason)
+            .copied()
+            .collect();
+
+        let len = other_masons.len();
+
+        let claim = if let Some(Doppel(p, Mason)) = get_role(state, mason) {
+            DoppelMasonAction(p, other_masons_to_zero_to_two(&other_masons))
+        } else {
+            MasonAction(other_masons_to_zero_to_two(&other_masons))
+        };
+
+        if len == 0 {
+            record(state,
+                   NightEvent::AwakeAlone {
+                       actor: mason,
+                       role: Mason,
+                   });
+        } else {
+            record_seen(state, mason, &other_masons);
+        }
+
+        if let Some(knowledge) = get_knowledge_mut(state, mason) {
+            knowledge.true_claim = claim;
+        }
+    }
+
+    push_seen_cards(state, &masons, events);
+}
+
+fn record_seen(state: &mut State, actor: Participant, seen: &[Participant]) {
+    for &target in seen.iter().filter(|&&p| p != actor) {
+        if let Some(card) = get_role(state, target) {
+            record(state,
+                   NightEvent::SawAwake {
+                       actor,
+                       target,
+                       card,
+                   });
+        }
+    }
+}
+
+fn push_seen_cards(state: &State, participants: &[Participant], events: &mut Vec<GameEvent>) {
+    for &p in participants.iter() {
+        if let Some(role) = get_role(state, p) {
+            events.push(GameEvent::SawCard(p, role));
+        }
+    }
+}
+
+fn other_masons_to_zero_to_two(other_masons: &[Participant]) -> ZeroToTwo<Participant> {
+    let len = other_masons.len();
+
+    if len >= 2 {
+        Two(other_masons[0], other_masons[1])
+    } else if len == 1 {
+        One(other_masons[0])
+    } else {
+        Zero
+    }
+}
+
+fn cpu_seer_turn(state: &mut State,
+                 role_pred: fn(&Card) -> bool,
+                 reveal_one_action: fn(&State, Participant, Participant, Card) -> Claim,
+                 reveal_two_action: fn(&State, Participant, CenterPair, Card, Card) -> Claim) {
+    if let Some(seer_index) = linear_search_by(state.table.cpu_cards(), role_pred) {
+        let seer = Cpu(seer_index);
+
+        match cpu::strategy(state, seer).seer_choice(state, seer) {
+            Some(SeerView::Center(pair)) => {
+                view_center(state, seer, pair, reveal_two_action, &mut Vec::new());
+            }
+            Some(SeerView::Player(chosen)) => {
+                view_player(state, seer, chosen, reveal_one_action, &mut Vec::new());
+            }
+            None => {}
+        }
+    }
+}
+
+fn view_center(state: &mut State,
+               seer: Participant,
+               pair: CenterPair,
+               action: fn(&State, Participant, CenterPair, Card, Card) -> Claim,
+               events: &mut Vec<GameEvent>) {
+    let (role1, role2) = get_role_pair(state, pair);
+    let true_claim = action(state, seer, pair, role1, role2);
+
+    let (card1, card2) = get_card_pair(pair);
+
+    record(state,
+           NightEvent::ViewedCenter {
+               actor: seer,
+               center: card1,
+               card: role1,
+           });
+    record(state,
+           NightEvent::ViewedCenter {
+               actor: seer,
+               center: card2,
+               card: role2,
+           });
+
+    if let Some(knowledge) = get_knowledge_mut(state, seer) {
+        knowledge.true_claim = true_claim;
+    }
+
+    events.push(GameEvent::SawCenterCard(card1, role1));
+    events.push(GameEvent::SawCenterCard(card2, role2));
+}
+
+fn view_player(state: &mut State,
+               seer: Participant,
+               chosen: Participant,
+               action: fn(&State, Participant, Participant, Card) -> Claim,
+               events: &mut Vec<GameEvent>) {
+    if let Some(seen_role) = get_role(state, chosen) {
+        let true_claim = action(state, seer, chosen, seen_role);
+
+        record(state,
+               NightEvent::ViewedPlayer {
+                   actor: seer,
+                   target: chosen,
+                   card: seen_role,
+               });
+
+        if let Some(knowledge) = get_knowledge_mut(state, seer) {
+            knowledge.true_claim = true_claim;
+        }
+
+        events.push(GameEvent::SawCard(chosen, seen_role));
+    }
+}
+
+fn cpu_robber_turn(state: &mut State,
+                   get_cpu_index: fn(&State) -> Option<usize>,
+                   action: fn(&State, Participant, Participant, Card) -> Claim) {
+    if let Some(robber_index) = get_cpu_index(state) {
+        let robber = Cpu(robber_index);
+
+        if let Some(chosen) = cpu::strategy(state, robber).robber_target(state, robber) {
+            rob(state, robber, chosen, action, &mut Vec::new());
+        }
+    }
+}
+
+fn rob(state: &mut State,
+       robber: Participant,
+       chosen: Participant,
+       action: fn(&State, Participant, Participant, Card) -> Claim,
+       events: &mut Vec<GameEvent>) {
+    state.table.swap(robber, chosen);
+
+    if let Some(new_role) = get_role(state, robber) {
+        let true_claim = action(state, robber, chosen, new_role);
+
+        record(state,
+               NightEvent::Robbed {
+                   actor: robber,
+                   target: chosen,
+                   card: new_role,
+               });
+
+        if let Some(knowledge) = get_knowledge_mut(state, robber) {
+            knowledge.true_claim = true_claim;
+        }
+
+        events.push(GameEvent::SwappedCards(robber, chosen));
+        events.push(GameEvent::SawCard(robber, new_role));
+    }
+}
+
+fn cpu_troublemaker_turn(state: &mut State,
+                         get_cpu_index: fn(&State) -> Option<usize>,
+                         action: fn(&State, Participant, Participant, Participant) -> Claim) {
+    if let Some(troublemaker_index) = get_cpu_index(state) {
+        let troublemaker = Cpu(troublemaker_index);
+
+        if let Some((first_choice, second_choice)) =
+            cpu::strategy(state, troublemaker).troublemaker_targets(state, troublemaker) {
+            exchange(state,
+                     troublemaker,
+                     first_choice,
+                     second_choice,
+                     action,
+                     &mut Vec::new());
+        }
+    }
+}
+
+fn exchange(state: &mut State,
+            troublemaker: Participant,
+            first_choice: Participant,
+            second_choice: Participant,
+            action: fn(&State, Participant, Participant, Participant) -> Claim,
+            events: &mut Vec<GameEvent>) {
+    state.table.swap(first_choice, second_choice);
+
+    let true_claim = action(state, troublemaker, first_choice, second_choice);
+
+    record(state,
+           NightEvent::Swapped {
+               actor: troublemaker,
+               first: first_choice,
+               second: second_choice,
+           });
+
+    if let Some(knowledge) = get_knowledge_mut(state, troublemaker) {
+        knowledge.true_claim = true_claim;
+    }
+
+    events.push(GameEvent::SwappedCards(first_choice, second_choice));
+}
+
+fn cpu_drunk_turn(state: &mut State,
+                  get_cpu_index: fn(&State) -> Option<usize>,
+                  action: fn(&State, Participant, CenterCard) -> Claim) {
+    if let Some(drunk_index) = get_cpu_index(state) {
+        let drunk = Cpu(drunk_index);
+
+        let target = cpu::strategy(state, drunk).drunk_target(state, drunk);
+
+        take_center(state, drunk, target, action, &mut Vec::new());
+    }
+}
+
+fn take_center(state: &mut State,
+               drunk: Participant,
+               card: CenterCard,
+               action: fn(&State, Participant, CenterCard) -> Claim,
+               events: &mut Vec<GameEvent>) {
+    state.table.swap_with_center(drunk, card);
+
+    let true_claim = action(state, drunk, card);
+
+    record(state,
+           NightEvent::TookCenter {
+               actor: drunk,
+               center: card,
+           });
+
+    if let Some(knowledge) = get_knowledge_mut(state, drunk) {
+        knowledge.true_claim = true_claim;
+    }
+
+    events.push(GameEvent::TookCenterCard(card));
+}
+
+fn look_at_own_card(state: &mut State,
+                    insomniac: Participant,
+                    action: fn(&State, Participant, Card) -> Claim,
+                    events: &mut Vec<GameEvent>) {
+    if let Some(role) = get_role(state, insomniac) {
+        let true_claim = action(state, insomniac, role);
+
+        record(state,
+               NightEvent::Peeked {
+                   actor: insomniac,
+                   card: role,
+               });
+
+        if let Some(knowledge) = get_knowledge_mut(state, insomniac) {
+            knowledge.true_claim = true_claim;
+        }
+
+        events.push(GameEvent::SawCard(insomniac, role));
+    }
+}
+
+//how many rounds the discussion can go on for before it's time to vote
+const DISCUSSION_ROUNDS: u8 = 5;
+
+fn begin_discussion(state: &mut State, events: &mut Vec<GameEvent>) {
+    state.claims.clear();
+    state.transcript.clear();
+
+    let mut first_speakers = get_other_participants(state, Player);
+    let len = first_speakers.len();
+    if len > 0 {
+        let first_speaker_count = state.rng.gen_range(0, len);
+
+        state.rng.shuffle(&mut first_speakers);
+        for _ in 0..first_speaker_count {
+            if let Some(participant) = first_speakers.pop() {
+                make_claim(state, participant, events);
+            }
+        }
+    }
+
+    //TODO player can make claims to affect cpu players claims
+    //Force show all claims until player claims are implemented (if ever)
+    make_remaining_claims(state, events);
+}
+
+fn make_remaining_claims(state: &mut State, events: &mut Vec<GameEvent>) {
+    let mut cpu_participants = get_other_participants(state, Player);
+    state.rng.shuffle(&mut cpu_participants);
+    for &participant in cpu_participants.iter() {
+        if !state.claims.contains_key(&participant) {
+            make_claim(state, participant, events);
+        }
+    }
+}
+
+//Picks the discussion up from what's been said elsewhere, as though it had
+//been said here, so a playout in `search` carries on from the real game.
+//Anyone who was yet to make a claim gets to, and then it's `turn`.
+pub fn resume_discussion(state: &mut State, transcript: &[Statement], turn: Turn) {
+    state.claims.clear();
+    state.transcript = transcript.to_vec();
+
+    for statement in transcript.iter() {
+        if let Some(claim) = statement.claim() {
+            state.claims.insert(statement.speaker, claim);
+        }
+    }
+
+    hear_everything(state);
+
+    if turn == BeginDiscussion {
+        make_remaining_claims(state, &mut Vec::new());
+
+        state.turn = Discuss;
+    } else {
+        state.turn = turn;
+    }
+}
+
+//Each cpu player gets a turn to answer what's been said so far. Returns
+//whether anyone said anything, since once nobody does it's time to vote.
+fn discussion_round(state: &mut State, events: &mut Vec<GameEvent>) -> bool {
+    let round = current_round(state) + 1;
+
+    if round > DISCUSSION_ROUNDS {
+        return false;
+    }
+
+    let mut speakers = get_cpu_participants(state);
+    state.rng.shuffle(&mut speakers);
+
+    let mut said_anything = false;
+
+    for &speaker in speakers.iter() {
+        if let Some(kind) = cpu::strategy(state, speaker).respond(state, speaker) {
+            say(state,
+                Statement {
+                    round,
+                    speaker,
+                    kind,
+                },
+                events);
+
+            said_anything = true;
+        }
+    }
+
+    said_anything
+}
+
+fn current_round(state: &State) -> u8 {
+    state.transcript.last().map(|statement| statement.round).unwrap_or(0)
+}
+
+fn make_claim(state: &mut State, participant: Participant, events: &mut Vec<GameEvent>) {
+    if let Some(claim) = cpu::strategy(state, participant).claim(state, participant) {
+        say(state,
+            Statement {
+                round: 1,
+                speaker: participant,
+                kind: StatementKind::Opening(claim),
+            },
+            events);
+    }
+}
+
+fn say(state: &mut State, statement: Statement, events: &mut Vec<GameEvent>) {
+    state.transcript.push(statement);
+
+    match statement.claim() {
+        Some(claim) => insert_claim(state, statement.speaker, claim),
+        None => hear_everything(state),
+    }
+
+    events.push(GameEvent::Said(statement));
+}
+
+//every claim goes through here so the cpu players hear it
+pub fn insert_claim(state: &mut State, participant: Participant, claim: Claim) {
+    state.claims.insert(participant, claim);
+
+    hear_everything(state);
+}
+
+fn hear_everything(state: &mut State) {
+    for i in 0..state.cpu_knowledge.len() {
+        if let Some(dealt) = state.initial_table.view(Cpu(i)) {
+            cpu::hear_claims(Cpu(i),
+                             &mut state.cpu_knowledge[i],
+                             dealt,
+                             &state.night_log,
+                             &state.claims,
+                             &state.transcript,
+                             &state.role_spec);
+        }
+    }
+}
+
+fn collect_votes(state: &mut State, player_vote: Participant, events: &mut Vec<GameEvent>) {
+    state.votes.clear();
+    state.vote_reasons.clear();
+
+    state.votes.push((Player, player_vote));
+
+    for i in 0..state.cpu_knowledge.len() {
+        let voter = Cpu(i);
+
+        apply_swaps(&mut state.cpu_knowledge[i], &state.night_log, voter);
+
+        let insomniac_peek = state.night_log.iter().any(|event| match *event {
+            NightEvent::Peeked { actor, .. } => actor == voter,
+            _ => false,
+        });
+
+        if insomniac_peek {
+            if let Some(role) = get_role(state, voter) {
+                let knowledge = &mut state.cpu_knowledge[i];
+
+                knowledge.role = role;
+                if is_werewolf(knowledge.role) {
+                    knowledge.known_villagers.remove(&voter);
+                    knowledge.known_werewolves.insert(voter);
+                } else if knowledge.role == Plain(Minion) {
+                    knowledge.known_villagers.remove(&voter);
+                    knowledge.known_minion = Some(voter);
+                } else if knowledge.role == Plain(Tanner) {
+                    knowledge.known_villagers.remove(&voter);
+                    knowledge.known_tanner = Some(voter);
+                };
+
+            }
+        }
+
+        let (vote, reason) = cpu::strategy(state, voter).vote(state, voter);
+
+        state.votes.push((voter, vote));
+        state.vote_reasons.push((voter, reason));
+    }
+
+    for &(voter, vote) in state.votes.iter() {
+        events.push(GameEvent::Voted(voter, vote));
+    }
+}
+
+fn apply_swaps(knowledge: &mut Knowledge, night_log: &[NightEvent], participant: Participant) {
+    //TODO maybe make this return a new type, "FinalKnowledge"?
+
+    //TODO Do wwe need doppel swapping? There will only ever be one and it will
+    //happen before a seer happens.
+    let robber_swap = night_log.iter()
+        .filter_map(|event| match *event {
+            NightEvent::Robbed { actor, target, card } if actor == participant => {
+                Some((actor, target, card))
+            }
+            _ => None,
+        })
+        .next();
+
+    if let Some((robber, target, previous_role)) = robber_swap {
+
+        knowledge.role = previous_role;
+        if is_werewolf(previous_role) {
+            knowledge.known_villagers.remove(&robber);
+            knowledge.known_werewolves.insert(robber);
+        } else if knowledge.role == Plain(Minion) {
+            knowledge.known_villagers.remove(&robber);
+            knowledge.known_minion = Some(robber);
+        } else if knowledge.role == Plain(Tanner) {
+            knowledge.known_villagers.remove(&robber);
+            knowledge.known_tanner = Some(robber);
+        };
+
+        knowledge.known_werewolves.remove(&target);
+        knowledge.known_villagers.insert(target);
+    }
+
+    let troublemaker_swap = night_log.iter()
+        .filter_map(|event| match *event {
+            NightEvent::Swapped { actor, first, second } if actor == participant => {
+                Some((first, second))
+            }
+            _ => None,
+        })
+        .next();
+
+    if let Some((target1, target2)) = troublemaker_swap {
+        swap_team_if_known(knowledge, target1);
+        swap_team_if_known(knowledge, target2);
+    }
+
+    //TODO Do we need Drunk swapping (or any swapping?!) if the cpus never trusst anyone?
+}
+
+fn swap_team_if_known(knowledge: &mut Knowledge, participant: Participant) {
+    if knowledge.known_werewolves.contains(&participant) {
+        knowledge.known_villagers.insert(participant);
+        knowledge.known_werewolves.remove(&participant);
+    } else if knowledge.known_villagers.contains(&participant) {
+        knowledge.known_werewolves.insert(participant);
+        knowledge.known_villagers.remove(&participant);
+    }
+}
+
+fn is_player_insomniac(state: &State) -> bool {
+    state.initial_table.player() == Plain(Insomniac)
+}
+
+fn is_player_doppel_insomniac(state: &State) -> bool {
+    matches!(state.table.player(), Doppel(_, Insomniac))
+}
+
+fn get_insomniac_index(state: &State) -> Option<usize> {
+    linear_search(state.initial_table.cpu_cards(), &Plain(Insomniac))
+}
+fn get_doppel_insomniac_index(state: &State) -> Option<usize> {
+    linear_search_by(state.table.cpu_cards(), |r| matches!(*r, Doppel(_, Insomniac)))
+}
+
+fn insomniac_action(_: &State, _: Participant, r: Card) -> Claim {
+    InsomniacAction(r.face())
+}
+fn doppel_insomniac_action(state: &State, participant: Participant, r: Card) -> Claim {
+    let participant = insomniac_doppel_target_or_player(get_role(state, participant)
+                                                            .unwrap_or(Plain(Villager)));
+    DoppelInsomniacAction(participant, r.face())
+}
+
+fn insomniac_doppel_target_or_player(role: Card) -> Participant {
+    match role {
+        Doppel(p, Insomniac) => p,
+        _ => Player,
+    }
+}
+
+fn is_player_drunk(state: &State) -> bool {
+    state.initial_table.player() == Plain(Drunk)
+}
+
+fn is_player_doppel_drunk(state: &State) -> bool {
+    matches!(state.table.player(), Doppel(_, Drunk))
+}
+
+fn get_drunk_index(state: &State) -> Option<usize> {
+    linear_search(state.initial_table.cpu_cards(), &Plain(Drunk))
+}
+fn get_doppel_drunk_index(state: &State) -> Option<usize> {
+    linear_search_by(state.table.cpu_cards(), |r| matches!(*r, Doppel(_, Drunk)))
+}
+
+fn drunk_action(_: &State, _: Participant, c: CenterCard) -> Claim {
+    DrunkAction(c)
+}
+fn doppel_drunk_action(state: &State, participant: Participant, c: CenterCard) -> Claim {
+    let participant = drunk_doppel_target_or_player(get_role(state, participant)
+                                                        .unwrap_or(Plain(Villager)));
+    DoppelDrunkAction(participant, c)
+}
+
+fn drunk_doppel_target_or_player(role: Card) -> Participant {
+    match role {
+        Doppel(p, Drunk) => p,
+        _ => Player,
+    }
+}
+
+fn is_player_troublemaker(state: &State) -> bool {
+    state.initial_table.player() == Plain(Troublemaker)
+}
+
+fn is_player_doppel_troublemaker(state: &State) -> bool {
+    matches!(state.table.player(), Doppel(_, Troublemaker))
+}
+
+fn get_troublemaker_index(state: &State) -> Option<usize> {
+    linear_search(state.initial_table.cpu_cards(), &Plain(Troublemaker))
+}
+fn get_doppel_troublemaker_index(state: &State) -> Option<usize> {
+    linear_search_by(state.table.cpu_cards(), |r| matches!(*r, Doppel(_, Troublemaker)))
+}
+
+fn troublemaker_action(_: &State, _: Participant, p1: Participant, p2: Participant) -> Claim {
+    TroublemakerAction(p1, p2)
+}
+fn doppel_troublemaker_action(state: &State,
+                              participant: Participant,
+                              p1: Participant,
+                              p2: Participant)
+                              -> Claim {
+    let participant = troublemaker_doppel_target_or_player(get_role(state, participant)
+                                                               .unwrap_or(Plain(Villager)));
+    DoppelTroublemakerAction(participant, p1, p2)
+}
+
+fn troublemaker_doppel_target_or_player(role: Card) -> Participant {
+    match role {
+        Doppel(p, Troublemaker) => p,
+        _ => Player,
+    }
+}
+
+fn is_player_minion(state: &State) -> bool {
+    state.initial_table.player() == Plain(Minion)
+}
+
+fn is_player_doppel_minion(state: &State) -> bool {
+    matches!(state.table.player(), Doppel(_, Minion))
+}
+
+fn get_minion_index(state: &State) -> Option<usize> {
+    linear_search(state.initial_table.cpu_cards(), &Plain(Minion))
+}
+fn get_doppel_minion_index(state: &State) -> Option<usize> {
+    linear_search_by(state.table.cpu_cards(), |r| matches!(*r, Doppel(_, Minion)))
+}
+
+fn robber_action(_: &State, _: Participant, p: Participant, r: Card) -> Claim {
+    RobberAction(p, r.face())
+}
+fn doppel_robber_action(state: &State, participant: Participant, p: Participant, r: Card) -> Claim {
+    let participant = robber_doppel_target_or_player(get_role(state, participant)
+                                                         .unwrap_or(Plain(Villager)));
+    DoppelRobberAction(participant, p, r.face())
+}
+
+fn robber_doppel_target_or_player(role: Card) -> Participant {
+    match role {
+        Doppel(p, Robber) => p,
+        _ => Player,
+    }
+}
+
+fn is_player_robber(state: &State) -> bool {
+    state.initial_table.player() == Plain(Robber)
+}
+fn is_player_doppel_robber(state: &State) -> bool {
+    matches!(state.table.player(), Doppel(_, Robber))
+}
+fn get_robber_index(state: &State) -> Option<usize> {
+    linear_search(state.initial_table.cpu_cards(), &Plain(Robber))
+}
+fn get_doppel_robber_index(state: &State) -> Option<usize> {
+    linear_search_by(state.table.cpu_cards(), |r| matches!(*r, Doppel(_, Robber)))
+}
+
+fn reveal_one_turn(_: &State, _: Participant, p: Participant, r: Card) -> Claim {
+    SeerRevealOneAction(p, r.face())
+}
+fn reveal_two_turn(_: &State, _: Participant, pair: CenterPair, r1: Card, r2: Card) -> Claim {
+    SeerRevealTwoAction(pair, r1.face(), r2.face())
+}
+
+fn doppel_reveal_one_turn(state: &State,
+                          participant: Participant,
+                          p: Participant,
+                          r: Card)
+                          -> Claim {
+    let participant = seer_doppel_target_or_player(get_role(state, participant)
+                                                       .unwrap_or(Plain(Villager)));
+
+    DoppelSeerRevealOneAction(participant, p, r.face())
+}
+fn doppel_reveal_two_turn(state: &State,
+                          participant: Participant,
+                          pair: CenterPair,
+                          r1: Card,
+                          r2: Card)
+                          -> Claim {
+    let participant = seer_doppel_target_or_player(get_role(state, participant)
+                                                       .unwrap_or(Plain(Villager)));
+    DoppelSeerRevealTwoAction(participant, pair, r1.face(), r2.face())
+}
+
+
+fn seer_doppel_target_or_player(role: Card) -> Participant {
+    match role {
+        Doppel(p, Seer) => p,
+        _ => Player,
+    }
+}
+
+fn is_seer(role: &Card) -> bool {
+    role == &Plain(Seer)
+}
+
+fn is_doppel_seer(role: &Card) -> bool {
+    matches!(*role, Doppel(_, Seer))
+}
+
+pub fn get_participant_with_role(state: &State, role: Card) -> Option<Participant> {
+    if state.table.player() == role {
+        Some(Player)
+    } else {
+        linear_search(state.table.cpu_cards(), &role).map(Cpu)
+    }
+}
+
+pub fn get_participant_by_role<F>(state: &State, mut f: F) -> Option<Participant>
+    where F: FnMut(&Card) -> bool
+{
+    if f(&state.table.player()) {
+        Some(Player)
+    } else {
+        linear_search_by(state.table.cpu_cards(), f).map(Cpu)
+    }
+}
+
+fn linear_search<T: PartialEq>(vector: &[T], thing: &T) -> Option<usize> {
+    vector.iter().position(|t| t == thing)
+}
+
+fn linear_search_by<'a, F, T>(vector: &'a [T], f: F) -> Option<usize>
+    where F: FnMut(&'a T) -> bool
+{
+    vector.iter().position(f)
+}
+
+pub fn get_role_pair(state: &State, pair: CenterPair) -> (Card, Card) {
+    let rs = state.table.center();
+    match pair {
+        FirstSecond => (rs[0], rs[1]),
+        FirstThird => (rs[0], rs[2]),
+        SecondThird => (rs[1], rs[2]),
+    }
+}
+
+fn get_card_pair(pair: CenterPair) -> (CenterCard, CenterCard) {
+    match pair {
+        FirstSecond => (First, Second),
+        FirstThird => (First, Third),
+        SecondThird => (Second, Third),
+    }
+}
+
+pub fn get_knowledge(state: &State, participant: Participant) -> Option<&Knowledge> {
+    match participant {
+        Player => Some(&state.player_knowledge),
+        Cpu(index) => state.cpu_knowledge.get(index),
+    }
+}
+fn get_knowledge_mut(state: &mut State, participant: Participant) -> Option<&mut Knowledge> {
+    match participant {
+        Player => Some(&mut state.player_knowledge),
+        Cpu(index) => state.cpu_knowledge.get_mut(index),
+    }
+}
+//the player doesn't have one, and neither do cpu players in games saved
+//before there were profiles
+pub fn get_profile(state: &State, participant: Participant) -> CpuProfile {
+    match participant {
+        Player => CpuProfile::default(),
+        Cpu(index) => state.cpu_profiles.get(index).cloned().unwrap_or_default(),
+    }
+}
+
+pub fn get_knowledge_copy(state: &State, participant: Participant) -> Option<Knowledge> {
+    get_knowledge(state, participant).cloned()
+}
+
+pub fn is_werewolf(role: Card) -> bool {
+    role.role() == Werewolf
+}
+pub fn is_mason(role: Card) -> bool {
+    role.role() == Mason
+}
+pub fn is_minion(role: Card) -> bool {
+    role.role() == Minion
+}
+pub fn is_tanner(role: Card) -> bool {
+    role.role() == Tanner
+}
+pub fn is_on_village_team(role: Card) -> bool {
+    role.def().team == Team::Village
+}
+
+
+pub fn get_other_participants(state: &State, participant: Participant) -> Vec<Participant> {
+    get_participants(state)
+        .iter()
+        .copied()
+        .filter(|&p| p != participant)
+        .collect()
+}
+
+pub fn get_role(state: &State, participant: Participant) -> Option<Card> {
+    state.table.view(participant)
+}
+
+pub fn get_participants(state: &State) -> Vec<Participant> {
+    let mut result = vec![Player];
+
+    for i in 0..state.table.cpu_cards().len() {
+        result.push(Cpu(i));
+    }
+
+    result
+}
+
+pub fn get_cpu_participants(state: &State) -> Vec<Participant> {
+    let mut result = Vec::new();
+
+    for i in 0..state.table.cpu_cards().len() {
+        result.push(Cpu(i));
+    }
+
+    result
+}
+
+pub fn get_werewolves(state: &State) -> Vec<Participant> {
+    let mut result = Vec::new();
+
+    if is_werewolf(state.table.player()) {
+        result.push(Player);
+    }
+
+    for i in 0..state.table.cpu_cards().len() {
+        if is_werewolf(state.table.cpu_cards()[i]) {
+            result.push(Cpu(i));
+        }
+    }
+
+    result
+}
+
+pub fn get_masons(state: &State) -> Vec<Participant> {
+    let mut result = Vec::new();
+
+    if is_mason(state.table.player()) {
+        result.push(Player);
+    }
+
+    for i in 0..state.table.cpu_cards().len() {
+        if is_mason(state.table.cpu_cards()[i]) {
+            result.push(Cpu(i));
+        }
+    }
+
+    result
+}
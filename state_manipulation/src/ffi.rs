@@ -0,0 +1,189 @@
+//The entry points `src/main.rs` calls. In debug builds they are looked up by
+//name after every reload, so the library on the other side may have been
+//built from newer code than `src/main.rs` was. Everything crosses over as
+//plain numbers, pointers to bytes, or the `Platform`, whose layout is pinned,
+//see its definition. The `State` only ever crosses over as an opaque pointer,
+//and when the library is reloaded the old one saves it to json and the new
+//one loads (and if needed migrates) it, see `save::from_str`. The frame's
+//`Event`s cross over as json too, so they can change shape without a restart.
+
+use common::*;
+
+use serde_json;
+
+use std::mem;
+use std::ptr;
+use std::rc::Rc;
+use std::slice;
+
+use bot::BotStrategy;
+use save;
+
+//Only this library ever looks inside one of these.
+pub type StateHandle = *mut State;
+
+#[no_mangle]
+pub extern "C" fn new_state_handle(width: i32,
+                                   height: i32,
+                                   has_seed: bool,
+                                   seed: u64)
+                                   -> StateHandle {
+    let seed = if has_seed { Some(seed as usize) } else { None };
+
+    Box::into_raw(Box::new(::new_state(Size::new(width, height), seed)))
+}
+
+/// `events` is a json array of `Event`s, `len` bytes long.
+/// returns true if quit requested
+///
+/// # Safety
+///
+/// `platform` and `state` must be null or valid, `state` must have come from
+/// this library, and `events` must be null or point to `len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn update_and_render_handle(platform: *const Platform,
+                                                  state: StateHandle,
+                                                  events: *const u8,
+                                                  len: usize)
+                                                  -> bool {
+    if platform.is_null() || state.is_null() {
+        return true;
+    }
+
+    let (platform, state) = (&*platform, &mut *state);
+
+    let mut events: Vec<Event> = if events.is_null() {
+        Vec::new()
+    } else {
+        match serde_json::from_slice(slice::from_raw_parts(events, len)) {
+            Ok(events) => events,
+            Err(e) => {
+                println!("couldn't read this frame's events: {}", e);
+
+                Vec::new()
+            }
+        }
+    };
+
+    ::update_and_render(platform, state, &mut events)
+}
+
+/// # Safety
+///
+/// `state` must be null or have come from this library, and isn't valid
+/// afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn drop_state_handle(state: StateHandle) {
+    if !state.is_null() {
+        drop(Box::from_raw(state));
+    }
+}
+
+/// Returns the state as json in a buffer owned by this library, which must be
+/// handed back to `free_saved_state` (before this library is unloaded!).
+/// Returns null if the state couldn't be saved.
+///
+/// # Safety
+///
+/// `state` must be null or have come from this library, and `len` must be
+/// null or writable.
+#[no_mangle]
+pub unsafe extern "C" fn save_state_handle(state: StateHandle, len: *mut usize) -> *mut u8 {
+    if state.is_null() || len.is_null() {
+        return ptr::null_mut();
+    }
+
+    match save::to_string(&*state) {
+        Ok(s) => {
+            let mut bytes = s.into_bytes().into_boxed_slice();
+
+            *len = bytes.len();
+
+            let result = bytes.as_mut_ptr();
+            mem::forget(bytes);
+
+            result
+        }
+        Err(e) => {
+            println!("couldn't save the state for reloading: {}", e);
+
+            ptr::null_mut()
+        }
+    }
+}
+
+/// # Safety
+///
+/// `buffer` and `len` must be null or what `save_state_handle` returned, and
+/// the buffer isn't valid afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn free_saved_state(buffer: *mut u8, len: usize) {
+    if !buffer.is_null() {
+        drop(Box::from_raw(ptr::slice_from_raw_parts_mut(buffer, len)));
+    }
+}
+
+/// Returns null if the state couldn't be loaded, say if it came from a newer
+/// version of the game.
+///
+/// # Safety
+///
+/// `buffer` must be null or point to `len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn load_state_handle(buffer: *const u8, len: usize) -> StateHandle {
+    if buffer.is_null() {
+        return ptr::null_mut();
+    }
+
+    let bytes = slice::from_raw_parts(buffer, len);
+
+    let loaded = String::from_utf8(bytes.to_vec())
+        .map_err(|e| format!("{}", e))
+        .and_then(|s| save::from_str(&s).map_err(|e| format!("{}", e)));
+
+    match loaded {
+        Ok(state) => Box::into_raw(Box::new(state)),
+        Err(e) => {
+            println!("couldn't load the state after reloading: {}", e);
+
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Starts `command` (utf-8, `len` bytes long) and puts it in cpu seat `seat`,
+/// see `bot`. Bots aren't saved, so this has to be done again after a reload.
+/// Returns false if there's no such seat or the bot couldn't be started.
+///
+/// # Safety
+///
+/// `state` must be null or have come from this library, and `command` must
+/// be null or point to `len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn plug_in_bot_handle(state: StateHandle,
+                                            seat: usize,
+                                            command: *const u8,
+                                            len: usize)
+                                            -> bool {
+    if state.is_null() || command.is_null() || seat >= (*state).table.cpu_cards().len() {
+        return false;
+    }
+
+    let command = match String::from_utf8(slice::from_raw_parts(command, len).to_vec()) {
+        Ok(command) => command,
+        Err(_) => return false,
+    };
+
+    match BotStrategy::spawn(&command) {
+        Ok(bot) => {
+            (*state).cpu_strategies.insert(seat, Rc::new(bot));
+
+            true
+        }
+        Err(e) => {
+            println!("couldn't start bot \"{}\": {}", command, e);
+
+            false
+        }
+    }
+}
@@ -1,5 +1,9 @@
 extern crate rand;
 extern crate common;
+extern crate serde;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde_json;
 
 use common::*;
 use common::Role::*;
@@ -7,122 +11,58 @@ use common::Turn::*;
 use common::Participant::*;
 use common::Claim::*;
 use common::CenterPair::*;
-use common::CenterCard::*;
 use common::ZeroToTwo::*;
 
-use rand::{StdRng, SeedableRng, Rng};
-use std::collections::HashMap;
+use rand::Rng;
+
+pub mod engine;
+pub mod outcome;
+pub mod save;
+pub mod ffi;
+pub mod deduction;
+pub mod cpu;
+pub mod bot;
+pub mod search;
+
+use outcome::{GameOutcome, Winner};
+use engine::{Action, get_role, get_role_pair, get_knowledge, get_werewolves, get_masons,
+             get_cpu_participants};
 
 //NOTE(Ryan1729): debug_assertions only appears to work correctly when the
 //crate is not a dylib. Assuming you make this crate *not* a dylib on release,
 //these configs should work
 #[cfg(debug_assertions)]
-#[no_mangle]
-pub fn new_state(size: Size) -> State {
+pub fn new_state(_: Size, seed: Option<usize>) -> State {
     //skip the title screen
     println!("debug on");
 
-    let seed: &[_] = &[42];
-    let rng: StdRng = SeedableRng::from_seed(seed);
+    let seed = seed.unwrap_or(42);
 
-    make_state(size, false, rng)
-}
-#[cfg(not(debug_assertions))]
-#[no_mangle]
-pub fn new_state(size: Size) -> State {
-    //show the title screen
-    let timestamp = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .map(|dur| dur.as_secs())
-        .unwrap_or(42);
+    println!("seed: {}", seed);
+    let mut state = engine::make_state(false, seed);
 
-    println!("{}", timestamp);
-    let seed: &[_] = &[timestamp as usize];
-    let rng: StdRng = SeedableRng::from_seed(seed);
+    state.can_continue = save::exists(save::SAVE_PATH);
 
-    make_state(size, false, rng)
+    state
 }
+#[cfg(not(debug_assertions))]
+pub fn new_state(_: Size, seed: Option<usize>) -> State {
+    //show the title screen
+    let seed = seed.unwrap_or_else(|| {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|dur| dur.as_secs())
+            .unwrap_or(42) as usize
+    });
 
+    println!("seed: {}", seed);
+    let mut state = engine::make_state(false, seed);
 
-fn make_state(_: Size, title_screen: bool, mut rng: StdRng) -> State {
-    let role_spec = rng.gen::<RoleSpec>();
-
-    let (player, cpu_roles, table_roles, player_knowledge, cpu_knowledge, _) =
-        get_roles_and_knowledge(&role_spec, &mut rng);
-
-    let initial_cpu_roles = cpu_roles.to_owned();
-
-    State {
-        rng: rng,
-        title_screen: title_screen,
-        player,
-        initial_player: player,
-        cpu_roles,
-        initial_cpu_roles,
-        table_roles,
-        turn: Ready,
-        player_knowledge,
-        cpu_knowledge,
-        votes: Vec::new(),
-        claims: HashMap::new(),
-        ui_context: UIContext::new(),
-        role_spec,
-        show_role_spec: false,
-    }
-}
-
-fn get_roles_and_knowledge(role_spec: &RoleSpec,
-                           rng: &mut StdRng)
-                           -> (Role, Vec<Role>, [Role; 3], Knowledge, Vec<Knowledge>, bool) {
-    let mut roles = role_spec.get_role_vector();
-
-    rng.shuffle(&mut roles);
-
-    let player = roles.pop().unwrap();
-
-    let table_roles = [roles.pop().unwrap(), roles.pop().unwrap(), roles.pop().unwrap()];
-
-    let mut cpu_roles = roles;
-
-    if let Some(doppel_index) = linear_search(&cpu_roles, &DoppelVillager(Player)) {
-        let mut other_roles: Vec<Role> = cpu_roles.iter()
-            .map(|&r| r)
-            .filter(|&r| r != DoppelVillager(Player))
-            .collect();
-        other_roles.push(player);
-
-        let len = other_roles.len();
-        let random_index = rng.gen_range(0, len);
-
-        let participant = if random_index == len {
-            Player
-        } else if random_index >= doppel_index {
-            // handle the fact that that doppel_index has been removed from other_roles
-            Cpu(random_index + 1)
-        } else {
-            Cpu(random_index)
-        };
-
-        cpu_roles[doppel_index] = get_doppel_role(other_roles[random_index], participant);
-    }
-
-    let player_knowledge = Knowledge::new(player, Player);
-
-    let mut cpu_knowledge = Vec::new();
-
-    for i in 0..cpu_roles.len() {
-        cpu_knowledge.push(Knowledge::new(cpu_roles[i], Cpu(i)));
-    }
+    state.can_continue = save::exists(save::SAVE_PATH);
 
-    (player,
-     cpu_roles,
-     table_roles,
-     player_knowledge,
-     cpu_knowledge,
-     player == DoppelVillager(Player))
+    state
 }
 
-#[no_mangle]
 //returns true if quit requested
 pub fn update_and_render(platform: &Platform, state: &mut State, events: &mut Vec<Event>) -> bool {
     if state.title_screen {
@@ -162,8 +102,20 @@ pub fn game_update_and_render(platform: &Platform,
             Event::KeyReleased { key: KeyCode::MouseLeft, ctrl: _, shift: _ } => {
                 left_mouse_released = true;
             }
+            Event::KeyPressed { key, ctrl: _, shift: _ } if state.seed_entry.is_some() &&
+                                                           key != KeyCode::Escape => {
+                seed_entry_key(state, key);
+            }
             Event::Close |
-            Event::KeyPressed { key: KeyCode::Escape, ctrl: _, shift: _ } => return true,
+            Event::KeyPressed { key: KeyCode::Escape, ctrl: _, shift: _ } => {
+                //if saving failed the player gets to see why, and leaving
+                //again quits without it
+                let warned = state.save_error.is_some();
+
+                if save_if_playing(state) || warned {
+                    return true;
+                }
+            }
             _ => (),
         }
     }
@@ -186,7 +138,7 @@ pub fn game_update_and_render(platform: &Platform,
                      &next_spec,
                      left_mouse_pressed,
                      left_mouse_released) {
-            state.turn = state.turn.next();
+            state.turn = engine::next_turn(state);
         }
     };
 
@@ -215,23 +167,39 @@ pub fn game_update_and_render(platform: &Platform,
 
     if state.show_role_spec {
         display_role_spec(platform, 10, 10, &state.role_spec);
+
+        display_night_script(platform, 10, 17, &state.night_script);
     } else {
         let t = state.turn;
-        advance_turn_if_needed(state, platform, left_mouse_pressed, left_mouse_released);
+
+        //the debug "Next" button can land us on a turn the player takes no part in.
+        engine::advance(state);
+
+        render_turn(state, platform, left_mouse_pressed, left_mouse_released);
 
         if t != state.turn {
             println!("{:?}", state.turn);
         }
     }
 
+    if let Some(ref error) = state.save_error {
+        (platform.print_xy)(0, size.height - 5, error);
+    }
+
     false
 }
 
+fn act(state: &mut State, action: Action) {
+    if let Err(error) = engine::apply(state, action) {
+        println!("{:?}", error);
+    }
+}
 
-fn advance_turn_if_needed(state: &mut State,
-                          platform: &Platform,
-                          left_mouse_pressed: bool,
-                          left_mouse_released: bool) {
+//Draws the current turn and turns any button presses into `Action`s.
+fn render_turn(state: &mut State,
+               platform: &Platform,
+               left_mouse_pressed: bool,
+               left_mouse_released: bool) {
     match state.turn {
         Ready => {
             let reroll_spec = ButtonSpec {
@@ -269,7 +237,7 @@ fn advance_turn_if_needed(state: &mut State,
             }
 
             let columns = vec![vec![Werewolf, Minion, Robber, Mason, Seer, Troublemaker, Drunk],
-                               vec![Insomniac, Villager, Tanner, Hunter, DoppelVillager(Player)]];
+                               vec![Insomniac, Villager, Tanner, Hunter, Doppelganger]];
 
             let mut max_column_length = 0;
 
@@ -310,30 +278,16 @@ fn advance_turn_if_needed(state: &mut State,
                     };
                 }
             }
+            seed_controls(platform, state, left_mouse_pressed, left_mouse_released);
+            profile_controls(platform, state, left_mouse_pressed, left_mouse_released);
+
             (platform.print_xy)(10, 5, "Ready to start a game?");
 
             display_role_spec(platform, 10, 7, &state.role_spec);
 
             if ready_button(platform, state, left_mouse_pressed, left_mouse_released) {
-                let (player,
-                     cpu_roles,
-                     table_roles,
-                     player_knowledge,
-                     cpu_knowledge,
-                     player_is_doppel) = get_roles_and_knowledge(&state.role_spec, &mut state.rng);
-
-                state.player = player;
-                state.initial_player = player;
-                state.initial_cpu_roles = cpu_roles.to_owned();
-                state.cpu_roles = cpu_roles;
-                state.table_roles = table_roles;
-                state.player_knowledge = player_knowledge;
-                state.cpu_knowledge = cpu_knowledge;
-
-                state.turn = SeeRole(player_is_doppel);
-            } else {
-                state.turn = Ready;
-            };
+                act(state, Action::Deal);
+            }
         }
         SeeRole(player_is_doppel) => {
             if player_is_doppel {
@@ -343,22 +297,16 @@ fn advance_turn_if_needed(state: &mut State,
                 let choice =
                     pick_cpu_player(platform, state, left_mouse_pressed, left_mouse_released);
 
-                match choice {
-                    Some(p) => {
-                        if let Some(role) = get_role(state, p) {
-                            state.player = get_doppel_role(role, p);
-                            state.turn = SeeRole(false);
-                        }
-                    }
-                    None => {}
+                if let Some(p) = choice {
+                    act(state, Action::CopyCard(p));
                 }
             } else {
                 (platform.print_xy)(10,
                                     12,
-                                    &format!("You are {}.", full_role_string(state.player)));
+                                    &format!("You are {}.", full_role_string(state.table.player())));
 
                 if ready_button(platform, state, left_mouse_pressed, left_mouse_released) {
-                    state.turn = state.turn.next();
+                    act(state, Action::Proceed);
                 };
             }
         }
@@ -367,11 +315,6 @@ fn advance_turn_if_needed(state: &mut State,
                       platform,
                       left_mouse_pressed,
                       left_mouse_released,
-                      DoppelSeerRevealOne,
-                      DoppelSeerRevealTwo,
-                      doppel_reveal_one_turn,
-                      doppel_reveal_two_turn,
-                      is_doppel_seer,
                       "DoppelSeer");
         }
         DoppelSeerRevealOne(participant) => {
@@ -393,10 +336,6 @@ fn advance_turn_if_needed(state: &mut State,
                         platform,
                         left_mouse_pressed,
                         left_mouse_released,
-                        DoppelRobberReveal,
-                        doppel_robber_action,
-                        is_player_doppel_robber,
-                        get_doppel_robber_index,
                         "DoppelRobber");
         }
         DoppelRobberReveal => {
@@ -407,10 +346,6 @@ fn advance_turn_if_needed(state: &mut State,
                               platform,
                               left_mouse_pressed,
                               left_mouse_released,
-                              is_player_doppel_troublemaker,
-                              get_doppel_troublemaker_index,
-                              DoppelTroublemakerSecondChoice,
-                              doppel_troublemaker_action,
                               "DoppelTroublemaker");
         }
         DoppelTroublemakerSecondChoice(first_choice) => {
@@ -418,8 +353,6 @@ fn advance_turn_if_needed(state: &mut State,
                                        platform,
                                        left_mouse_pressed,
                                        left_mouse_released,
-                                       doppel_troublemaker_action,
-                                       DoppelTroublemakerTurn,
                                        first_choice);
         }
         DoppelDrunkTurn => {
@@ -427,9 +360,6 @@ fn advance_turn_if_needed(state: &mut State,
                        platform,
                        left_mouse_pressed,
                        left_mouse_released,
-                       is_player_doppel_drunk,
-                       get_doppel_drunk_index,
-                       doppel_drunk_action,
                        "DoppelDrunk");
         }
         DoppelMinionTurn => {
@@ -437,111 +367,40 @@ fn advance_turn_if_needed(state: &mut State,
                         platform,
                         left_mouse_pressed,
                         left_mouse_released,
-                        is_player_doppel_minion,
-                        get_doppel_minion_index,
                         "DoppelMinion");
         }
         Werewolves => {
-            let werewolves = get_werewolves(state);
-
-            let ready = if is_werewolf(state.player) {
-                (platform.print_xy)(10, 10, "Werewolves, wake up and look for other werewolves.");
+            (platform.print_xy)(10, 10, "Werewolves, wake up and look for other werewolves.");
 
-                list_werewolves(platform, &werewolves);
+            list_werewolves(platform, &get_werewolves(state));
 
-                ready_button(platform, state, left_mouse_pressed, left_mouse_released)
-            } else {
-                true
-            };
-
-            if ready {
-                for &werewolf in werewolves.iter() {
-                    match werewolf {
-                        Player => {
-                            state.player_knowledge.known_werewolves.extend(werewolves.iter());
-                        }
-                        Cpu(index) => {
-                            let ref mut knowledge = state.cpu_knowledge[index];
-
-                            knowledge.known_werewolves.extend(werewolves.iter());
-                        }
-                    }
-                }
-
-                state.turn = state.turn.next();
+            if ready_button(platform, state, left_mouse_pressed, left_mouse_released) {
+                act(state, Action::Proceed);
             }
-
         }
         MinionTurn => {
             minion_turn(state,
                         platform,
                         left_mouse_pressed,
                         left_mouse_released,
-                        is_player_minion,
-                        get_minion_index,
                         "Minion");
         }
         MasonTurn => {
             let masons = get_masons(state);
 
-            let ready = if is_mason(state.player) {
-                (platform.print_xy)(10, 10, "Masons, wake up and look for other Masons.");
-
-                for i in 0..masons.len() {
-                    let index = i as i32;
-
-                    match masons[i] {
-                        Player => (platform.print_xy)(10, 12 + index, "You are a mason. (duh!)"),
-                        cpu => (platform.print_xy)(10, 12 + index, &format!("{} is a mason.", cpu)),
-                    }
-                }
-
-                ready_button(platform, state, left_mouse_pressed, left_mouse_released)
-            } else {
-                true
-            };
-
-            if ready {
-                for &mason in masons.iter() {
-                    let other_masons: Vec<Participant> = masons.iter()
-                        .filter(|&&p| p != mason)
-                        .map(|&p| p)
-                        .collect();
-
-                    let len = other_masons.len();
-
-                    let claim = if let Some(DoppelMason(p)) = get_role(state, mason) {
-                        DoppelMasonAction(p, other_masons_to_zero_to_two(&other_masons))
-                    } else {
-                        MasonAction(other_masons_to_zero_to_two(&other_masons))
-                    };
-
-                    match mason {
-                        Player => {
-                            if len == 0 {
-                                state.player_knowledge.known_non_active.insert(Mason);
-                            } else {
-                                state.player_knowledge.known_villagers.extend(masons.iter());
-                            }
-
-                            state.player_knowledge.true_claim = claim;
-                        }
-                        Cpu(index) => {
-                            let ref mut knowledge = state.cpu_knowledge[index];
-
-                            if len == 0 {
-                                knowledge.known_non_active.insert(Mason);
-                            } else {
-                                knowledge.known_villagers.extend(masons.iter());
-                            }
+            (platform.print_xy)(10, 10, "Masons, wake up and look for other Masons.");
 
+            for i in 0..masons.len() {
+                let index = i as i32;
 
-                            knowledge.true_claim = claim;
-                        }
-                    }
+                match masons[i] {
+                    Player => (platform.print_xy)(10, 12 + index, "You are a mason. (duh!)"),
+                    cpu => (platform.print_xy)(10, 12 + index, &format!("{} is a mason.", cpu)),
                 }
+            }
 
-                state.turn = state.turn.next();
+            if ready_button(platform, state, left_mouse_pressed, left_mouse_released) {
+                act(state, Action::Proceed);
             }
         }
         SeerTurn => {
@@ -549,11 +408,6 @@ fn advance_turn_if_needed(state: &mut State,
                       platform,
                       left_mouse_pressed,
                       left_mouse_released,
-                      SeerRevealOne,
-                      SeerRevealTwo,
-                      reveal_one_turn,
-                      reveal_two_turn,
-                      is_seer,
                       "Seer");
         }
         SeerRevealOne(participant) => {
@@ -575,10 +429,6 @@ fn advance_turn_if_needed(state: &mut State,
                         platform,
                         left_mouse_pressed,
                         left_mouse_released,
-                        RobberReveal,
-                        robber_action,
-                        is_player_robber,
-                        get_robber_index,
                         "Robber");
         }
         RobberReveal => {
@@ -589,10 +439,6 @@ fn advance_turn_if_needed(state: &mut State,
                               platform,
                               left_mouse_pressed,
                               left_mouse_released,
-                              is_player_troublemaker,
-                              get_troublemaker_index,
-                              TroublemakerSecondChoice,
-                              troublemaker_action,
                               "Troublemaker");
         }
         TroublemakerSecondChoice(first_choice) => {
@@ -600,8 +446,6 @@ fn advance_turn_if_needed(state: &mut State,
                                        platform,
                                        left_mouse_pressed,
                                        left_mouse_released,
-                                       troublemaker_action,
-                                       TroublemakerTurn,
                                        first_choice);
         }
         DrunkTurn => {
@@ -609,9 +453,6 @@ fn advance_turn_if_needed(state: &mut State,
                        platform,
                        left_mouse_pressed,
                        left_mouse_released,
-                       is_player_drunk,
-                       get_drunk_index,
-                       drunk_action,
                        "Drunk");
         }
         InsomniacTurn => {
@@ -619,9 +460,6 @@ fn advance_turn_if_needed(state: &mut State,
                            platform,
                            left_mouse_pressed,
                            left_mouse_released,
-                           is_player_insomniac,
-                           get_insomniac_index,
-                           insomniac_action,
                            "Insomniac");
         }
         DoppelInsomniacTurn => {
@@ -629,63 +467,26 @@ fn advance_turn_if_needed(state: &mut State,
                            platform,
                            left_mouse_pressed,
                            left_mouse_released,
-                           is_player_doppel_insomniac,
-                           get_doppel_insomniac_index,
-                           doppel_insomniac_action,
                            "DoppelInsomniac");
         }
         BeginDiscussion => {
-            state.claims.clear();
-
-            let mut first_speakers = get_other_participants(state, Player);
-            let len = first_speakers.len();
-            if len > 0 {
-                let first_speaker_count = state.rng.gen_range(0, len);
-
-                state.rng.shuffle(&mut first_speakers);
-                for _ in 0..first_speaker_count {
-                    if let Some(participant) = first_speakers.pop() {
-                        make_cpu_claim(state, participant);
-                    }
-                }
-            }
-
-            state.turn = state.turn.next();
+            //the cpu players open the discussion on their own, see `engine::advance`.
         }
         Discuss => {
-            //TODO player can make claims to affect cpu players claims
-            // if let Some(player_claim_or_silence) =
-            //     get_player_claim_or_silence(platform,
-            //                                 state,
-            //                                 left_mouse_pressed,
-            //                                 left_mouse_released) {
-            //     match player_claim_or_silence {
-            //         // ActualClaim(player_claim) => insert_claim(state, Player, player_claim),
-            //         Silence => {}
-            //     }
-            //
-            //     make_remaining_claims(state);
-            // }
-            //Force show all claims until player claims are implemented (if ever)
-            make_remaining_claims(state);
-
-            let claims = get_claim_vec(state);
-
-            let lines = claims_to_lines(state, claims);
-
-            for i in 0..lines.len() {
+            let lines = transcript_to_lines(state);
+
+            //the latest round is the one to see, so older lines scroll off the top
+            let room = ((platform.size)().height - 5 - MAX_CLAIM_HEIGHT).max(0) as usize;
+            let skipped = lines.len().saturating_sub(room);
+
+            for (i, line) in lines.iter().skip(skipped).enumerate() {
                 let index = i as i32;
 
-                (platform.print_xy)(10, MAX_CLAIM_HEIGHT + (index), &lines[i]);
+                (platform.print_xy)(10, MAX_CLAIM_HEIGHT + (index), line);
             }
 
             if ready_button(platform, state, left_mouse_pressed, left_mouse_released) {
-                //if the player doesn't want to make a claim/see the reminaing claims,
-                //that's their business, but the cpu players should get to see what
-                //the thother cpu players say.
-                make_remaining_claims(state);
-
-                state.turn = state.turn.next();
+                act(state, Action::Proceed);
             }
 
         }
@@ -695,244 +496,458 @@ fn advance_turn_if_needed(state: &mut State,
                 pick_cpu_player(platform, state, left_mouse_pressed, left_mouse_released);
 
             if let Some(player_vote) = possible_player_vote {
-                state.votes.clear();
+                act(state, Action::VoteFor(player_vote));
+            }
+        }
+        Resolution => {
+            let size = (platform.size)();
 
-                state.votes.push((Player, player_vote));
+            let toggle_night_log_spec = ButtonSpec {
+                x: size.width - 20,
+                y: size.height - 4,
+                w: 18,
+                h: 3,
+                text: if state.show_night_log {
+                    "Show results".to_owned()
+                } else {
+                    "Show the night".to_owned()
+                },
+                id: 4,
+            };
 
-                for i in 0..state.cpu_knowledge.len() {
-                    let voter = Cpu(i);
+            if do_button(platform,
+                         &mut state.ui_context,
+                         &toggle_night_log_spec,
+                         left_mouse_pressed,
+                         left_mouse_released) {
+                state.show_night_log = !state.show_night_log;
+            }
 
-                    let insomniac_peek = {
-                        let knowledge = &mut state.cpu_knowledge[i];
+            if state.show_night_log {
+                display_night_log(platform, 10, 1, &state.night_log);
+            } else {
+                let outcome = outcome::resolve(state);
 
-                        apply_swaps(knowledge);
+                display_outcome(platform, state, &outcome);
+            }
 
-                        knowledge.insomniac_peek
-                    };
+            if ready_button(platform, state, left_mouse_pressed, left_mouse_released) {
+                state.show_night_log = false;
+                act(state, Action::Proceed);
+            }
+        }
 
-                    if insomniac_peek {
-                        if let Some(role) = get_role(state, voter) {
-                            let knowledge = &mut state.cpu_knowledge[i];
-
-                            knowledge.role = role;
-                            if is_werewolf(knowledge.role) {
-                                knowledge.known_villagers.remove(&voter);
-                                knowledge.known_werewolves.insert(voter);
-                            } else if knowledge.role == Minion {
-                                knowledge.known_villagers.remove(&voter);
-                                knowledge.known_minion = Some(voter);
-                            } else if knowledge.role == Tanner {
-                                knowledge.known_villagers.remove(&voter);
-                                knowledge.known_tanner = Some(voter);
-                            };
+    }
 
-                        }
-                    }
+}
 
-                    let vote = get_vote(voter,
-                                        get_participants(state),
-                                        &state.cpu_knowledge[i],
-                                        &mut state.rng);
+//A button for each cpu seat, which goes through the `CpuProfile`s then
+//random when clicked.
+fn profile_controls(platform: &Platform,
+                    state: &mut State,
+                    left_mouse_pressed: bool,
+                    left_mouse_released: bool) {
+    (platform.print_xy)(68, 11, "Cpu styles:");
 
-                    state.votes.push((voter, vote));
-                }
+    let cpu_count = state.role_spec.get_cpu_player_count(None) as usize;
 
-                state.turn = state.turn.next();
-            }
-        }
-        Resolution => {
-            for i in 0..state.votes.len() {
-                let (voter, vote) = state.votes[i];
-                (platform.print_xy)(10,
-                                    (i as i32 + 1),
-                                    &format!("{} voted for {}!", voter, vote));
-            }
-
-            let just_votes = &state.votes
-                                  .iter()
-                                  .map(|&(_, v)| v)
-                                  .collect();
-            let mut targets = count_votes(&just_votes);
-
-            if let Some(hunter_participant) = get_participant_with_role(state, Hunter) {
-                if targets.contains(&hunter_participant) {
-                    if let Some(hunter_target) =
-                        state.votes
-                            .iter()
-                            .find(|&&(voter, _)| voter == hunter_participant)
-                            .map(|&(_, v)| v) {
-                        if !targets.contains(&hunter_target) {
-                            targets.push(hunter_target)
-                        }
-                    }
-                }
-            };
+    for i in 0..std::cmp::min(cpu_count, state.profile_picks.len()) {
+        let pick = state.profile_picks[i];
 
-            if let Some(doppel_hunter_participant) =
-                get_participant_by_role(state, |r| match r {
-                    &DoppelHunter(_) => true,
-                    _ => false,
-                }) {
-                if targets.contains(&doppel_hunter_participant) {
-                    if let Some(doppel_hunter_target) =
-                        state.votes
-                            .iter()
-                            .find(|&&(voter, _)| voter == doppel_hunter_participant)
-                            .map(|&(_, v)| v) {
-                        if !targets.contains(&doppel_hunter_target) {
-                            targets.push(doppel_hunter_target)
-                        }
-                    }
+        let spec = ButtonSpec {
+            x: 68,
+            y: 12 + (3 * i as i32),
+            w: 12,
+            h: 3,
+            text: match pick {
+                Some(profile) => format!("{} {}", i, profile),
+                None => format!("{} Random", i),
+            },
+            id: 800 + i as i32,
+        };
+
+        if do_button(platform,
+                     &mut state.ui_context,
+                     &spec,
+                     left_mouse_pressed,
+                     left_mouse_released) {
+            let profiles = all_profiles();
+
+            state.profile_picks[i] = match pick {
+                None => profiles.first().cloned(),
+                Some(profile) => {
+                    profiles.iter()
+                        .position(|&p| p == profile)
+                        .and_then(|index| profiles.get(index + 1))
+                        .cloned()
                 }
             };
+        }
+    }
+}
 
-            targets.sort();
+fn seed_controls(platform: &Platform,
+                 state: &mut State,
+                 left_mouse_pressed: bool,
+                 left_mouse_released: bool) {
+    let seed_line = match state.seed_entry {
+        Some(ref entry) => format!("Seed: {}_", entry),
+        None => format!("Seed: {}", state.seed),
+    };
 
-            if targets.len() == 0 {
-                (platform.print_xy)(10, 10, "Nobody died.");
+    (platform.print_xy)(50, 5, &seed_line);
 
-                let werewolves = get_werewolves(state);
+    let enter_seed_spec = ButtonSpec {
+        x: 52,
+        y: 0,
+        w: 13,
+        h: 3,
+        text: if state.seed_entry.is_some() {
+            "Use seed".to_owned()
+        } else {
+            "Enter seed".to_owned()
+        },
+        id: 5,
+    };
 
-                let len = werewolves.len();
-                if len == 0 {
-                    (platform.print_xy)(10, 12, "And nobody was a werewolf!");
-                    (platform.print_xy)(10, 13, "Village team wins!");
-                } else {
-                    if len > 1 {
-                        (platform.print_xy)(10, 12, &format!("But there were {} werewolves!", len));
-                    } else {
-                        (platform.print_xy)(10, 12, "But there was a werewolf!");
-                    }
-                    (platform.print_xy)(10, 13, "Werewolf team wins!");
-                }
-            } else {
-                (platform.print_xy)(10, 10, &format!("{} died!", str_list(&targets)));
-
-                let target_roles = targets.iter().filter_map(|&p| get_role(state, p));
-                let hit_werevoles_count = target_roles.filter(|&r| is_werewolf(r)).count();
-
-                let possible_dead_tanner =
-                    get_participant_with_role(state, Tanner).and_then(|p| if
-                        targets.contains(&p) {
-                                                                          Some(p)
-                                                                      } else {
-                                                                          None
-                                                                      });
-                let possible_dead_doppel_tanner =
-                    get_participant_by_role(state, |r| match r {
-                        &DoppelTanner(_) => true,
-                        _ => false,
-                    })
-                            .and_then(|p| if targets.contains(&p) { Some(p) } else { None });
-
-                if hit_werevoles_count >= 1 {
-                    if hit_werevoles_count == 1 {
-                        (platform.print_xy)(10, 12, "A werewolf died!");
-                    } else {
-                        (platform.print_xy)(10,
-                                            12,
-                                            &format!("{} werewolves died!", hit_werevoles_count));
-                    }
-                    (platform.print_xy)(10, 13, "Village team wins!");
+    if do_button(platform,
+                 &mut state.ui_context,
+                 &enter_seed_spec,
+                 left_mouse_pressed,
+                 left_mouse_released) {
+        if state.seed_entry.is_some() {
+            finish_seed_entry(state);
+        } else {
+            state.seed_entry = Some(String::new());
+        }
+    }
 
-                    if let Some(dead_tanner) = possible_dead_tanner {
-                        display_tanner_win(platform, dead_tanner, true);
-                    }
-                    if let Some(dead_doppel_tanner) = possible_dead_doppel_tanner {
-                        display_doppel_tanner_win(platform, dead_doppel_tanner, true);
-                    }
+    if state.can_continue && state.last_deal.is_none() {
+        let continue_spec = ButtonSpec {
+            x: 66,
+            y: 0,
+            w: 13,
+            h: 3,
+            text: "Continue".to_owned(),
+            id: 9,
+        };
 
-                } else {
-                    let werewolves = get_werewolves(state);
-
-                    if werewolves.len() > 0 {
-                        (platform.print_xy)(10,
-                                            12,
-                                            "No werewolves died but a player was a werewolf!");
-
-                        match (possible_dead_tanner, possible_dead_doppel_tanner) {
-                            (None, None) => {
-                                (platform.print_xy)(10, 13, "Werewolf team wins!");
-                            }
-                            (Some(dead_tanner), None) => {
-                                display_tanner_win(platform, dead_tanner, false);
-                            }
-                            (None, Some(dead_doppel_tanner)) => {
-                                display_doppel_tanner_win(platform, dead_doppel_tanner, false);
-                            }
-                            (Some(dead_tanner), Some(dead_doppel_tanner)) => {
-                                display_tanner_win(platform, dead_tanner, false);
-                                display_doppel_tanner_win(platform, dead_doppel_tanner, true);
-
-                            }
-                        };
-
-                    } else {
-                        (platform.print_xy)(10,
-                                            12,
-                                            "No werewolves died but nobody was a werewolf!");
-
-                        if let Some(_) = get_participant_with_role(state, Minion) {
-                            (platform.print_xy)(10, 13, "But there was a minion! The minion wins!");
-
-                            if let Some(dead_tanner) = possible_dead_tanner {
-                                display_tanner_win(platform, dead_tanner, true);
-                            }
-                            if let Some(dead_doppel_tanner) = possible_dead_doppel_tanner {
-                                display_doppel_tanner_win(platform, dead_doppel_tanner, true);
-                            }
-                        } else {
-                            match (possible_dead_tanner, possible_dead_doppel_tanner) {
-                                (None, None) => {
-                                    (platform.print_xy)(10, 13, "Nobody wins!");
-                                }
-                                (Some(dead_tanner), None) => {
-                                    display_tanner_win(platform, dead_tanner, false);
-                                }
-                                (None, Some(dead_doppel_tanner)) => {
-                                    display_doppel_tanner_win(platform, dead_doppel_tanner, false);
-                                }
-                                (Some(dead_tanner), Some(dead_doppel_tanner)) => {
-                                    display_tanner_win(platform, dead_tanner, false);
-                                    display_doppel_tanner_win(platform, dead_doppel_tanner, true);
-
-                                }
-                            };
-                        }
-                    }
+        if do_button(platform,
+                     &mut state.ui_context,
+                     &continue_spec,
+                     left_mouse_pressed,
+                     left_mouse_released) {
+            match save::take(save::SAVE_PATH) {
+                Ok(saved) => {
+                    *state = saved;
+                    state.can_continue = false;
+                }
+                Err(e) => {
+                    state.save_error = Some(format!("couldn't continue: {}", e));
+                    state.can_continue = false;
                 }
             }
+        }
+    }
 
-            (platform.print_xy)(10,
-                                20,
-                                &format!("You are {}", full_role_string(state.player)));
+    if state.last_deal.is_some() {
+        let replay_spec = ButtonSpec {
+            x: 66,
+            y: 0,
+            w: 13,
+            h: 3,
+            text: "Replay deal".to_owned(),
+            id: 8,
+        };
+
+        if do_button(platform,
+                     &mut state.ui_context,
+                     &replay_spec,
+                     left_mouse_pressed,
+                     left_mouse_released) {
+            state.seed_entry = None;
+            act(state, Action::Replay);
+        }
+    }
+}
+
+//returns false if the game couldn't be saved, after putting why on the screen
+fn save_if_playing(state: &mut State) -> bool {
+    match save::save_or_delete(state, save::SAVE_PATH) {
+        Ok(()) => true,
+        Err(e) => {
+            state.save_error = Some(format!("couldn't save: {}", e));
+
+            false
+        }
+    }
+}
+
+fn seed_entry_key(state: &mut State, key: KeyCode) {
+    let digit = match key {
+        KeyCode::Row0 | KeyCode::Num0 => Some('0'),
+        KeyCode::Row1 | KeyCode::Num1 => Some('1'),
+        KeyCode::Row2 | KeyCode::Num2 => Some('2'),
+        KeyCode::Row3 | KeyCode::Num3 => Some('3'),
+        KeyCode::Row4 | KeyCode::Num4 => Some('4'),
+        KeyCode::Row5 | KeyCode::Num5 => Some('5'),
+        KeyCode::Row6 | KeyCode::Num6 => Some('6'),
+        KeyCode::Row7 | KeyCode::Num7 => Some('7'),
+        KeyCode::Row8 | KeyCode::Num8 => Some('8'),
+        KeyCode::Row9 | KeyCode::Num9 => Some('9'),
+        _ => None,
+    };
+
+    match key {
+        KeyCode::Enter | KeyCode::NumEnter => finish_seed_entry(state),
+        KeyCode::Backspace => {
+            if let Some(ref mut entry) = state.seed_entry {
+                entry.pop();
+            }
+        }
+        _ => {
+            if let (Some(ref mut entry), Some(digit)) = (state.seed_entry.as_mut(), digit) {
+                entry.push(digit);
+            }
+        }
+    }
+}
+
+//keeps the old seed if nothing usable was typed in
+fn finish_seed_entry(state: &mut State) {
+    if let Some(seed) = state.seed_entry.take().and_then(|entry| entry.parse::<usize>().ok()) {
+        state.seed = seed;
+    }
+}
+
+fn display_outcome(platform: &Platform, state: &State, outcome: &GameOutcome) {
+    for i in 0..outcome.votes.len() {
+        let (voter, vote) = outcome.votes[i];
+        (platform.print_xy)(10, i as i32 + 1, &format!("{} voted for {}!", voter, vote));
+    }
+
+    let dead_tanners = outcome.dead_tanners();
+    let village_or_werewolf_won = outcome.team_won(Winner::Village) ||
+                                  outcome.team_won(Winner::Werewolf);
+
+    if outcome.dead.is_empty() {
+        (platform.print_xy)(10, 10, "Nobody died.");
+
+        let len = outcome.werewolves.len();
+        if len == 0 {
+            (platform.print_xy)(10, 12, "And nobody was a werewolf!");
+            (platform.print_xy)(10, 13, "Village team wins!");
+        } else {
+            if len > 1 {
+                (platform.print_xy)(10, 12, &format!("But there were {} werewolves!", len));
+            } else {
+                (platform.print_xy)(10, 12, "But there was a werewolf!");
+            }
+            (platform.print_xy)(10, 13, "Werewolf team wins!");
+        }
+    } else {
+        (platform.print_xy)(10, 10, &format!("{} died!", str_list(&outcome.dead)));
+
+        let hit_werevoles_count = outcome.dead_werewolf_count;
 
-            for i in 0..state.cpu_roles.len() {
+        if hit_werevoles_count >= 1 {
+            if hit_werevoles_count == 1 {
+                (platform.print_xy)(10, 12, "A werewolf died!");
+            } else {
                 (platform.print_xy)(10,
-                                    21 + i as i32,
-                                    &format!("{} is {}",
-                                             Cpu(i),
-                                             full_role_string(state.cpu_roles[i])));
+                                    12,
+                                    &format!("{} werewolves died!", hit_werevoles_count));
             }
+            (platform.print_xy)(10, 13, "Village team wins!");
+        } else if !outcome.werewolves.is_empty() {
+            (platform.print_xy)(10, 12, "No werewolves died but a player was a werewolf!");
 
-            if ready_button(platform, state, left_mouse_pressed, left_mouse_released) {
-                state.turn = state.turn.next();
+            if outcome.team_won(Winner::Werewolf) {
+                (platform.print_xy)(10, 13, "Werewolf team wins!");
+            }
+        } else {
+            (platform.print_xy)(10, 12, "No werewolves died but nobody was a werewolf!");
+
+            if outcome.team_won(Winner::Werewolf) {
+                (platform.print_xy)(10, 13, "But there was a minion! The minion wins!");
+            } else if dead_tanners.is_empty() {
+                (platform.print_xy)(10, 13, "Nobody wins!");
             }
         }
 
+        let mut tanner_already_won = false;
+        for &dead_tanner in dead_tanners.iter() {
+            let is_doppel_tanner = matches!(get_role(state, dead_tanner),
+                                            Some(Card::Doppel(_, Tanner)));
+
+            if is_doppel_tanner {
+                display_doppel_tanner_win(platform,
+                                          dead_tanner,
+                                          village_or_werewolf_won || tanner_already_won);
+            } else {
+                display_tanner_win(platform, dead_tanner, village_or_werewolf_won);
+            }
+
+            tanner_already_won = true;
+        }
+    }
+
+    for i in 0..outcome.results.len() {
+        let result = outcome.results[i];
+
+        let won_or_lost = if result.won { "won" } else { "lost" };
+
+        let line = match result.participant {
+            Player => format!("You are {} and you {}.", full_role_string(result.role), won_or_lost),
+            cpu => format!("{} is {} and they {}.", cpu, full_role_string(result.role), won_or_lost),
+        };
+
+        (platform.print_xy)(10, 20 + i as i32, &line);
     }
 
+    let reasons_y = 21 + outcome.results.len() as i32;
+
+    for i in 0..state.vote_reasons.len() {
+        let (voter, reason) = state.vote_reasons[i];
+
+        if let Some(&(_, vote)) = outcome.votes.iter().find(|&&(v, _)| v == voter) {
+            (platform.print_xy)(10,
+                                reasons_y + i as i32,
+                                &describe_vote_reason(state, voter, vote, reason));
+        }
+    }
 }
 
-fn other_masons_to_zero_to_two(other_masons: &Vec<Participant>) -> ZeroToTwo<Participant> {
-    let len = other_masons.len();
+//what a cpu says about their vote once it's all over
+fn describe_vote_reason(state: &State,
+                        voter: Participant,
+                        vote: Participant,
+                        reason: VoteReason)
+                        -> String {
+    let because = match reason {
+        VoteReason::SawWerewolf => {
+            format!("I saw that {} {} {}.", object_str(vote), was_str(vote), Werewolf)
+        }
+        VoteReason::KnewVillager => {
+            format!("I knew {} {}n't {}.", object_str(vote), was_str(vote), Werewolf)
+        }
+        VoteReason::Lied => {
+            let changed = state.transcript.iter().any(|s| match s.kind {
+                StatementKind::Changed(_) => s.speaker == vote,
+                _ => false,
+            });
 
-    if len >= 2 {
-        Two(other_masons[0], other_masons[1])
-    } else if len == 1 {
-        One(other_masons[0])
-    } else {
-        Zero
+            match state.claims.get(&vote) {
+                _ if changed => "I don't trust anyone who changes their story.".to_owned(),
+                Some(claim) => {
+                    format!("I knew that {} {:o} claim couldn't be true.",
+                            possessive_str(vote),
+                            claim.face())
+                }
+                None => format!("I knew {} {} lying.", object_str(vote), was_str(vote)),
+            }
+        }
+        VoteReason::Suspected => format!("Someone I believed pointed at {}.", object_str(vote)),
+        VoteReason::Likeliest => {
+            format!("From what was said, {} {} the likeliest {:o}.",
+                    object_str(vote),
+                    was_str(vote),
+                    Werewolf)
+        }
+        VoteReason::LeastLikely => {
+            format!("From what was said, {} {} the least likely {:o}.",
+                    object_str(vote),
+                    was_str(vote),
+                    Werewolf)
+        }
+        VoteReason::NotAWerewolf => format!("I voted at random, for anyone but {}.", Werewolf),
+        VoteReason::Random => "I had no information, so I voted at random.".to_owned(),
+        VoteReason::NextSeat => {
+            "I had nobody left to suspect, so I voted for the next seat.".to_owned()
+        }
+        VoteReason::Framed => {
+            format!("I said {} {} {}, so I had to stick to it.",
+                    object_str(vote),
+                    was_str(vote),
+                    Werewolf)
+        }
+        VoteReason::SplitVotes => {
+            "The vote was heading for a Werewolf, so I split it.".to_owned()
+        }
+        VoteReason::JoinedIn => {
+            format!("Everyone was voting for {}, so I did too.", object_str(vote))
+        }
+        VoteReason::FewestVotes => {
+            "I wanted to be voted out, so I voted for whoever was safest.".to_owned()
+        }
+        VoteReason::PlayedOut => {
+            format!("I played the game out, and voting for {} won most often.",
+                    object_str(vote))
+        }
+        VoteReason::Unexplained => return format!("{} didn't say why.", voter),
+    };
+
+    format!("{}: \"{}\"", voter, because)
+}
+
+fn display_night_log(platform: &Platform, x: i32, y: i32, night_log: &[NightEvent]) {
+    (platform.print_xy)(x, y, "During the night:");
+
+    for (i, event) in night_log.iter().enumerate() {
+        (platform.print_xy)(x + 2, y + 1 + i as i32, &describe_night_event(event));
+    }
+}
+
+fn describe_night_event(event: &NightEvent) -> String {
+    match *event {
+        NightEvent::Copied { actor, target, role } => {
+            format!("{} copied {}, who was {}.", actor, target, role)
+        }
+        NightEvent::SawAwake { actor, target, card } => {
+            format!("{} saw that {} was {}.", actor, target, full_role_string(card))
+        }
+        NightEvent::AwakeAlone { actor, role } => {
+            format!("{} woke up as the only {:o}.", actor, role)
+        }
+        NightEvent::ViewedPlayer { actor, target, card } => {
+            format!("{} viewed {} card: {}.",
+                    actor,
+                    possessive(target),
+                    full_role_string(card))
+        }
+        NightEvent::ViewedCenter { actor, center, card } => {
+            format!("{} viewed the {} center card: {}.",
+                    actor,
+                    center,
+                    full_role_string(card))
+        }
+        NightEvent::Robbed { actor, target, card } => {
+            format!("{} robbed {} and became {}.",
+                    actor,
+                    target,
+                    full_role_string(card))
+        }
+        NightEvent::Swapped { actor, first, second } => {
+            format!("{} swapped {} and {} cards.",
+                    actor,
+                    possessive(first),
+                    possessive(second))
+        }
+        NightEvent::TookCenter { actor, center } => {
+            format!("{} took the {} center card.", actor, center)
+        }
+        NightEvent::Peeked { actor, card } => {
+            format!("{} looked again and ended the night as {}.",
+                    actor,
+                    full_role_string(card))
+        }
+    }
+}
+
+fn possessive(participant: Participant) -> String {
+    match participant {
+        Player => "your".to_owned(),
+        cpu => format!("{}'s", cpu),
     }
 }
 
@@ -1000,6 +1015,14 @@ fn display_role_spec(platform: &Platform, x: i32, y: i32, role_spec: &RoleSpec)
 
 const MAX_ROLE_COUNTS_PER_LINE: usize = 3;
 
+fn display_night_script(platform: &Platform, x: i32, y: i32, night_script: &[NightStep]) {
+    (platform.print_xy)(x, y, "Tonight's script:");
+
+    for (i, step) in night_script.iter().enumerate() {
+        (platform.print_xy)(x + 2, y + 1 + i as i32, &format!("{}. {}", i + 1, step));
+    }
+}
+
 #[derive(Debug)]
 struct RoleCount(Role, u32);
 
@@ -1014,114 +1037,17 @@ impl std::fmt::Display for RoleCount {
     }
 }
 
-fn is_player_insomniac(state: &State) -> bool {
-    state.initial_player == Insomniac
-}
-
-fn is_player_doppel_insomniac(state: &State) -> bool {
-    match state.player {
-        DoppelInsomniac(_) => true,
-        _ => false,
-    }
-}
-
-fn get_insomniac_index(state: &State) -> Option<usize> {
-    linear_search(&state.initial_cpu_roles, &Insomniac)
-}
-fn get_doppel_insomniac_index(state: &State) -> Option<usize> {
-    linear_search_by(&state.cpu_roles, |r| match r {
-        &DoppelInsomniac(_) => true,
-        _ => false,
-    })
-}
-
-fn insomniac_action(_: &State, _: Participant, r: Role) -> Claim {
-    InsomniacAction(r)
-}
-fn doppel_insomniac_action(state: &State, participant: Participant, r: Role) -> Claim {
-    let participant = insomniac_doppel_target_or_player(get_role(state, participant)
-                                                            .unwrap_or(Villager));
-    DoppelInsomniacAction(participant, r)
-}
-
-fn insomniac_doppel_target_or_player(role: Role) -> Participant {
-    match role {
-        DoppelInsomniac(p) => p,
-        _ => Player,
-    }
-}
-
 fn insomniac_turn(state: &mut State,
                   platform: &Platform,
                   left_mouse_pressed: bool,
                   left_mouse_released: bool,
-                  player_pred: fn(&State) -> bool,
-                  get_cpu_index: fn(&State) -> Option<usize>,
-                  action: fn(&State, Participant, Role) -> Claim,
                   name: &str) {
+    (platform.print_xy)(15, 3, &format!("{}, wake up and look at your card.", name));
 
-    if player_pred(state) {
-        (platform.print_xy)(15, 3, &format!("{}, wake up and look at your card.", name));
-
-        (platform.print_xy)(15, 5, &format!("You are {}", state.player));
-        state.player_knowledge.true_claim = action(state, Player, state.player);
+    (platform.print_xy)(15, 5, &format!("You are {}", state.table.player()));
 
-        if ready_button(platform, state, left_mouse_pressed, left_mouse_released) {
-            state.turn = state.turn.next();
-        }
-    } else {
-        if let Some(i) = get_cpu_index(state) {
-
-            if let Some(&role) = state.cpu_roles.get(i) {
-                let true_claim = action(state, Player, role);
-                if let Some(knowledge) = state.cpu_knowledge.get_mut(i) {
-
-                    knowledge.true_claim = true_claim;
-                    knowledge.insomniac_peek = true;
-                    knowledge.role = role;
-                }
-            }
-        }
-
-        state.turn = state.turn.next();
-    }
-
-}
-
-fn is_player_drunk(state: &State) -> bool {
-    state.initial_player == Drunk
-}
-
-fn is_player_doppel_drunk(state: &State) -> bool {
-    match state.player {
-        DoppelDrunk(_) => true,
-        _ => false,
-    }
-}
-
-fn get_drunk_index(state: &State) -> Option<usize> {
-    linear_search(&state.initial_cpu_roles, &Drunk)
-}
-fn get_doppel_drunk_index(state: &State) -> Option<usize> {
-    linear_search_by(&state.cpu_roles, |r| match r {
-        &DoppelDrunk(_) => true,
-        _ => false,
-    })
-}
-
-fn drunk_action(_: &State, _: Participant, c: CenterCard) -> Claim {
-    DrunkAction(c)
-}
-fn doppel_drunk_action(state: &State, participant: Participant, c: CenterCard) -> Claim {
-    let participant = drunk_doppel_target_or_player(get_role(state, participant)
-                                                        .unwrap_or(Villager));
-    DoppelDrunkAction(participant, c)
-}
-
-fn drunk_doppel_target_or_player(role: Role) -> Participant {
-    match role {
-        DoppelDrunk(p) => p,
-        _ => Player,
+    if ready_button(platform, state, left_mouse_pressed, left_mouse_released) {
+        act(state, Action::Proceed);
     }
 }
 
@@ -1129,93 +1055,21 @@ fn drunk_turn(state: &mut State,
               platform: &Platform,
               left_mouse_pressed: bool,
               left_mouse_released: bool,
-              player_pred: fn(&State) -> bool,
-              get_cpu_index: fn(&State) -> Option<usize>,
-              action: fn(&State, Participant, CenterCard) -> Claim,
               name: &str) {
-    if player_pred(state) {
-        (platform.print_xy)(15,
-                            3,
-                            &format!("{}, wake up
+    (platform.print_xy)(15,
+                        3,
+                        &format!("{}, wake up
 and exchange your card with a card from the center.",
-                                     name));
-
-        let choice = pick_displayable(platform,
-                                      state,
-                                      left_mouse_pressed,
-                                      left_mouse_released,
-                                      &CenterCard::all_values());
-        match choice {
-            Some(chosen) => {
-                swap_role_with_center(state, Player, chosen);
-
-                let true_claim = action(state, Player, chosen);
-                if let Some(knowledge) = get_knowledge_mut(state, Player) {
-                    knowledge.true_claim = true_claim;
-                    knowledge.drunk_swap = Some((Player, chosen));
-                }
+                                 name));
 
-                state.turn = state.turn.next();
-            }
-            None => {}
-        }
-    } else {
-        if let Some(drunk_index) = get_cpu_index(state) {
-            let drunk = Cpu(drunk_index);
-
-            let target = state.rng.gen::<CenterCard>();
-
-            swap_role_with_center(state, drunk, target);
-
-            let true_claim = action(state, drunk, target);
-            if let Some(knowledge) = get_knowledge_mut(state, drunk) {
-                knowledge.true_claim = true_claim;
-                knowledge.drunk_swap = Some((drunk, target));
-            }
-        }
-
-        state.turn = state.turn.next();
-    };
-}
-
-fn is_player_troublemaker(state: &State) -> bool {
-    state.initial_player == Troublemaker
-}
+    let choice = pick_displayable(platform,
+                                  state,
+                                  left_mouse_pressed,
+                                  left_mouse_released,
+                                  &CenterCard::all_values());
 
-fn is_player_doppel_troublemaker(state: &State) -> bool {
-    match state.player {
-        DoppelTroublemaker(_) => true,
-        _ => false,
-    }
-}
-
-fn get_troublemaker_index(state: &State) -> Option<usize> {
-    linear_search(&state.initial_cpu_roles, &Troublemaker)
-}
-fn get_doppel_troublemaker_index(state: &State) -> Option<usize> {
-    linear_search_by(&state.cpu_roles, |r| match r {
-        &DoppelTroublemaker(_) => true,
-        _ => false,
-    })
-}
-
-fn troublemaker_action(_: &State, _: Participant, p1: Participant, p2: Participant) -> Claim {
-    TroublemakerAction(p1, p2)
-}
-fn doppel_troublemaker_action(state: &State,
-                              participant: Participant,
-                              p1: Participant,
-                              p2: Participant)
-                              -> Claim {
-    let participant = troublemaker_doppel_target_or_player(get_role(state, participant)
-                                                               .unwrap_or(Villager));
-    DoppelTroublemakerAction(participant, p1, p2)
-}
-
-fn troublemaker_doppel_target_or_player(role: Role) -> Participant {
-    match role {
-        DoppelTroublemaker(p) => p,
-        _ => Player,
+    if let Some(chosen) = choice {
+        act(state, Action::TakeCenter(chosen));
     }
 }
 
@@ -1223,8 +1077,6 @@ fn troublemaker_second_choice(state: &mut State,
                               platform: &Platform,
                               left_mouse_pressed: bool,
                               left_mouse_released: bool,
-                              action: fn(&State, Participant, Participant, Participant) -> Claim,
-                              back_turn: Turn,
                               first_choice: Participant) {
     (platform.print_xy)(15, 5, "Choose the second other player:");
 
@@ -1239,10 +1091,7 @@ fn troublemaker_second_choice(state: &mut State,
                          left_mouse_pressed,
                          left_mouse_released,
                          &remaining_options) {
-        swap_roles(state, first_choice, second_choice);
-        state.player_knowledge.true_claim = action(state, Player, first_choice, second_choice);
-
-        state.turn = state.turn.next();
+        act(state, Action::PickSecond(second_choice));
     };
     if do_button(platform,
                  &mut state.ui_context,
@@ -1256,261 +1105,87 @@ fn troublemaker_second_choice(state: &mut State,
                   },
                  left_mouse_pressed,
                  left_mouse_released) {
-        state.turn = back_turn;
+        act(state, Action::Back);
     }
 }
 fn troublemaker_turn(state: &mut State,
                      platform: &Platform,
                      left_mouse_pressed: bool,
                      left_mouse_released: bool,
-                     player_pred: fn(&State) -> bool,
-                     get_cpu_index: fn(&State) -> Option<usize>,
-                     second_choice: fn(Participant) -> Turn,
-                     action: fn(&State, Participant, Participant, Participant) -> Claim,
                      name: &str) {
-    if player_pred(state) {
-        (platform.print_xy)(15,
-                            3,
-                            &format!("{}, wake up.
+    (platform.print_xy)(15,
+                        3,
+                        &format!("{}, wake up.
 You may exchange cards between two other players.",
-                                     name));
+                                 name));
 
-        (platform.print_xy)(15, 5, "Choose the first other player:");
+    (platform.print_xy)(15, 5, "Choose the first other player:");
 
 
-        let choice =
-            pick_cpu_player_or_skip(platform, state, left_mouse_pressed, left_mouse_released);
-        match choice {
-            Skip => {
-                state.turn = state.turn.next();
-            }
-            Chosen(chosen) => {
-                state.turn = second_choice(chosen);
-            }
-            NoChoice => {}
+    let choice =
+        pick_cpu_player_or_skip(platform, state, left_mouse_pressed, left_mouse_released);
+    match choice {
+        Skip => {
+            act(state, Action::Skip);
         }
-    } else {
-        if let Some(troublemaker_index) = get_cpu_index(state) {
-            let troublemaker = Cpu(troublemaker_index);
-
-            let mut other_participants = get_other_participants(state, troublemaker);
-            state.rng.shuffle(&mut other_participants);
-
-            if let (Some(first_choice), Some(second_choice)) =
-                (other_participants.pop(), other_participants.pop()) {
-                swap_roles(state, first_choice, second_choice);
-
-                let true_claim = action(state, troublemaker, first_choice, second_choice);
-                if let Some(knowledge) = get_knowledge_mut(state, troublemaker) {
-                    knowledge.true_claim = true_claim;
-                    knowledge.troublemaker_swap = Some((first_choice, second_choice));
-                }
-            }
+        Chosen(chosen) => {
+            act(state, Action::PickFirst(chosen));
         }
-
-        state.turn = state.turn.next();
-    };
-}
-
-fn is_player_minion(state: &State) -> bool {
-    state.initial_player == Minion
-}
-
-fn is_player_doppel_minion(state: &State) -> bool {
-    match state.player {
-        DoppelMinion(_) => true,
-        _ => false,
+        NoChoice => {}
     }
 }
 
-fn get_minion_index(state: &State) -> Option<usize> {
-    linear_search(&state.initial_cpu_roles, &Minion)
-}
-fn get_doppel_minion_index(state: &State) -> Option<usize> {
-    linear_search_by(&state.cpu_roles, |r| match r {
-        &DoppelMinion(_) => true,
-        _ => false,
-    })
-}
 fn minion_turn(state: &mut State,
-               platform: &Platform,
-               left_mouse_pressed: bool,
-               left_mouse_released: bool,
-               player_pred: fn(&State) -> bool,
-               get_cpu_index: fn(&State) -> Option<usize>,
-               name: &str) {
-    let werewolves = get_werewolves(state);
-
-    if player_pred(state) {
-        (platform.print_xy)(10,
-                            10,
-                            &format!("{}, wake up. Werewolves, stick out
-your thumb so the Minion can see who you are.",
-                                     name));
-
-        list_werewolves(platform, &werewolves);
-
-        if ready_button(platform, state, left_mouse_pressed, left_mouse_released) {
-            state.turn = state.turn.next();
-        }
-    } else {
-        if let Some(minion_index) = get_cpu_index(state) {
-            let minion = Cpu(minion_index);
-
-            if let Some(knowledge) = get_knowledge_mut(state, minion) {
-                knowledge.known_werewolves.extend(werewolves.iter());
-                knowledge.known_minion = Some(minion);
-                knowledge.true_claim = Simple(Minion);
-            }
-        }
-
-        state.turn = state.turn.next();
-    }
-}
-
-fn robber_action(_: &State, _: Participant, p: Participant, r: Role) -> Claim {
-    RobberAction(p, r)
-}
-fn doppel_robber_action(state: &State, participant: Participant, p: Participant, r: Role) -> Claim {
-    let participant = robber_doppel_target_or_player(get_role(state, participant)
-                                                         .unwrap_or(Villager));
-    DoppelRobberAction(participant, p, r)
-}
-
-fn robber_doppel_target_or_player(role: Role) -> Participant {
-    match role {
-        DoppelRobber(p) => p,
-        _ => Player,
-    }
-}
-
-
-fn reveal_player(state: &mut State,
-                 platform: &Platform,
-                 left_mouse_pressed: bool,
-                 left_mouse_released: bool) {
-    (platform.print_xy)(10, 10, &format!("You are now {}.", state.player));
-
-    if ready_button(platform, state, left_mouse_pressed, left_mouse_released) {
-        state.turn = state.turn.next();
-    }
-}
-fn robber_turn(state: &mut State,
-               platform: &Platform,
-               left_mouse_pressed: bool,
-               left_mouse_released: bool,
-               reveal_turn: Turn,
-               action: fn(&State, Participant, Participant, Role) -> Claim,
-               player_pred: fn(&State) -> bool,
-               get_cpu_index: fn(&State) -> Option<usize>,
-               name: &str) {
-    if player_pred(state) {
-        (platform.print_xy)(15,
-                            3,
-                            &format!("{}, wake up.
-You may exchange your card with another player’s card,
-and then view your new card.",
-                                     name));
-
-
-        let choice =
-            pick_cpu_player_or_skip(platform, state, left_mouse_pressed, left_mouse_released);
-        match choice {
-            Skip => {
-                state.turn = state.turn.next();
-            }
-            Chosen(chosen) => {
-                swap_roles(state, Player, chosen);
-
-                state.turn = reveal_turn;
-            }
-            NoChoice => {}
-        }
-    } else {
-        if let Some(robber_index) = get_cpu_index(state) {
-            let robber = Cpu(robber_index);
-
-            let other_participants = get_other_participants(state, robber);
-            if let Some(&chosen) = state.rng.choose(&other_participants) {
-                swap_roles(state, robber, chosen);
-
-                if let Some(new_role) = get_role(state, robber) {
-                    let true_claim = action(state, robber, chosen, new_role);
-                    if let Some(knowledge) = get_knowledge_mut(state, robber) {
-                        knowledge.role = new_role;
-                        knowledge.true_claim = true_claim;
-                        knowledge.robber_swap = Some((robber, chosen, new_role));
-                    }
-                }
-            }
-        }
-
-        state.turn = state.turn.next();
-    };
-}
-fn is_player_robber(state: &State) -> bool {
-    state.initial_player == Robber
-}
-fn is_player_doppel_robber(state: &State) -> bool {
-    match state.player {
-        DoppelRobber(_) => true,
-        _ => false,
-    }
-}
-fn get_robber_index(state: &State) -> Option<usize> {
-    linear_search(&state.initial_cpu_roles, &Robber)
-}
-fn get_doppel_robber_index(state: &State) -> Option<usize> {
-    linear_search_by(&state.cpu_roles, |r| match r {
-        &DoppelRobber(_) => true,
-        _ => false,
-    })
-}
-
-fn reveal_one_turn(_: &State, _: Participant, p: Participant, r: Role) -> Claim {
-    SeerRevealOneAction(p, r)
-}
-fn reveal_two_turn(_: &State, _: Participant, pair: CenterPair, r1: Role, r2: Role) -> Claim {
-    SeerRevealTwoAction(pair, r1, r2)
-}
+               platform: &Platform,
+               left_mouse_pressed: bool,
+               left_mouse_released: bool,
+               name: &str) {
+    (platform.print_xy)(10,
+                        10,
+                        &format!("{}, wake up. Werewolves, stick out
+your thumb so the Minion can see who you are.",
+                                 name));
 
-fn doppel_reveal_one_turn(state: &State,
-                          participant: Participant,
-                          p: Participant,
-                          r: Role)
-                          -> Claim {
-    let participant = seer_doppel_target_or_player(get_role(state, participant)
-                                                       .unwrap_or(Villager));
+    list_werewolves(platform, &get_werewolves(state));
 
-    DoppelSeerRevealOneAction(participant, p, r)
-}
-fn doppel_reveal_two_turn(state: &State,
-                          participant: Participant,
-                          pair: CenterPair,
-                          r1: Role,
-                          r2: Role)
-                          -> Claim {
-    let participant = seer_doppel_target_or_player(get_role(state, participant)
-                                                       .unwrap_or(Villager));
-    DoppelSeerRevealTwoAction(participant, pair, r1, r2)
+    if ready_button(platform, state, left_mouse_pressed, left_mouse_released) {
+        act(state, Action::Proceed);
+    }
 }
 
+fn reveal_player(state: &mut State,
+                 platform: &Platform,
+                 left_mouse_pressed: bool,
+                 left_mouse_released: bool) {
+    (platform.print_xy)(10, 10, &format!("You are now {}.", state.table.player()));
 
-fn seer_doppel_target_or_player(role: Role) -> Participant {
-    match role {
-        DoppelSeer(p) => p,
-        _ => Player,
+    if ready_button(platform, state, left_mouse_pressed, left_mouse_released) {
+        act(state, Action::Proceed);
     }
 }
+fn robber_turn(state: &mut State,
+               platform: &Platform,
+               left_mouse_pressed: bool,
+               left_mouse_released: bool,
+               name: &str) {
+    (platform.print_xy)(15,
+                        3,
+                        &format!("{}, wake up.
+You may exchange your card with another player’s card,
+and then view your new card.",
+                                 name));
 
-fn is_seer(role: &Role) -> bool {
-    role == &Seer
-}
 
-fn is_doppel_seer(role: &Role) -> bool {
-    match role {
-        &DoppelSeer(_) => true,
-        _ => false,
+    let choice =
+        pick_cpu_player_or_skip(platform, state, left_mouse_pressed, left_mouse_released);
+    match choice {
+        Skip => {
+            act(state, Action::Skip);
+        }
+        Chosen(chosen) => {
+            act(state, Action::Rob(chosen));
+        }
+        NoChoice => {}
     }
 }
 
@@ -1532,7 +1207,7 @@ fn seer_reveal_two(state: &mut State,
 
 
     if ready_button(platform, state, left_mouse_pressed, left_mouse_released) {
-        state.turn = state.turn.next();
+        act(state, Action::Proceed);
     }
 }
 
@@ -1550,7 +1225,7 @@ fn seer_reveal_one(state: &mut State,
     }
 
     if ready_button(platform, state, left_mouse_pressed, left_mouse_released) {
-        state.turn = state.turn.next();
+        act(state, Action::Proceed);
     }
 }
 
@@ -1558,94 +1233,33 @@ fn seer_turn(state: &mut State,
              platform: &Platform,
              left_mouse_pressed: bool,
              left_mouse_released: bool,
-             reveal_one: fn(Participant) -> Turn,
-             reveal_two: fn(CenterPair) -> Turn,
-             reveal_one_action: fn(&State, Participant, Participant, Role) -> Claim,
-             reveal_two_action: fn(&State, Participant, CenterPair, Role, Role) -> Claim,
-             role_pred: fn(&Role) -> bool,
              name: &str) {
-
-
-    if role_pred(&state.player) {
-        (platform.print_xy)(15,
-                            3,
-                            &format!("{}, wake up.
+    (platform.print_xy)(15,
+                        3,
+                        &format!("{}, wake up.
 You may look at another
 player’s card or two of the center cards.",
-                                     name));
-
-
-        let choice = pick_seer_choice(platform, state, left_mouse_pressed, left_mouse_released);
-        match choice {
-            SeerCpuOrSkip(cpu_or_skip) => {
-                match cpu_or_skip {
-
-                    Skip => {
-                        state.turn = state.turn.next();
-                    }
-                    Chosen(chosen) => {
-                        state.turn = reveal_one(chosen);
-                    }
-                    NoChoice => {}
-                }
-            }
-            ChosenPair(chosen) => {
-                state.turn = reveal_two(chosen);
-            }
-        }
-    } else {
-        if let Some(seer_index) = linear_search_by(&state.cpu_roles, role_pred) {
-            let seer = Cpu(seer_index);
-
-
-            let look_at_two = state.rng.gen::<bool>();
-
-            //TODO choose player or center based on active roles?
-            if look_at_two {
-
-                let pair = state.rng.gen::<CenterPair>();
+                                 name));
 
-                let (role1, role2) = get_role_pair(state, pair);
-                let true_claim = reveal_two_action(state, seer, pair, role1, role2);
 
-                if let Some(knowledge) = get_knowledge_mut(state, seer) {
-                    knowledge.known_non_active.insert(role1);
-                    knowledge.known_non_active.insert(role2);
+    let choice = pick_seer_choice(platform, state, left_mouse_pressed, left_mouse_released);
+    match choice {
+        SeerCpuOrSkip(cpu_or_skip) => {
+            match cpu_or_skip {
 
-                    knowledge.true_claim = true_claim;
+                Skip => {
+                    act(state, Action::Skip);
                 }
-            } else {
-                let other_participants = get_other_participants(state, seer);
-                if let Some(&chosen) = state.rng.choose(&other_participants) {
-                    if let Some(seen_role) = get_role(state, chosen) {
-                        let true_claim = reveal_one_action(state, seer, chosen, seen_role);
-
-                        if let Some(knowledge) = get_knowledge_mut(state, seer) {
-                            if is_werewolf(seen_role) {
-                                knowledge.known_villagers.remove(&chosen);
-                                knowledge.known_werewolves.insert(chosen);
-                            } else if seen_role == Minion {
-                                knowledge.known_villagers.remove(&chosen);
-                                knowledge.known_werewolves.remove(&chosen);
-                                knowledge.known_minion = Some(chosen);
-                            } else if seen_role == Tanner {
-                                knowledge.known_villagers.remove(&chosen);
-                                knowledge.known_werewolves.remove(&chosen);
-                                knowledge.known_tanner = Some(chosen);
-                            } else {
-                                knowledge.known_villagers.insert(chosen);
-                            };
-
-                            knowledge.true_claim = true_claim;
-                        }
-                    }
+                Chosen(chosen) => {
+                    act(state, Action::ViewPlayer(chosen));
                 }
-
+                NoChoice => {}
             }
         }
-
-        state.turn = state.turn.next();
-    };
+        ChosenPair(chosen) => {
+            act(state, Action::ViewCenter(chosen));
+        }
+    }
 }
 
 fn display_tanner_win(platform: &Platform, dead_tanner: Participant, addtional: bool) {
@@ -1674,7 +1288,7 @@ fn display_doppel_tanner_win(platform: &Platform,
                         &format!("{} died and {} were {}.",
                                  dead_doppel_tanner,
                                  pronoun,
-                                 DoppelTanner(Player)));
+                                 Doppelganger));
     if addtional {
 
         (platform.print_xy)(10, 17, "DoppelTanner wins as well!");
@@ -1703,241 +1317,8 @@ fn list_werewolves(platform: &Platform, werewolves: &Vec<Participant>) {
     }
 }
 
-fn get_participant_with_role(state: &State, role: Role) -> Option<Participant> {
-    if state.player == role {
-        Some(Player)
-    } else {
-        linear_search(&state.cpu_roles, &role).map(|i| Cpu(i))
-    }
-}
-
-fn get_participant_by_role<'a, F>(state: &'a State, mut f: F) -> Option<Participant>
-    where F: FnMut(&'a Role) -> bool
-{
-    if f(&state.player) {
-        Some(Player)
-    } else {
-        linear_search_by(&state.cpu_roles, f).map(|i| Cpu(i))
-    }
-}
-
-fn linear_search<T: PartialEq>(vector: &Vec<T>, thing: &T) -> Option<usize> {
-    for i in 0..vector.len() {
-        if thing == &vector[i] {
-            return Some(i);
-        }
-    }
-
-    None
-}
-
-fn linear_search_by<'a, F, T>(vector: &'a Vec<T>, mut f: F) -> Option<usize>
-    where F: FnMut(&'a T) -> bool
-{
-    for i in 0..vector.len() {
-        if f(&vector[i]) {
-            return Some(i);
-        }
-    }
-
-    None
-}
-
-
-fn apply_swaps(knowledge: &mut Knowledge) {
-    //TODO maybe make this return a new type, "FinalKnowledge"?
-
-    //TODO Do wwe need doppel swapping? There will only ever be one and it will
-    //happen before a seer happens.
-    if let Some((robber, target, previous_role)) = knowledge.robber_swap {
-
-        knowledge.role = previous_role;
-        if is_werewolf(previous_role) {
-            knowledge.known_villagers.remove(&robber);
-            knowledge.known_werewolves.insert(robber);
-        } else if knowledge.role == Minion {
-            knowledge.known_villagers.remove(&robber);
-            knowledge.known_minion = Some(robber);
-        } else if knowledge.role == Tanner {
-            knowledge.known_villagers.remove(&robber);
-            knowledge.known_tanner = Some(robber);
-        };
-
-        knowledge.known_werewolves.remove(&target);
-        knowledge.known_villagers.insert(target);
-    }
-
-    if let Some((target1, target2)) = knowledge.troublemaker_swap {
-        swap_team_if_known(knowledge, target1);
-        swap_team_if_known(knowledge, target2);
-    }
-
-    //TODO Do we need Drunk swapping (or any swapping?!) if the cpus never trusst anyone?
-}
-
-fn swap_team_if_known(knowledge: &mut Knowledge, participant: Participant) {
-    if knowledge.known_werewolves.contains(&participant) {
-        knowledge.known_villagers.insert(participant);
-        knowledge.known_werewolves.remove(&participant);
-    } else if knowledge.known_villagers.contains(&participant) {
-        knowledge.known_werewolves.insert(participant);
-        knowledge.known_villagers.remove(&participant);
-    }
-}
-
-
-fn get_role_pair(state: &State, pair: CenterPair) -> (Role, Role) {
-    let rs = state.table_roles;
-    match pair {
-        FirstSecond => (rs[0], rs[1]),
-        FirstThird => (rs[0], rs[2]),
-        SecondThird => (rs[1], rs[2]),
-    }
-}
-
 const MAX_CLAIM_HEIGHT: i32 = 4;
 
-fn get_knowledge(state: &State, participant: Participant) -> Option<&Knowledge> {
-    match participant {
-        Player => Some(&state.player_knowledge),
-        Cpu(index) => state.cpu_knowledge.get(index),
-    }
-}
-fn get_knowledge_mut(state: &mut State, participant: Participant) -> Option<&mut Knowledge> {
-    match participant {
-        Player => Some(&mut state.player_knowledge),
-        Cpu(index) => state.cpu_knowledge.get_mut(index),
-    }
-}
-fn get_knowledge_copy(state: &State, participant: Participant) -> Option<Knowledge> {
-    get_knowledge(state, participant).map(|k| k.clone())
-}
-
-
-fn make_cpu_claim(state: &mut State, participant: Participant) {
-    if participant == Player {
-        return;
-    }
-
-    //the copy is needed so we can get random numbers below
-    let possible_knowledge = get_knowledge_copy(state, participant);
-
-    let possible_claim = if let Some(knowledge) = possible_knowledge {
-        let claim = if is_werewolf(knowledge.role) {
-            //TODO better lying
-            //equal probability of all plausible possibilities?
-            attempt_not_to_be_picked(state, participant)
-        } else if is_minion(knowledge.role) {
-            if knowledge.known_werewolves.len() > 0 {
-                //TODO try to cover for Werewolves and not specifically try to get picked?
-                attempt_to_be_picked(state, participant)
-            } else {
-                //TODO try to get another player voted for
-                Simple(Villager)
-            }
-        } else if is_tanner(knowledge.role) {
-            attempt_to_be_picked(state, participant)
-        } else {
-            //TODO occasionally lying while a villager to try and snuff out werewolves
-            knowledge.true_claim
-        };
-
-        Some(claim)
-    } else {
-        None
-    };
-
-    if let Some(claim) = possible_claim {
-        insert_claim(state, participant, claim);
-    }
-}
-
-fn plausable_lie(state: &mut State, participant: Participant) -> Option<Claim> {
-    let other_participants = get_other_participants(state, participant);
-
-    let role_vec: Vec<Role> = state.role_spec.get_role_vector();
-
-    let rng = &mut state.rng;
-    //TODO more types of lies
-    get_fake_robber_claim(&other_participants, &role_vec, rng).or_else(|| {
-        get_fake_insomniac_claim(&role_vec, rng)
-    })
-}
-
-//TODO should this and attempt_not_to_be_picked be so similar?
-//maybe attempt_to_be_picked should look at prior claims and try to create confusion
-//and the other one should try to reduce it?
-fn attempt_to_be_picked(state: &mut State, participant: Participant) -> Claim {
-    plausable_lie(state, participant).unwrap_or(Simple(Werewolf))
-}
-
-fn attempt_not_to_be_picked(state: &mut State, participant: Participant) -> Claim {
-    plausable_lie(state, participant).unwrap_or(Simple(Villager))
-}
-
-fn get_fake_robber_claim<R: Rng>(other_participants: &Vec<Participant>,
-                                 role_vec: &Vec<Role>,
-                                 rng: &mut R)
-                                 -> Option<Claim> {
-    if !role_vec.contains(&Robber) {
-        return None;
-    }
-
-    let filtered = role_vec.iter()
-        .filter(|&&r| is_on_village_team(r) && !is_mason(r) && r != Robber)
-        .map(|&r| r)
-        .collect();
-
-    if let (Some(&target), Some(&claimed_role)) =
-        (get_random(&other_participants, rng), get_random(&filtered, rng)) {
-        Some(RobberAction(target, claimed_role))
-    } else {
-        None
-    }
-}
-
-fn get_fake_insomniac_claim<R: Rng>(role_vec: &Vec<Role>, rng: &mut R) -> Option<Claim> {
-    if !role_vec.contains(&Insomniac) {
-        return None;
-    }
-
-    if role_vec.contains(&Troublemaker) {
-        let filtered = if role_vec.contains(&Robber) {
-            role_vec.iter()
-                .filter(|&&r| is_on_village_team(r) && !is_mason(r))
-                .map(|&r| r)
-                .collect()
-        } else {
-            role_vec.iter()
-                .filter(|&&r| is_on_village_team(r) && !is_mason(r) && r != Troublemaker)
-                .map(|&r| r)
-                .collect()
-
-        };
-
-        if let Some(&claimed_role) = get_random(&filtered, rng) {
-            Some(InsomniacAction(claimed_role))
-        } else {
-            //TODO cnfirm that expansions make a none case (and thus the return type) necessary
-            None
-        }
-    } else if role_vec.contains(&Robber) {
-        Some(InsomniacAction(Robber))
-    } else {
-        Some(InsomniacAction(Insomniac))
-    }
-}
-
-fn get_random<'a, T, R: Rng>(things: &'a Vec<T>, rng: &mut R) -> Option<&'a T> {
-    let len = things.len();
-
-    if len > 0 {
-        things.get(rng.gen_range(0, len)).map(|t| t)
-    } else {
-        None
-    }
-}
-
 enum ClaimOrSilence {
     // ActualClaim(Claim),
     Silence,
@@ -1970,34 +1351,105 @@ fn get_player_claim_or_silence(platform: &Platform,
     //TODO allow player to make claim
     None
 }
-fn insert_claim(state: &mut State, participant: Participant, claim: Claim) {
-    //TODO update cpu_knowledge
-    //for example, if someone claims to be the seer and their claim about
-    //who someone is matches what you know, then most likely they are the
-    //seer, and didn't just guess luckily
+//The discussion so far, a round at a time, in the order things were said.
+fn transcript_to_lines(state: &State) -> Vec<String> {
+    let mut result = Vec::new();
+    let mut round = 0;
 
-    state.claims.insert(participant, claim);
-}
-fn get_claim_vec(state: &State) -> Vec<(Participant, Claim)> {
-    let mut result: Vec<(Participant, Claim)> = state.claims
-        .iter()
-        .map(|(&p, &c)| (p, c))
-        .collect();
+    for statement in state.transcript.iter() {
+        if statement.round != round {
+            round = statement.round;
 
-    result.sort();
+            result.push(format!("Round {}", round));
+            result.push("".to_owned());
+        }
+
+        push_statement_lines(state, &mut result, statement);
+
+        result.push("".to_owned());
+    }
 
     result
 }
 
-fn claims_to_lines(state: &State, claims: Vec<(Participant, Claim)>) -> Vec<String> {
-    let mut result = Vec::new();
+fn push_statement_lines(state: &State, result: &mut Vec<String>, statement: &Statement) {
+    let speaker = statement.speaker;
+    let (think, say) = if speaker == Player {
+        ("think", "say")
+    } else {
+        ("thinks", "says")
+    };
 
-    for pair in claims.iter() {
-        push_claim_lines(state, &mut result, pair);
-        result.push("".to_owned());
+    match statement.kind {
+        StatementKind::Opening(claim) => {
+            push_claim_lines(state, result, &(speaker, claim));
+        }
+        StatementKind::Changed(claim) => {
+            result.push(format!("{} changes their story:", speaker));
+            push_claim_lines(state, result, &(speaker, claim));
+        }
+        StatementKind::Countered(other) => {
+            result.push(format!("{} sticks to their story and says {} {} lying.",
+                                speaker,
+                                object_str(other),
+                                is_str(other)));
+        }
+        StatementKind::Confessed(claim) => {
+            result.push(format!("{} comes clean:", speaker));
+            push_claim_lines(state, result, &(speaker, claim));
+        }
+        StatementKind::Accused(other) => {
+            result.push(format!("{} {} {} {} {}.",
+                                speaker,
+                                think,
+                                object_str(other),
+                                is_str(other),
+                                Werewolf));
+        }
+        StatementKind::Believed(other) => {
+            let verb = if speaker == Player { "believe" } else { "believes" };
+            result.push(format!("{} {} {} claim.", speaker, verb, possessive_str(other)));
+        }
+        StatementKind::VotingFor(other) => {
+            let verb = if speaker == Player { "are" } else { "is" };
+            result.push(format!("{} {} voting for {}.", speaker, verb, object_str(other)));
+        }
+        StatementKind::Reasoned(other, role, werewolf) => {
+            result.push(format!("{} {} that if {} {} the {:o},",
+                                speaker,
+                                say,
+                                object_str(other),
+                                is_str(other),
+                                role));
+            result.push(format!("then {} {} {} now.",
+                                object_str(werewolf),
+                                is_str(werewolf),
+                                Werewolf));
+        }
+    }
+}
+
+//how to refer to someone partway through a sentence
+fn object_str(participant: Participant) -> String {
+    match participant {
+        Player => "you".to_owned(),
+        Cpu(_) => participant.to_string(),
     }
+}
 
-    result
+fn possessive_str(participant: Participant) -> String {
+    match participant {
+        Player => "your".to_owned(),
+        Cpu(_) => format!("{}'s", participant),
+    }
+}
+
+fn is_str(participant: Participant) -> &'static str {
+    if participant == Player { "are" } else { "is" }
+}
+
+fn was_str(participant: Participant) -> &'static str {
+    if participant == Player { "were" } else { "was" }
 }
 
 fn push_claim_lines(state: &State,
@@ -2215,17 +1667,6 @@ fn get_known_werevolves_str_list(state: &State, participant: Participant) -> Opt
    })
 }
 
-fn make_remaining_claims(state: &mut State) {
-    let mut cpu_participants = get_other_participants(state, Player);
-    state.rng.shuffle(&mut cpu_participants);
-    for &participant in cpu_participants.iter() {
-        if !state.claims.contains_key(&participant) {
-            make_cpu_claim(state, participant);
-        }
-    }
-
-}
-
 use std::fmt::Write;
 fn str_list<T: std::fmt::Display>(things: &Vec<T>) -> String {
     let len = things.len();
@@ -2250,117 +1691,6 @@ fn str_list<T: std::fmt::Display>(things: &Vec<T>) -> String {
     }
 }
 
-fn count_votes(votes: &Vec<Participant>) -> Vec<Participant> {
-    let mut counts = HashMap::new();
-
-    for &vote in votes.iter() {
-        let counter = counts.entry(vote).or_insert(0);
-        *counter += 1;
-    }
-
-    let max_count = counts.values()
-        .max()
-        .map(|&c| c)
-        .unwrap_or(0);
-
-    if max_count > 1 {
-        counts.iter()
-            .filter(|&(_, &count)| count == max_count)
-            .map(|(&p, _)| p)
-            .collect()
-    } else {
-        Vec::new()
-    }
-}
-
-fn is_werewolf(role: Role) -> bool {
-    match role {
-        Werewolf |
-        DoppelWerewolf(_) => true,
-        _ => false,
-    }
-}
-fn is_mason(role: Role) -> bool {
-    match role {
-        Mason | DoppelMason(_) => true,
-        _ => false,
-    }
-}
-fn is_minion(role: Role) -> bool {
-    match role {
-        Minion | DoppelMinion(_) => true,
-        _ => false,
-    }
-}
-fn is_tanner(role: Role) -> bool {
-    match role {
-        Tanner | DoppelTanner(_) => true,
-        _ => false,
-    }
-}
-fn is_on_village_team(role: Role) -> bool {
-    !(is_werewolf(role) || is_minion(role) || is_tanner(role))
-}
-
-
-fn swap_roles(state: &mut State, p1: Participant, p2: Participant) {
-    unsafe {
-        let ptr1 = get_role_ptr(state, p1);
-        let ptr2 = get_role_ptr(state, p2);
-
-        std::ptr::swap(ptr1, ptr2);
-    }
-}
-
-unsafe fn get_role_ptr(state: &mut State, p: Participant) -> *mut Role {
-    match p {
-        Player => &mut state.player,
-        Cpu(i) => &mut state.cpu_roles[i],
-    }
-}
-
-fn swap_role_with_center(state: &mut State, p: Participant, center_card: CenterCard) {
-    unsafe {
-        let role_ptr = get_role_ptr(state, p);
-        let center_ptr = get_center_role_ptr(state, center_card);
-
-        std::ptr::swap(role_ptr, center_ptr);
-    }
-}
-
-unsafe fn get_center_role_ptr(state: &mut State, center_card: CenterCard) -> *mut Role {
-    match center_card {
-        First => &mut state.cpu_roles[0],
-        Second => &mut state.cpu_roles[1],
-        Third => &mut state.cpu_roles[2],
-    }
-}
-
-fn get_other_participants(state: &State, participant: Participant) -> Vec<Participant> {
-    get_participants(state)
-        .iter()
-        .map(|&p| p)
-        .filter(|&p| p != participant)
-        .collect()
-}
-
-fn get_role(state: &State, participant: Participant) -> Option<Role> {
-    match participant {
-        Player => Some(state.player),
-        Cpu(i) => state.cpu_roles.get(i).map(|&r| r),
-    }
-}
-
-fn get_participants(state: &State) -> Vec<Participant> {
-    let mut result = vec![Player];
-
-    for i in 0..state.cpu_roles.len() {
-        result.push(Cpu(i));
-    }
-
-    result
-}
-
 fn pick_cpu_player_or_skip(platform: &Platform,
                            state: &mut State,
                            left_mouse_pressed: bool,
@@ -2492,102 +1822,6 @@ fn pick_displayable<T: Display + Copy>(platform: &Platform,
 }
 
 
-fn get_cpu_participants(state: &State) -> Vec<Participant> {
-    let mut result = Vec::new();
-
-    for i in 0..state.cpu_roles.len() {
-        result.push(Cpu(i));
-    }
-
-    result
-}
-
-fn get_vote(participant: Participant,
-            participants: Vec<Participant>,
-            knowledge: &Knowledge,
-            rng: &mut StdRng)
-            -> Participant {
-    //TODO count the number of valid ways that a given set of claims being true would be consistent
-    //given known facts and assume those claims are true
-    let filtered: Vec<Participant> = if is_werewolf(knowledge.role) || knowledge.role == Minion {
-        let mut vec: Vec<Participant> = knowledge.known_villagers
-            .iter()
-            .map(|&p| p)
-            .collect();
-        vec.sort();
-        if let Some(&villager) = rng.choose(&vec) {
-            return villager;
-        }
-
-        participants.iter()
-            .filter(|p| **p != participant && !knowledge.known_werewolves.contains(p))
-            .map(|&p| p)
-            .collect()
-
-
-    } else {
-        let mut vec: Vec<Participant> = knowledge.known_werewolves
-            .iter()
-            .map(|&p| p)
-            .collect();
-        vec.sort();
-        if let Some(&werewolf) = rng.choose(&vec) {
-            return werewolf;
-        }
-
-        participants.iter()
-            .filter(|p| **p != participant && !knowledge.known_villagers.contains(p))
-            .map(|&p| p)
-            .collect()
-    };
-
-    if let Some(&p) = rng.choose(&filtered) {
-        return p;
-    }
-
-    //TODO do process of elimination with known_non_active. (can this happen earlier?)
-
-    //vote clockwise
-    println!("clockwise : {}", participant);
-    *(match participant {
-              Player => participants.get(0),
-              Cpu(i) => participants.get(i + 1),
-          })
-         .unwrap_or(&Player)
-}
-
-fn get_werewolves(state: &State) -> Vec<Participant> {
-    let mut result = Vec::new();
-
-    if is_werewolf(state.player) {
-        result.push(Player);
-    }
-
-    for i in 0..state.cpu_roles.len() {
-        if is_werewolf(state.cpu_roles[i]) {
-            result.push(Cpu(i));
-        }
-    }
-
-    result
-}
-
-fn get_masons(state: &State) -> Vec<Participant> {
-    let mut result = Vec::new();
-
-    if is_mason(state.player) {
-        result.push(Player);
-    }
-
-    for i in 0..state.cpu_roles.len() {
-        if is_mason(state.cpu_roles[i]) {
-            result.push(Cpu(i));
-        }
-    }
-
-    result
-}
-
 fn ready_button(platform: &Platform,
                 state: &mut State,
                 left_mouse_pressed: bool,
@@ -2615,7 +1849,11 @@ fn cross_mode_event_handling(platform: &Platform, state: &mut State, event: &Eve
     match *event {
         Event::KeyPressed { key: KeyCode::R, ctrl: true, shift: _ } => {
             println!("reset");
-            *state = new_state((platform.size)());
+            save_if_playing(state);
+
+            let save_error = state.save_error.take();
+            *state = new_state((platform.size)(), None);
+            state.save_error = save_error;
         }
         _ => (),
     }
@@ -0,0 +1,202 @@
+//Works out who died and who won once the votes are in. Nothing here draws
+//anything, the Resolution screen just displays a `GameOutcome`.
+
+use common::*;
+use common::Role::*;
+
+use std::collections::HashMap;
+
+use engine::{get_participants, get_role, get_werewolves, is_werewolf, is_minion, is_tanner};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Winner {
+    Village,
+    //this includes a Minion winning without any werewolves
+    Werewolf,
+    //each dead Tanner (or DoppelTanner) wins on their own
+    Tanner(Participant),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ParticipantResult {
+    pub participant: Participant,
+    //the card they had at the end of the night
+    pub role: Card,
+    pub won: bool,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct GameOutcome {
+    pub votes: Vec<(Participant, Participant)>,
+    //how many votes each participant received, for those that received any
+    pub tallies: Vec<(Participant, u32)>,
+    pub dead: Vec<Participant>,
+    //(hunter, target) in the order the shots were taken
+    pub hunter_shots: Vec<(Participant, Participant)>,
+    pub werewolves: Vec<Participant>,
+    pub dead_werewolf_count: usize,
+    pub winners: Vec<Winner>,
+    pub results: Vec<ParticipantResult>,
+}
+
+impl GameOutcome {
+    pub fn team_won(&self, team: Winner) -> bool {
+        self.winners.contains(&team)
+    }
+
+    pub fn dead_tanners(&self) -> Vec<Participant> {
+        self.winners
+            .iter()
+            .filter_map(|&t| match t {
+                Winner::Tanner(p) => Some(p),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn won(&self, participant: Participant) -> bool {
+        self.results
+            .iter()
+            .find(|r| r.participant == participant)
+            .map(|r| r.won)
+            .unwrap_or(false)
+    }
+}
+
+pub fn resolve(state: &State) -> GameOutcome {
+    let just_votes: Vec<Participant> = state.votes
+        .iter()
+        .map(|&(_, v)| v)
+        .collect();
+
+    let mut dead = count_votes(&just_votes);
+    dead.sort();
+
+    let mut hunter_shots = Vec::new();
+
+    //a Hunter shot by another Hunter still gets to shoot, so keep going
+    //until nobody new dies.
+    let mut i = 0;
+    while i < dead.len() {
+        let possible_hunter = dead[i];
+
+        if get_role(state, possible_hunter).map(is_hunter).unwrap_or(false) {
+            if let Some(&(_, target)) = state.votes
+                .iter()
+                .find(|&&(voter, _)| voter == possible_hunter) {
+                if !dead.contains(&target) {
+                    dead.push(target);
+                    hunter_shots.push((possible_hunter, target));
+                }
+            }
+        }
+
+        i += 1;
+    }
+
+    dead.sort();
+
+    let werewolves = get_werewolves(state);
+
+    let dead_werewolf_count = dead.iter()
+        .filter_map(|&p| get_role(state, p))
+        .filter(|&r| is_werewolf(r))
+        .count();
+
+    let dead_tanners: Vec<Participant> = dead.iter()
+        .cloned()
+        .filter(|&p| get_role(state, p).map(is_tanner).unwrap_or(false))
+        .collect();
+
+    let minion_present = get_participants(state)
+        .iter()
+        .filter_map(|&p| get_role(state, p))
+        .any(is_minion);
+
+    let mut winners = Vec::new();
+
+    if dead.is_empty() {
+        if werewolves.is_empty() {
+            winners.push(Winner::Village);
+        } else {
+            winners.push(Winner::Werewolf);
+        }
+    } else if dead_werewolf_count >= 1 {
+        winners.push(Winner::Village);
+    } else if !werewolves.is_empty() {
+        if dead_tanners.is_empty() {
+            winners.push(Winner::Werewolf);
+        }
+    } else if minion_present {
+        winners.push(Winner::Werewolf);
+    }
+
+    winners.extend(dead_tanners.iter().map(|&p| Winner::Tanner(p)));
+
+    let results = get_participants(state)
+        .iter()
+        .filter_map(|&participant| {
+            get_role(state, participant).map(|role| {
+                let won = match role.def().team {
+                    Team::Tanner => winners.contains(&Winner::Tanner(participant)),
+                    Team::Werewolf => winners.contains(&Winner::Werewolf),
+                    Team::Village => winners.contains(&Winner::Village),
+                };
+
+                ParticipantResult {
+                    participant,
+                    role,
+                    won,
+                }
+            })
+        })
+        .collect();
+
+    GameOutcome {
+        votes: state.votes.clone(),
+        tallies: tally_votes(&just_votes),
+        dead,
+        hunter_shots,
+        werewolves,
+        dead_werewolf_count,
+        winners,
+        results,
+    }
+}
+
+fn is_hunter(role: Card) -> bool {
+    role.role() == Hunter
+}
+
+fn tally_votes(votes: &[Participant]) -> Vec<(Participant, u32)> {
+    let mut counts = HashMap::new();
+
+    for &vote in votes.iter() {
+        let counter = counts.entry(vote).or_insert(0);
+        *counter += 1;
+    }
+
+    let mut result: Vec<(Participant, u32)> = counts.into_iter().collect();
+
+    result.sort();
+
+    result
+}
+
+pub fn count_votes(votes: &[Participant]) -> Vec<Participant> {
+    let tallies = tally_votes(votes);
+
+    let max_count = tallies.iter()
+        .map(|&(_, c)| c)
+        .max()
+        .unwrap_or(0);
+
+    if max_count > 1 {
+        tallies.iter()
+            .filter(|&&(_, count)| count == max_count)
+            .map(|&(p, _)| p)
+            .collect()
+    } else {
+        Vec::new()
+    }
+}
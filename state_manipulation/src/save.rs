@@ -0,0 +1,324 @@
+//Saving the whole `State` to a file so a game can be picked up again later.
+//The file is json with a version number next to the state, so a save from
+//an older build can be migrated instead of being misread. This is also how
+//the state survives the library being reloaded, see `ffi`.
+
+use common::*;
+
+use serde_json;
+use serde_json::{Map, Value};
+
+use std::fs::{self, File};
+use std::io;
+use std::fmt;
+
+pub const SAVE_VERSION: u32 = 8;
+
+pub const SAVE_PATH: &str = "./one_night_save.json";
+
+#[derive(Serialize)]
+struct SaveFileRef<'a> {
+    version: u32,
+    state: &'a State,
+}
+
+#[derive(Deserialize)]
+struct SaveFile {
+    version: u32,
+    state: Value,
+}
+
+//`MIGRATIONS[i]` turns the json of a version `i + 1` state into the json of a
+//version `i + 2` state. When `State` (or anything in it) changes shape, bump
+//`SAVE_VERSION` and add a function here that makes the old json fit.
+const MIGRATIONS: &[fn(Value) -> Value] = &[add_claim_beliefs,
+                                            add_cpu_profiles,
+                                            add_bluffs,
+                                            add_transcript,
+                                            add_opinions,
+                                            add_vote_reasons,
+                                            add_heard_faces];
+
+//version 2 added what each participant makes of everyone's claims
+fn add_claim_beliefs(state: Value) -> Value {
+    let mut state = state;
+
+    for key in ["player_knowledge", "cpu_knowledge"].iter() {
+        let knowledges = match state.get_mut(*key) {
+            Some(&mut Value::Array(ref mut knowledges)) => knowledges.iter_mut().collect(),
+            Some(knowledge) => vec![knowledge],
+            None => Vec::new(),
+        };
+
+        for knowledge in knowledges {
+            if let Some(object) = knowledge.as_object_mut() {
+                for field in ["trusted", "liars", "contested"].iter() {
+                    object.insert(field.to_string(), Value::Array(Vec::new()));
+                }
+            }
+        }
+    }
+
+    state
+}
+
+//version 3 added `CpuProfile`s. The cpu players in a game that was already
+//going carry on the way they were, see `engine::get_profile`.
+fn add_cpu_profiles(state: Value) -> Value {
+    let mut state = state;
+
+    if let Some(object) = state.as_object_mut() {
+        object.insert("profile_picks".to_owned(),
+                      Value::Array(vec![Value::Null; MAXIMUM_CPU_PLAYERS as usize]));
+        object.insert("cpu_profiles".to_owned(), Value::Array(Vec::new()));
+    }
+
+    state
+}
+
+//version 4 added `bluffs`. Nobody could bluff on the village team before.
+fn add_bluffs(state: Value) -> Value {
+    let mut state = state;
+
+    if let Some(object) = state.as_object_mut() {
+        object.insert("bluffs".to_owned(), Value::Array(Vec::new()));
+    }
+
+    state
+}
+
+//version 5 replaced `bluffs` with the `transcript`. Everything in a version 4
+//discussion was said in the first round, except for anyone coming clean.
+fn add_transcript(state: Value) -> Value {
+    let mut state = state;
+
+    if let Some(object) = state.as_object_mut() {
+        let bluffs = object.remove("bluffs").unwrap_or(Value::Array(Vec::new()));
+        let bluffs = bluffs.as_array().cloned().unwrap_or_default();
+        let claims = object.get("claims").and_then(|c| c.as_array()).cloned().unwrap_or_default();
+
+        let statement = |round: u64, pair: &Value, claim: &Value, kind: &str| {
+            let mut statement = Map::new();
+            statement.insert("round".to_owned(), Value::from(round));
+            statement.insert("speaker".to_owned(), pair[0].clone());
+            statement.insert("kind".to_owned(), Value::from(kind));
+            statement.insert("claim".to_owned(), claim.clone());
+
+            Value::Object(statement)
+        };
+
+        let mut transcript = Vec::new();
+        let mut came_clean = Vec::new();
+
+        for pair in claims.iter() {
+            match bluffs.iter().find(|bluff| bluff[0] == pair[0]) {
+                Some(bluff) => {
+                    transcript.push(statement(1, pair, &bluff[1], "Opening"));
+                    came_clean.push(statement(2, pair, &pair[1], "Confessed"));
+                }
+                None => {
+                    transcript.push(statement(1, pair, &pair[1], "Opening"));
+                }
+            }
+        }
+
+        transcript.extend(came_clean);
+
+        object.insert("transcript".to_owned(), Value::Array(transcript));
+    }
+
+    state
+}
+
+//version 6 let statements be about other people rather than only a claim,
+//so a statement's claim went inside its kind, and everyone got `suspects`.
+fn add_opinions(state: Value) -> Value {
+    let mut state = state;
+
+    if let Some(&mut Value::Array(ref mut transcript)) = state.get_mut("transcript") {
+        for statement in transcript.iter_mut() {
+            if let Some(object) = statement.as_object_mut() {
+                let claim = object.remove("claim").unwrap_or(Value::Null);
+
+                let kind = match object.remove("kind") {
+                    Some(Value::String(name)) => {
+                        let mut kind = Map::new();
+                        kind.insert(name, claim);
+
+                        Value::Object(kind)
+                    }
+                    Some(kind) => kind,
+                    None => Value::Null,
+                };
+
+                object.insert("kind".to_owned(), kind);
+            }
+        }
+    }
+
+    for key in ["player_knowledge", "cpu_knowledge"].iter() {
+        let knowledges = match state.get_mut(*key) {
+            Some(&mut Value::Array(ref mut knowledges)) => knowledges.iter_mut().collect(),
+            Some(knowledge) => vec![knowledge],
+            None => Vec::new(),
+        };
+
+        for knowledge in knowledges {
+            if let Some(object) = knowledge.as_object_mut() {
+                object.insert("suspects".to_owned(), Value::Array(Vec::new()));
+            }
+        }
+    }
+
+    state
+}
+
+//version 7 added why each cpu voted the way they did. Nobody said, before.
+fn add_vote_reasons(state: Value) -> Value {
+    let mut state = state;
+
+    if let Some(object) = state.as_object_mut() {
+        object.insert("vote_reasons".to_owned(), Value::Array(Vec::new()));
+    }
+
+    state
+}
+
+//version 8 kept what cpu players heard other people saw apart from what
+//they saw themselves. Anything heard before then stays mixed in.
+fn add_heard_faces(state: Value) -> Value {
+    let mut state = state;
+
+    for key in ["player_knowledge", "cpu_knowledge"].iter() {
+        let knowledges = match state.get_mut(*key) {
+            Some(&mut Value::Array(ref mut knowledges)) => knowledges.iter_mut().collect(),
+            Some(knowledge) => vec![knowledge],
+            None => Vec::new(),
+        };
+
+        for knowledge in knowledges {
+            if let Some(object) = knowledge.as_object_mut() {
+                for field in ["heard_werewolves", "heard_villagers"].iter() {
+                    object.insert(field.to_string(), Value::Array(Vec::new()));
+                }
+            }
+        }
+    }
+
+    state
+}
+
+#[derive(Debug)]
+pub enum SaveError {
+    Io(io::Error),
+    Format(serde_json::Error),
+    //the file was written by a newer build, or one too old to migrate
+    Version(u32),
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SaveError::Io(ref e) => write!(f, "{}", e),
+            SaveError::Format(ref e) => write!(f, "bad save file: {}", e),
+            SaveError::Version(v) => {
+                write!(f,
+                       "save file is version {}, which can't be turned into version {}",
+                       v,
+                       SAVE_VERSION)
+            }
+        }
+    }
+}
+
+impl From<io::Error> for SaveError {
+    fn from(e: io::Error) -> Self {
+        SaveError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for SaveError {
+    fn from(e: serde_json::Error) -> Self {
+        SaveError::Format(e)
+    }
+}
+
+pub fn to_string(state: &State) -> Result<String, SaveError> {
+    let save_file = SaveFileRef {
+        version: SAVE_VERSION,
+        state,
+    };
+
+    Ok(serde_json::to_string(&save_file)?)
+}
+
+pub fn from_str(s: &str) -> Result<State, SaveError> {
+    //the version is checked before the state is parsed, since an older
+    //state probably won't parse at all.
+    let save_file: SaveFile = serde_json::from_str(s)?;
+
+    let state = migrate(save_file.version, save_file.state)?;
+
+    Ok(serde_json::from_value(state)?)
+}
+
+fn migrate(version: u32, state: Value) -> Result<Value, SaveError> {
+    if version == 0 || version > SAVE_VERSION {
+        return Err(SaveError::Version(version));
+    }
+
+    let mut state = state;
+
+    for migration in MIGRATIONS.iter().skip(version as usize - 1) {
+        state = migration(state);
+    }
+
+    Ok(state)
+}
+
+pub fn save(state: &State, path: &str) -> Result<(), SaveError> {
+    use std::io::Write;
+
+    let s = to_string(state)?;
+
+    let mut file = File::create(path)?;
+    file.write_all(s.as_bytes())?;
+
+    Ok(())
+}
+
+pub fn load(path: &str) -> Result<State, SaveError> {
+    let s = fs::read_to_string(path)?;
+
+    from_str(&s)
+}
+
+//Loads a game and deletes the save, so the same game can't be picked up
+//twice. It's saved again if it's left before it's over.
+pub fn take(path: &str) -> Result<State, SaveError> {
+    let state = load(path)?;
+
+    delete(path)?;
+
+    Ok(state)
+}
+
+//Saves a game that's still going. Before a game starts or once it's over
+//there's nothing to pick up again, so the save is deleted instead.
+pub fn save_or_delete(state: &State, path: &str) -> Result<(), SaveError> {
+    match state.turn {
+        Turn::Ready | Turn::Resolution => delete(path),
+        _ => save(state, path),
+    }
+}
+
+pub fn delete(path: &str) -> Result<(), SaveError> {
+    match fs::remove_file(path) {
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        result => Ok(result?),
+    }
+}
+
+pub fn exists(path: &str) -> bool {
+    fs::metadata(path).map(|m| m.is_file()).unwrap_or(false)
+}
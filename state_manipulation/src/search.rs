@@ -0,0 +1,546 @@
+//Cpu seats that look ahead before choosing, the `Hard` profile. For each
+//choice the seat makes up deals that fit what it knows, see
+//`deduction::sample_deals`, and plays each of them out to the end of the vote
+//once per option, with the engine's own rules and every other seat playing
+//by its profile. Then it goes with the option it won with most often, on
+//whichever team it ended up on. Every option is played on the same deals with
+//the same random numbers, so luck doesn't favour any of them.
+//
+//The search stops after `playouts` games or once `budget` has run out,
+//whichever comes first, so on a slow enough machine the same seed can go
+//differently.
+
+use common::*;
+use common::Role::*;
+use common::Turn::*;
+use common::Participant::*;
+use common::Claim::*;
+use common::CenterPair::*;
+use common::CenterCard::*;
+
+use rand::Rng;
+use std::cmp::Ordering;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use cpu;
+use cpu::ProfileStrategy;
+use deduction;
+use engine;
+use engine::{get_knowledge_copy, get_profile, get_participants, get_other_participants,
+             is_on_village_team, is_tanner};
+use outcome;
+
+pub const DEFAULT_BUDGET: Duration = Duration::from_millis(200);
+pub const DEFAULT_PLAYOUTS: usize = 2000;
+
+//how many made up deals there are to pick from
+const DEAL_POOL: usize = 40;
+//how many lies to try besides the claim the profile falls back on
+const LIE_OPTIONS: usize = 4;
+//a game that goes on longer than this has gone wrong somewhere
+const MAX_TURNS: usize = 200;
+
+//made up deals in seat order, see `deduction::sample_deals`, with how likely
+//each one is
+type Deals = Vec<(Vec<Card>, f64)>;
+
+pub struct SearchStrategy {
+    //how long each choice can take, checked between rounds of playouts
+    pub budget: Duration,
+    //the most games to play out for each choice
+    pub playouts: usize,
+}
+
+impl Default for SearchStrategy {
+    fn default() -> SearchStrategy {
+        SearchStrategy {
+            budget: DEFAULT_BUDGET,
+            playouts: DEFAULT_PLAYOUTS,
+        }
+    }
+}
+
+impl SearchStrategy {
+    //The option that won the most playouts, or `None` if there wasn't time
+    //to play any or no deal fits what the seat knows.
+    fn best<T: Copy>(&self,
+                     state: &mut State,
+                     seat: Participant,
+                     options: Vec<T>,
+                     deals: fn(&State, Participant, usize) -> Deals,
+                     try_option: fn(&mut Script, T))
+                     -> Option<T> {
+        if options.len() <= 1 {
+            return options.first().cloned();
+        }
+
+        //the player's seat is played by `cpu::player_action`, which makes
+        //the Troublemaker's second pick itself, so a script can't steer it
+        if seat == Player {
+            return None;
+        }
+
+        let started = Instant::now();
+        let mut rng = GameRng::new(state.rng.gen::<usize>());
+
+        let pool = deals(state, seat, rng.gen::<usize>());
+        let weights: Vec<(usize, f64)> = pool.iter()
+            .enumerate()
+            .map(|(i, &(_, weight))| (i, weight))
+            .collect();
+
+        let past = Script::from_state(state, seat);
+
+        let mut wins = vec![0; options.len()];
+        let mut played = 0;
+
+        while played + options.len() <= self.playouts && started.elapsed() < self.budget {
+            let deal = match cpu::choose_weighted(&weights, &mut rng) {
+                Some(index) => &pool[index].0,
+                None => break,
+            };
+            let seed = rng.gen::<usize>();
+
+            for (i, &option) in options.iter().enumerate() {
+                let mut script = past.clone();
+                try_option(&mut script, option);
+
+                if play_out(state, seat, deal, script, seed) {
+                    wins[i] += 1;
+                }
+            }
+
+            played += options.len();
+        }
+
+        if played == 0 {
+            return None;
+        }
+
+        //the first of the best, so the order of `options` breaks ties
+        let mut best = 0;
+        for i in 1..options.len() {
+            if wins[i] > wins[best] {
+                best = i;
+            }
+        }
+
+        Some(options[best])
+    }
+}
+
+impl CpuStrategy for SearchStrategy {
+    fn copy_target(&self, state: &mut State, doppelganger: Participant) -> Option<Participant> {
+        let options = get_other_participants(state, doppelganger).into_iter().map(Some).collect();
+
+        self.best(state,
+                  doppelganger,
+                  options,
+                  shuffled_deals,
+                  |script, target| script.copy = Some(target))
+            .unwrap_or_else(|| ProfileStrategy.copy_target(state, doppelganger))
+    }
+
+    fn seer_choice(&self, state: &mut State, seer: Participant) -> Option<SeerView> {
+        let mut options: Vec<Option<SeerView>> = get_other_participants(state, seer)
+            .into_iter()
+            .map(|p| Some(SeerView::Player(p)))
+            .collect();
+        options.extend(CenterPair::all_values()
+            .into_iter()
+            .map(|pair| Some(SeerView::Center(pair))));
+        options.push(None);
+
+        self.best(state,
+                  seer,
+                  options,
+                  sampled_deals,
+                  |script, view| script.seer = Some(view))
+            .unwrap_or_else(|| ProfileStrategy.seer_choice(state, seer))
+    }
+
+    fn robber_target(&self, state: &mut State, robber: Participant) -> Option<Participant> {
+        let mut options: Vec<Option<Participant>> =
+            get_other_participants(state, robber).into_iter().map(Some).collect();
+        options.push(None);
+
+        self.best(state,
+                  robber,
+                  options,
+                  sampled_deals,
+                  |script, target| script.robber = Some(target))
+            .unwrap_or_else(|| ProfileStrategy.robber_target(state, robber))
+    }
+
+    fn troublemaker_targets(&self,
+                            state: &mut State,
+                            troublemaker: Participant)
+                            -> Option<(Participant, Participant)> {
+        let others = get_other_participants(state, troublemaker);
+
+        let mut options = Vec::new();
+        for (i, &first) in others.iter().enumerate() {
+            for &second in others[i + 1..].iter() {
+                options.push(Some((first, second)));
+            }
+        }
+        options.push(None);
+
+        self.best(state,
+                  troublemaker,
+                  options,
+                  sampled_deals,
+                  |script, targets| script.troublemaker = Some(targets))
+            .unwrap_or_else(|| ProfileStrategy.troublemaker_targets(state, troublemaker))
+    }
+
+    fn drunk_target(&self, state: &mut State, drunk: Participant) -> CenterCard {
+        self.best(state,
+                  drunk,
+                  CenterCard::all_values(),
+                  sampled_deals,
+                  |script, center| script.drunk = Some(center))
+            .unwrap_or_else(|| ProfileStrategy.drunk_target(state, drunk))
+    }
+
+    fn claim(&self, state: &mut State, participant: Participant) -> Option<Claim> {
+        let options = claim_options(state, participant);
+
+        self.best(state,
+                  participant,
+                  options,
+                  sampled_deals,
+                  |script, claim| script.claim = Some(claim))
+            .unwrap_or_else(|| ProfileStrategy.claim(state, participant))
+    }
+
+    fn respond(&self, state: &mut State, participant: Participant) -> Option<StatementKind> {
+        ProfileStrategy.respond(state, participant)
+    }
+
+    fn vote(&self, state: &mut State, voter: Participant) -> (Participant, VoteReason) {
+        let options = get_other_participants(state, voter);
+
+        self.best(state,
+                  voter,
+                  options,
+                  sampled_deals,
+                  |script, vote| script.vote = Some(vote))
+            .map(|vote| (vote, VoteReason::PlayedOut))
+            .unwrap_or_else(|| ProfileStrategy.vote(state, voter))
+    }
+}
+
+//What the seat's profile might say: the truth on the village team, otherwise
+//the cover story it falls back on, and either way the lies it likes best.
+fn claim_options(state: &State, participant: Participant) -> Vec<Option<Claim>> {
+    let knowledge = match get_knowledge_copy(state, participant) {
+        Some(knowledge) => knowledge,
+        None => return Vec::new(),
+    };
+
+    let mut claims = vec![if is_on_village_team(knowledge.role) {
+                              knowledge.true_claim
+                          } else if is_tanner(knowledge.role) {
+                              Simple(Werewolf)
+                          } else {
+                              Simple(Villager)
+                          }];
+
+    let profile = profile_def(get_profile(state, participant));
+    let mut lies = cpu::get_lies(state, participant, &knowledge, profile);
+    lies.sort_by(|&(_, a), &(_, b)| b.partial_cmp(&a).unwrap_or(Ordering::Equal));
+
+    for (lie, _) in lies.into_iter().take(LIE_OPTIONS) {
+        if !claims.contains(&lie) {
+            claims.push(lie);
+        }
+    }
+
+    claims.into_iter().map(Some).collect()
+}
+
+fn sampled_deals(state: &State, seat: Participant, sample_seed: usize) -> Deals {
+    deduction::sample_deals(state, seat, sample_seed, DEAL_POOL)
+}
+
+//The Doppelganger copies as soon as the cards are dealt, before anyone has
+//seen anything, so one deal is as likely as any other.
+fn shuffled_deals(state: &State, seat: Participant, sample_seed: usize) -> Deals {
+    let mut rng = GameRng::new(sample_seed);
+
+    let mut remaining = state.role_spec.get_role_vector();
+    match remaining.iter().position(|&r| r == Doppelganger) {
+        Some(index) => {
+            remaining.remove(index);
+        }
+        None => return Vec::new(),
+    }
+
+    let seat_index = match seat {
+        Player => 0,
+        Cpu(i) => i + 1,
+    };
+
+    (0..DEAL_POOL)
+        .map(|_| {
+            rng.shuffle(&mut remaining);
+
+            let mut deal: Vec<Card> = remaining.iter().map(|&r| Card::Plain(r)).collect();
+            deal.insert(seat_index, Card::Plain(Doppelganger));
+
+            (deal, 1.0)
+        })
+        .collect()
+}
+
+//What the searching seat does in a playout, where that's already decided.
+//Anything else is left to its profile.
+#[derive(Clone, Default)]
+struct Script {
+    copy: Option<Option<Participant>>,
+    seer: Option<Option<SeerView>>,
+    robber: Option<Option<Participant>>,
+    troublemaker: Option<Option<(Participant, Participant)>>,
+    drunk: Option<CenterCard>,
+    claim: Option<Option<Claim>>,
+    vote: Option<Participant>,
+    //the steps of the night that are already over, so whatever the seat
+    //didn't do in them it didn't do at all
+    done: Vec<NightStep>,
+}
+
+impl Script {
+    //what `seat` has done so far in the real game
+    fn from_state(state: &State, seat: Participant) -> Script {
+        let mut script = Script::default();
+        let mut centers = Vec::new();
+
+        for event in state.night_log.iter().filter(|e| e.actor() == seat) {
+            match *event {
+                NightEvent::ViewedPlayer { target, .. } => {
+                    script.seer = Some(Some(SeerView::Player(target)));
+                }
+                NightEvent::ViewedCenter { center, .. } => {
+                    centers.push(center);
+                }
+                NightEvent::Robbed { target, .. } => {
+                    script.robber = Some(Some(target));
+                }
+                NightEvent::Swapped { first, second, .. } => {
+                    script.troublemaker = Some(Some((first, second)));
+                }
+                NightEvent::TookCenter { center, .. } => {
+                    script.drunk = Some(center);
+                }
+                _ => {}
+            }
+        }
+
+        if let Some(pair) = center_pair(&centers) {
+            script.seer = Some(Some(SeerView::Center(pair)));
+        }
+
+        let night_over = matches!(state.turn, BeginDiscussion | Discuss | Vote | Resolution);
+
+        script.done = match state.turn.night_step() {
+            Some(step) => state.night_script.iter().cloned().take_while(|&s| s != step).collect(),
+            None if night_over => state.night_script.clone(),
+            None => Vec::new(),
+        };
+
+        //before the discussion, the transcript is the last game's
+        if night_over {
+            script.claim = state.transcript
+                .iter()
+                .filter(|statement| statement.speaker == seat)
+                .filter_map(|statement| match statement.kind {
+                    StatementKind::Opening(claim) => Some(Some(claim)),
+                    _ => None,
+                })
+                .next();
+        }
+
+        script
+    }
+
+    fn over(&self, sim: &State) -> bool {
+        sim.turn.night_step().map(|step| self.done.contains(&step)).unwrap_or(false)
+    }
+}
+
+fn center_pair(centers: &[CenterCard]) -> Option<CenterPair> {
+    match (centers.first(), centers.get(1)) {
+        (Some(&First), Some(&Second)) => Some(FirstSecond),
+        (Some(&First), Some(&Third)) => Some(FirstThird),
+        (Some(&Second), Some(&Third)) => Some(SecondThird),
+        _ => None,
+    }
+}
+
+struct Scripted(Script);
+
+impl CpuStrategy for Scripted {
+    fn copy_target(&self, state: &mut State, doppelganger: Participant) -> Option<Participant> {
+        self.0.copy.unwrap_or_else(|| Playout.copy_target(state, doppelganger))
+    }
+
+    fn seer_choice(&self, state: &mut State, seer: Participant) -> Option<SeerView> {
+        match self.0.seer {
+            Some(view) => view,
+            None if self.0.over(state) => None,
+            None => Playout.seer_choice(state, seer),
+        }
+    }
+
+    fn robber_target(&self, state: &mut State, robber: Participant) -> Option<Participant> {
+        match self.0.robber {
+            Some(target) => target,
+            None if self.0.over(state) => None,
+            None => Playout.robber_target(state, robber),
+        }
+    }
+
+    fn troublemaker_targets(&self,
+                            state: &mut State,
+                            troublemaker: Participant)
+                            -> Option<(Participant, Participant)> {
+        match self.0.troublemaker {
+            Some(targets) => targets,
+            None if self.0.over(state) => None,
+            None => Playout.troublemaker_targets(state, troublemaker),
+        }
+    }
+
+    fn drunk_target(&self, state: &mut State, drunk: Participant) -> CenterCard {
+        self.0.drunk.unwrap_or_else(|| Playout.drunk_target(state, drunk))
+    }
+
+    fn claim(&self, state: &mut State, participant: Participant) -> Option<Claim> {
+        self.0.claim.unwrap_or_else(|| Playout.claim(state, participant))
+    }
+
+    fn respond(&self, state: &mut State, participant: Participant) -> Option<StatementKind> {
+        Playout.respond(state, participant)
+    }
+
+    fn vote(&self, state: &mut State, voter: Participant) -> (Participant, VoteReason) {
+        match self.0.vote {
+            Some(vote) => (vote, VoteReason::Unexplained),
+            None => Playout.vote(state, voter),
+        }
+    }
+}
+
+//How everyone else plays in a playout: by their profile, except that working
+//out the odds for the vote takes far too long to do for every playout.
+struct Playout;
+
+impl CpuStrategy for Playout {
+    fn copy_target(&self, state: &mut State, doppelganger: Participant) -> Option<Participant> {
+        ProfileStrategy.copy_target(state, doppelganger)
+    }
+
+    fn seer_choice(&self, state: &mut State, seer: Participant) -> Option<SeerView> {
+        ProfileStrategy.seer_choice(state, seer)
+    }
+
+    fn robber_target(&self, state: &mut State, robber: Participant) -> Option<Participant> {
+        ProfileStrategy.robber_target(state, robber)
+    }
+
+    fn troublemaker_targets(&self,
+                            state: &mut State,
+                            troublemaker: Participant)
+                            -> Option<(Participant, Participant)> {
+        ProfileStrategy.troublemaker_targets(state, troublemaker)
+    }
+
+    fn drunk_target(&self, state: &mut State, drunk: Participant) -> CenterCard {
+        ProfileStrategy.drunk_target(state, drunk)
+    }
+
+    fn claim(&self, state: &mut State, participant: Participant) -> Option<Claim> {
+        ProfileStrategy.claim(state, participant)
+    }
+
+    fn respond(&self, state: &mut State, participant: Participant) -> Option<StatementKind> {
+        ProfileStrategy.respond(state, participant)
+    }
+
+    fn vote(&self, state: &mut State, voter: Participant) -> (Participant, VoteReason) {
+        let knowledge = get_knowledge_copy(state, voter)
+            .unwrap_or_else(|| state.player_knowledge.clone());
+
+        cpu::get_vote(voter,
+                      get_participants(state),
+                      &knowledge,
+                      None,
+                      &mut state.rng)
+    }
+}
+
+//Plays `deal` out to the end of the vote, returning whether `seat` won.
+fn play_out(state: &State,
+            seat: Participant,
+            deal: &[Card],
+            script: Script,
+            seed: usize)
+            -> bool {
+    let mut sim = engine::make_state(false, seed);
+    sim.role_spec = state.role_spec;
+    sim.profile_picks = state.cpu_profiles.iter().map(|&p| Some(p)).collect();
+
+    for i in 0..deal.len() - 4 {
+        sim.cpu_strategies.insert(i, Rc::new(Playout));
+    }
+    if let Cpu(i) = seat {
+        sim.cpu_strategies.insert(i, Rc::new(Scripted(script)));
+    }
+
+    engine::deal_exactly(&mut sim, dealing_order(deal));
+    engine::advance(&mut sim);
+
+    //once the night is over, the sim says what was really said instead of
+    //what its own cpus came up with, and only the rest is played out
+    let mut discussing = matches!(state.turn, BeginDiscussion | Discuss | Vote);
+
+    for _ in 0..MAX_TURNS {
+        if sim.turn == Resolution {
+            return outcome::resolve(&sim).won(seat);
+        }
+
+        if discussing && sim.turn == Discuss {
+            engine::resume_discussion(&mut sim, &state.transcript, state.turn);
+            discussing = false;
+
+            continue;
+        }
+
+        match cpu::player_action(&mut sim, &Playout) {
+            Some(action) => {
+                if engine::apply(&mut sim, action).is_err() {
+                    return false;
+                }
+            }
+            None => return false,
+        }
+    }
+
+    false
+}
+
+//`engine` deals the cards off the end, so a deal in seat order goes in
+//backwards, with the player's card last.
+fn dealing_order(deal: &[Card]) -> Vec<Card> {
+    let players = deal.len() - 3;
+
+    let mut roles: Vec<Card> = deal[1..players].to_vec();
+    roles.push(deal[players + 2]);
+    roles.push(deal[players + 1]);
+    roles.push(deal[players]);
+    roles.push(deal[0]);
+
+    roles
+}
@@ -0,0 +1,70 @@
+//Checks cpu seats played by another program, using the reference bot.
+
+extern crate common;
+extern crate state_manipulation;
+
+mod support;
+
+use common::*;
+use common::Role::*;
+use common::Turn::*;
+use common::Participant::*;
+
+use state_manipulation::bot::BotStrategy;
+use state_manipulation::engine;
+
+use std::rc::Rc;
+use std::time::Duration;
+
+use support::*;
+
+const REFERENCE_BOT: &str = env!("CARGO_BIN_EXE_reference_bot");
+
+fn robber_in_seat_0() -> State {
+    deal_until(RoleSpec::default(),
+               |state| state.table.cpu_cards()[0] == Card::Plain(Robber))
+}
+
+#[test]
+fn a_bot_plays_its_seat() {
+    let mut state = robber_in_seat_0();
+    state.cpu_strategies.insert(0, Rc::new(BotStrategy::spawn(REFERENCE_BOT).unwrap()));
+    proceed_until(&mut state, Vote);
+
+    //the reference bot always picks the first choice, and the player comes first
+    let robbed = state.initial_table.player();
+    assert!(state.night_log.contains(&NightEvent::Robbed {
+        actor: Cpu(0),
+        target: Player,
+        card: robbed,
+    }));
+    assert_eq!(state.claims[&Cpu(0)], Claim::RobberAction(Player, robbed.face()));
+    assert_eq!(state.transcript.iter().filter(|s| s.speaker == Cpu(0)).count(), 1);
+
+    engine::apply(&mut state, engine::Action::VoteFor(Cpu(1))).unwrap();
+
+    assert!(state.votes.contains(&(Cpu(0), Player)));
+}
+
+#[test]
+fn a_bot_that_doesnt_answer_is_played_by_its_profile() {
+    let mut plain = robber_in_seat_0();
+
+    let mut bot = BotStrategy::spawn(&format!("{} --slow", REFERENCE_BOT)).unwrap();
+    bot.timeout = Duration::from_millis(50);
+
+    let mut slow = robber_in_seat_0();
+    slow.cpu_strategies.insert(0, Rc::new(bot));
+
+    proceed_until(&mut plain, Vote);
+    proceed_until(&mut slow, Vote);
+
+    assert_eq!(plain.night_log, slow.night_log);
+    assert_eq!(plain.transcript, slow.transcript);
+}
+
+#[test]
+fn a_bot_that_cant_be_started_is_an_error() {
+    assert!(BotStrategy::spawn("./there/is/no/bot/here").is_err());
+    assert!(BotStrategy::spawn("").is_err());
+}
@@ -0,0 +1,415 @@
+//Checks what the cpu players make of each other's claims.
+
+extern crate common;
+extern crate state_manipulation;
+
+mod support;
+
+use common::*;
+use common::Role::*;
+use common::Turn::*;
+use common::Claim::*;
+use common::Participant::*;
+use common::ZeroToTwo::*;
+
+use state_manipulation::cpu;
+use state_manipulation::engine;
+
+use support::*;
+
+use std::collections::HashMap;
+
+//Cpu 0 and Cpu 1 are the Werewolves, so they know what each other's cards are.
+fn werewolves_at_discussion() -> State {
+    let mut state = deal_until(no_swapping(), |state| {
+        state.table.cpu_cards()[0] == Card::Plain(Werewolf) &&
+        state.table.cpu_cards()[1] == Card::Plain(Werewolf)
+    });
+    proceed_until(&mut state, Discuss);
+
+    state
+}
+
+#[test]
+fn a_seer_claim_that_matches_is_trusted() {
+    let mut state = werewolves_at_discussion();
+
+    engine::insert_claim(&mut state, Player, SeerRevealOneAction(Cpu(1), Werewolf));
+
+    let knowledge = &state.cpu_knowledge[0];
+    assert!(knowledge.trusted.contains(&Player));
+    assert!(!knowledge.liars.contains(&Player));
+}
+
+#[test]
+fn a_seer_claim_that_doesnt_match_is_a_lie() {
+    let mut state = werewolves_at_discussion();
+
+    engine::insert_claim(&mut state, Player, SeerRevealOneAction(Cpu(1), Villager));
+
+    let knowledge = &state.cpu_knowledge[0];
+    assert!(knowledge.liars.contains(&Player));
+    assert!(!knowledge.trusted.contains(&Player));
+}
+
+#[test]
+fn claiming_to_be_someone_you_were_seen_not_to_be_is_a_lie() {
+    let mut state = werewolves_at_discussion();
+
+    engine::insert_claim(&mut state, Cpu(1), Simple(Villager));
+
+    assert!(state.cpu_knowledge[0].liars.contains(&Cpu(1)));
+}
+
+#[test]
+fn two_seers_means_one_is_lying() {
+    let mut state = deal_until(RoleSpec::default(), |state| {
+        state.table.player() == Card::Plain(Villager) &&
+        state.table.cpu_cards()[0] == Card::Plain(Villager)
+    });
+    proceed_until(&mut state, Discuss);
+    state.claims.clear();
+
+    engine::insert_claim(&mut state, Player, Simple(Seer));
+    engine::insert_claim(&mut state, Cpu(1), Simple(Seer));
+
+    let knowledge = &state.cpu_knowledge[0];
+    assert!(knowledge.contested.contains(&(Player, Cpu(1))));
+    assert!(knowledge.liars.is_empty());
+}
+
+#[test]
+fn claiming_the_listeners_role_is_a_lie() {
+    let mut state = deal_until(RoleSpec::default(),
+                               |state| state.table.cpu_cards()[0] == Card::Plain(Seer));
+    proceed_until(&mut state, Discuss);
+    state.claims.clear();
+
+    engine::insert_claim(&mut state, Player, Simple(Seer));
+
+    assert!(state.cpu_knowledge[0].liars.contains(&Player));
+}
+
+#[test]
+fn a_truthful_werewolf_owns_up() {
+    let mut state = deal_until(no_swapping(),
+                               |state| state.table.cpu_cards()[0] == Card::Plain(Werewolf));
+    state.cpu_profiles[0] = CpuProfile::Truthful;
+    proceed_until(&mut state, Vote);
+
+    assert_eq!(state.claims[&Cpu(0)], Simple(Werewolf));
+}
+
+//The first deals where Cpu 0 and Cpu 1 are the Werewolves, taken up to the
+//vote with both of them playing as `profile`.
+fn werewolf_pairs(profile: CpuProfile) -> Vec<State> {
+    let mut result = Vec::new();
+
+    for seed in 0..2000 {
+        let mut state = engine::make_state(false, seed);
+        state.seed = seed;
+        state.profile_picks[0] = Some(profile);
+        state.profile_picks[1] = Some(profile);
+
+        engine::apply(&mut state, engine::Action::Deal).unwrap();
+
+        if state.table.cpu_cards()[0] == Card::Plain(Werewolf) &&
+           state.table.cpu_cards()[1] == Card::Plain(Werewolf) {
+            proceed_until(&mut state, Vote);
+            result.push(state);
+        }
+
+        if result.len() >= 40 {
+            break;
+        }
+    }
+
+    result
+}
+
+#[test]
+fn werewolves_dont_both_claim_a_role_there_is_one_of() {
+    for state in werewolf_pairs(CpuProfile::Bluffer) {
+        let roles = state.role_spec.get_role_vector();
+        let (first, second) = (state.claims[&Cpu(0)].face(), state.claims[&Cpu(1)].face());
+
+        assert!(first != second || roles.iter().filter(|&&r| r == first).count() > 1,
+                "both claimed {:?} in seed {}",
+                first,
+                state.seed);
+    }
+}
+
+#[test]
+fn werewolves_tell_the_same_story() {
+    let mut named = 0;
+
+    for state in werewolf_pairs(CpuProfile::Cautious) {
+        for &(speaker, other) in [(Cpu(0), Cpu(1)), (Cpu(1), Cpu(0))].iter() {
+            match state.claims[&speaker] {
+                SeerRevealOneAction(target, role) |
+                RobberAction(target, role) if target == other => {
+                    named += 1;
+                    assert_eq!(state.claims[&other].face(), role, "in seed {}", state.seed);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    assert!(named > 0, "the Werewolves never mentioned each other");
+}
+
+//Games where every cpu player is a `Bluffer`, taken up to the vote. The
+//player has to be dealt a card they don't need to pick anything for.
+fn bluffer_discussions() -> Vec<State> {
+    discussions(Some(CpuProfile::Bluffer))
+}
+
+fn discussions(profile: Option<CpuProfile>) -> Vec<State> {
+    (0..400)
+        .filter_map(|seed| {
+            let mut state = engine::make_state(false, seed);
+            state.seed = seed;
+            state.profile_picks = vec![profile; MAXIMUM_CPU_PLAYERS as usize];
+
+            engine::apply(&mut state, engine::Action::Deal).unwrap();
+
+            match state.table.player().role() {
+                Doppelganger | Drunk => None,
+                _ => {
+                    proceed_until(&mut state, Vote);
+
+                    Some(state)
+                }
+            }
+        })
+        .collect()
+}
+
+//going by the card they had when they made their claim
+fn on_the_village_team(state: &State, i: usize) -> bool {
+    role_def(state.cpu_knowledge[i].role.role()).team == Team::Village
+}
+
+#[test]
+fn bluffers_come_clean_with_what_they_know() {
+    let mut came_clean = 0;
+
+    for state in bluffer_discussions() {
+        for statement in state.transcript.iter() {
+            let confession = match statement.kind {
+                StatementKind::Confessed(claim) => claim,
+                _ => continue,
+            };
+            let i = match statement.speaker {
+                Cpu(i) => i,
+                Player => panic!("the player doesn't bluff"),
+            };
+            came_clean += 1;
+
+            let opening = state.transcript
+                .iter()
+                .find(|s| s.speaker == statement.speaker)
+                .and_then(|s| s.claim())
+                .unwrap();
+
+            //a Tanner owns up too, once someone says they took the card
+            assert!(on_the_village_team(&state, i) || state.cpu_knowledge[i].role.role() == Tanner);
+            assert!(opening != confession);
+            assert_eq!(confession, state.cpu_knowledge[i].true_claim);
+        }
+    }
+
+    assert!(came_clean > 0, "nobody ever came clean");
+}
+
+#[test]
+fn a_bluff_lasts_until_someone_claims_the_bluffers_role() {
+    let mut kept_up = 0;
+
+    for state in bluffer_discussions() {
+        for i in 0..state.cpu_knowledge.len() {
+            let true_claim = state.cpu_knowledge[i].true_claim;
+
+            if !on_the_village_team(&state, i) || state.claims[&Cpu(i)] == true_claim {
+                continue;
+            }
+            kept_up += 1;
+
+            for (&p, &claim) in state.claims.iter() {
+                assert!(p == Cpu(i) || claim.face() != true_claim.face(),
+                        "{} claimed to be Cpu {}'s {:?} in seed {}",
+                        p,
+                        i,
+                        true_claim.face(),
+                        state.seed);
+            }
+        }
+    }
+
+    assert!(kept_up > 0, "nobody ever kept up a bluff");
+}
+
+//what a statement says about someone else, if anything
+fn opinion_about(kind: StatementKind) -> Option<Participant> {
+    match kind {
+        StatementKind::Accused(p) |
+        StatementKind::Believed(p) |
+        StatementKind::VotingFor(p) |
+        StatementKind::Reasoned(_, _, p) => Some(p),
+        _ => None,
+    }
+}
+
+#[test]
+fn cpus_say_what_they_think_of_each_other_once() {
+    let mut said = Vec::new();
+
+    for state in discussions(None) {
+        for (i, statement) in state.transcript.iter().enumerate() {
+            let about = match opinion_about(statement.kind) {
+                Some(p) => p,
+                None => continue,
+            };
+            said.push(std::mem::discriminant(&statement.kind));
+
+            assert!(about != statement.speaker, "in seed {}", state.seed);
+            assert!(!state.transcript[..i]
+                         .iter()
+                         .any(|s| s.speaker == statement.speaker && s.kind == statement.kind),
+                    "{:?} was said twice in seed {}",
+                    statement,
+                    state.seed);
+        }
+    }
+
+    for &kind in [StatementKind::Accused(Player),
+                  StatementKind::Believed(Player),
+                  StatementKind::VotingFor(Player),
+                  StatementKind::Reasoned(Player, Robber, Player)]
+        .iter() {
+        assert!(said.contains(&std::mem::discriminant(&kind)),
+                "nobody ever said anything like {:?}",
+                kind);
+    }
+}
+
+#[test]
+fn werewolves_only_vouch_for_each_other() {
+    let mut vouched = 0;
+
+    for state in discussions(Some(CpuProfile::Bluffer)) {
+        for statement in state.transcript.iter() {
+            if let (Cpu(i), StatementKind::Believed(p)) = (statement.speaker, statement.kind) {
+                if state.cpu_knowledge[i].role.role() != Werewolf {
+                    continue;
+                }
+                vouched += 1;
+
+                assert!(state.cpu_knowledge[i].known_werewolves.contains(&p),
+                        "Cpu {} vouched for {} in seed {}",
+                        i,
+                        p,
+                        state.seed);
+            }
+        }
+    }
+
+    assert!(vouched > 0, "the Werewolves never backed each other up");
+}
+
+#[test]
+fn a_seer_who_saw_a_werewolf_says_so() {
+    let mut seers = 0;
+
+    for state in discussions(Some(CpuProfile::Truthful)) {
+        for i in 0..state.cpu_knowledge.len() {
+            let werewolf = match state.cpu_knowledge[i].true_claim {
+                SeerRevealOneAction(p, Werewolf) => p,
+                _ => continue,
+            };
+            seers += 1;
+
+            assert!(state.transcript
+                        .iter()
+                        .any(|s| s.speaker == Cpu(i) && s.kind == StatementKind::Accused(werewolf)),
+                    "Cpu {} kept quiet about {} in seed {}",
+                    i,
+                    werewolf,
+                    state.seed);
+        }
+    }
+
+    assert!(seers > 0, "the Seer never saw a Werewolf");
+}
+
+#[test]
+fn an_accusation_from_someone_trusted_makes_a_suspect() {
+    let mut state = werewolves_at_discussion();
+
+    state.transcript.push(Statement {
+        round: 1,
+        speaker: Player,
+        kind: StatementKind::Accused(Cpu(3)),
+    });
+    engine::insert_claim(&mut state, Player, SeerRevealOneAction(Cpu(1), Werewolf));
+
+    let knowledge = &state.cpu_knowledge[0];
+    assert!(knowledge.trusted.contains(&Player));
+    assert!(knowledge.suspects.contains(&Cpu(3)));
+}
+
+#[test]
+fn what_was_heard_is_forgotten_when_the_story_changes() {
+    //Cpu 0 trusts their fellow Mason, who vouches for Cpu 2's Seer claim
+    let role_spec = role_spec_with(&[Mason]);
+    let night_log = vec![NightEvent::SawAwake {
+                             actor: Cpu(0),
+                             target: Cpu(1),
+                             card: Card::Plain(Mason),
+                         }];
+    let mut claims = HashMap::new();
+    claims.insert(Cpu(1), MasonAction(One(Cpu(0))));
+    claims.insert(Cpu(2), SeerRevealOneAction(Cpu(3), Werewolf));
+    let mut transcript = vec![Statement {
+                                  round: 2,
+                                  speaker: Cpu(1),
+                                  kind: StatementKind::Believed(Cpu(2)),
+                              }];
+
+    let mut knowledge = Knowledge::new(Card::Plain(Mason), Cpu(0));
+    let everyone = vec![Player, Cpu(0), Cpu(1), Cpu(2), Cpu(3)];
+
+    cpu::hear_claims(Cpu(0),
+                     &mut knowledge,
+                     Card::Plain(Mason),
+                     &night_log,
+                     &claims,
+                     &transcript,
+                     &role_spec);
+
+    assert!(knowledge.heard_werewolves.contains(&Cpu(3)));
+    assert_eq!(cpu::get_vote(Cpu(0), everyone.clone(), &knowledge, None, &mut GameRng::new(0)),
+               (Cpu(3), VoteReason::Suspected));
+
+    claims.insert(Cpu(2), Simple(Villager));
+    transcript.push(Statement {
+        round: 2,
+        speaker: Cpu(2),
+        kind: StatementKind::Changed(Simple(Villager)),
+    });
+
+    cpu::hear_claims(Cpu(0),
+                     &mut knowledge,
+                     Card::Plain(Mason),
+                     &night_log,
+                     &claims,
+                     &transcript,
+                     &role_spec);
+
+    assert!(knowledge.heard_werewolves.is_empty());
+    assert!(knowledge.known_werewolves.is_empty());
+    assert_eq!(cpu::get_vote(Cpu(0), everyone, &knowledge, None, &mut GameRng::new(0)),
+               (Cpu(2), VoteReason::Lied));
+}
@@ -0,0 +1,95 @@
+//Checks what the cpu players work out about who the Werewolves are.
+
+extern crate common;
+extern crate state_manipulation;
+
+mod support;
+
+use common::*;
+use common::Role::*;
+use common::Turn::*;
+use common::Claim::*;
+use common::Participant::*;
+
+use state_manipulation::deduction;
+use state_manipulation::engine::{self, Action};
+
+use support::*;
+
+#[test]
+fn werewolves_know_each_other() {
+    let mut state = deal_until(no_swapping(), |state| {
+        state.table.cpu_cards()[0] == Card::Plain(Werewolf) &&
+        state.table.cpu_cards()[1] == Card::Plain(Werewolf)
+    });
+    proceed_until(&mut state, Vote);
+
+    let chances = deduction::werewolf_chances(&state, Cpu(0), 0).unwrap();
+
+    assert_eq!(chances[&Cpu(1)], 1.0);
+    assert_eq!(chances[&Player], 0.0);
+}
+
+#[test]
+fn a_lone_werewolf_knows_the_other_is_in_the_center() {
+    let mut state = deal_until(no_swapping(), |state| {
+        state.table.cpu_cards()[0] == Card::Plain(Werewolf) &&
+        state.table.center().contains(&Card::Plain(Werewolf))
+    });
+    proceed_until(&mut state, Vote);
+
+    let chances = deduction::werewolf_chances(&state, Cpu(0), 0).unwrap();
+
+    assert_eq!(chances[&Cpu(0)], 1.0);
+    for (&p, &chance) in chances.iter() {
+        if p != Cpu(0) {
+            assert_eq!(chance, 0.0, "{} might be a Werewolf", p);
+        }
+    }
+}
+
+#[test]
+fn a_seer_claim_makes_who_they_named_more_suspicious() {
+    let mut state = deal_until(RoleSpec::default(),
+                               |state| state.table.cpu_cards()[0] == Card::Plain(Villager));
+    proceed_until(&mut state, Vote);
+
+    state.claims.clear();
+    let before = deduction::werewolf_chances(&state, Cpu(0), 0).unwrap();
+
+    state.claims.insert(Cpu(1), SeerRevealOneAction(Cpu(2), Werewolf));
+    let after = deduction::werewolf_chances(&state, Cpu(0), 0).unwrap();
+
+    assert!(after[&Cpu(2)] > before[&Cpu(2)],
+            "{} isn't more than {}",
+            after[&Cpu(2)],
+            before[&Cpu(2)]);
+    assert!(after[&Cpu(1)] < before[&Cpu(1)],
+            "{} isn't less than {}",
+            after[&Cpu(1)],
+            before[&Cpu(1)]);
+}
+
+#[test]
+fn a_robber_who_kept_their_card_knows_they_are_not_a_werewolf() {
+    //nobody else moves cards around
+    let mut role_spec = role_spec_with(&[Villager]);
+    role_spec.remove(&Troublemaker);
+
+    let mut robbed = deal_until(role_spec, |state| {
+        state.table.player() == Card::Plain(Robber) &&
+        state.table.cpu_cards()[0] == Card::Plain(Werewolf)
+    });
+    let mut skipped = copy_state(&robbed);
+
+    proceed_until(&mut robbed, RobberTurn);
+    engine::apply(&mut robbed, Action::Rob(Cpu(0))).unwrap();
+    proceed_until(&mut robbed, Vote);
+    proceed_until(&mut skipped, Vote);
+
+    let robbed = deduction::werewolf_chances(&robbed, Player, 0).unwrap();
+    let skipped = deduction::werewolf_chances(&skipped, Player, 0).unwrap();
+
+    assert_eq!(robbed[&Player], 1.0);
+    assert_eq!(skipped[&Player], 0.0);
+}
@@ -0,0 +1,61 @@
+//Checks that a game can be played through the engine alone, with no screen.
+
+extern crate common;
+extern crate state_manipulation;
+
+mod support;
+
+use common::*;
+use common::Turn::*;
+use common::Participant::*;
+
+use state_manipulation::engine::{self, Action, GameEvent, RuleError};
+
+use support::*;
+
+#[test]
+fn only_the_player_is_offered_actions() {
+    let state = deal_until(RoleSpec::default(), |_| true);
+
+    for cpu in engine::get_cpu_participants(&state) {
+        assert_eq!(engine::available_actions(&state, cpu), Vec::new());
+    }
+    assert!(!engine::available_actions(&state, Player).is_empty());
+}
+
+#[test]
+fn an_action_that_isnt_available_is_an_error() {
+    let mut state = engine::make_state(false, 0);
+
+    assert_eq!(engine::apply(&mut state, Action::VoteFor(Cpu(0))),
+               Err(RuleError::NotAvailable(Action::VoteFor(Cpu(0)), Ready)));
+    assert_eq!(state.turn, Ready);
+}
+
+#[test]
+fn the_engine_stops_whenever_the_player_has_something_to_do() {
+    for seed in 0..10 {
+        let mut state = engine::make_state(false, seed);
+        state.seed = seed;
+
+        while state.turn != Resolution {
+            let actions = engine::available_actions(&state, Player);
+            assert!(!actions.is_empty(), "seed {} is stuck on {:?}", seed, state.turn);
+
+            engine::apply(&mut state, actions[0]).unwrap();
+        }
+    }
+}
+
+#[test]
+fn everyone_votes_by_the_end_of_a_game() {
+    let mut state = deal_until(RoleSpec::default(), |_| true);
+    play_until(&mut state, Vote);
+
+    let events = engine::apply(&mut state, Action::VoteFor(Cpu(1))).unwrap();
+
+    assert_eq!(state.turn, Resolution);
+    assert!(events.contains(&GameEvent::TurnBegan(Resolution)));
+    assert!(events.contains(&GameEvent::Voted(Player, Cpu(1))));
+    assert_eq!(state.votes.len(), engine::get_participants(&state).len());
+}
@@ -0,0 +1,90 @@
+//Checks the entry points `src/main.rs` uses to get at the library.
+
+extern crate common;
+extern crate serde_json;
+extern crate state_manipulation;
+
+mod support;
+
+use common::*;
+
+use state_manipulation::ffi;
+
+use support::*;
+
+use std::ptr;
+
+fn json(events: &[Event]) -> Vec<u8> {
+    serde_json::to_vec(&events.to_vec()).unwrap()
+}
+
+#[test]
+fn events_cross_over_as_json() {
+    let platform = platform();
+    let state = ffi::new_state_handle(WIDTH, HEIGHT, true, 7);
+
+    let events = json(&[Event::MouseMove { x: 3, y: 4 },
+                        Event::KeyPressed {
+                            key: KeyCode::MouseLeft,
+                            ctrl: false,
+                            shift: true,
+                        }]);
+
+    unsafe {
+        assert!(!ffi::update_and_render_handle(&platform, state, events.as_ptr(), events.len()));
+        assert!(!ffi::update_and_render_handle(&platform, state, ptr::null(), 0));
+
+        ffi::drop_state_handle(state);
+    }
+}
+
+#[test]
+fn events_that_cant_be_read_are_skipped() {
+    let platform = platform();
+    let state = ffi::new_state_handle(WIDTH, HEIGHT, true, 7);
+
+    let events = b"[{\"NotAnEvent\": 1}]";
+
+    unsafe {
+        assert!(!ffi::update_and_render_handle(&platform, state, events.as_ptr(), events.len()));
+
+        ffi::drop_state_handle(state);
+    }
+}
+
+#[test]
+fn the_state_survives_a_reload() {
+    let state = ffi::new_state_handle(WIDTH, HEIGHT, true, 7);
+
+    unsafe {
+        (*state).seed = 1234;
+
+        let mut len = 0;
+        let saved = ffi::save_state_handle(state, &mut len);
+        assert!(!saved.is_null());
+        ffi::drop_state_handle(state);
+
+        let loaded = ffi::load_state_handle(saved, len);
+        ffi::free_saved_state(saved, len);
+
+        assert!(!loaded.is_null());
+        assert_eq!((*loaded).seed, 1234);
+
+        ffi::drop_state_handle(loaded);
+    }
+}
+
+#[test]
+fn a_bot_cant_be_plugged_into_a_seat_that_isnt_there() {
+    let state = ffi::new_state_handle(WIDTH, HEIGHT, true, 7);
+    let command = b"reference_bot";
+
+    unsafe {
+        let seat = (*state).table.cpu_cards().len();
+
+        assert!(!ffi::plug_in_bot_handle(state, seat, command.as_ptr(), command.len()));
+        assert!((*state).cpu_strategies.is_empty());
+
+        ffi::drop_state_handle(state);
+    }
+}
@@ -0,0 +1,101 @@
+//Checks how a cpu Minion covers for the Werewolves.
+
+extern crate common;
+extern crate state_manipulation;
+
+mod support;
+
+use common::*;
+use common::Role::*;
+use common::Turn::*;
+use common::Claim::*;
+use common::Participant::*;
+
+use state_manipulation::engine;
+
+use support::*;
+
+//who a claim says is holding a Werewolf card now, other than a known Werewolf
+fn framed(claim: Claim, werewolves: &[Participant]) -> Option<Participant> {
+    match claim {
+        SeerRevealOneAction(p, Werewolf) => Some(p),
+        TroublemakerAction(first, second) if werewolves.contains(&first) => Some(second),
+        TroublemakerAction(first, second) if werewolves.contains(&second) => Some(first),
+        _ => None,
+    }
+}
+
+#[test]
+fn a_bluffing_minion_frames_someone_who_isnt_a_werewolf() {
+    let mut state = deal_with_profile_until(CpuProfile::Bluffer,
+                                            role_spec_with(&[Villager, Minion]),
+                                            |state| {
+        let cards = state.table.cpu_cards();
+        cards[0] == Card::Plain(Minion) && cards.contains(&Card::Plain(Werewolf))
+    });
+    proceed_until(&mut state, Vote);
+
+    let werewolves: Vec<Participant> = state.cpu_knowledge[0].known_werewolves.iter().cloned().collect();
+    let target = framed(state.claims[&Cpu(0)], &werewolves);
+
+    assert!(!werewolves.is_empty());
+    assert!(target.is_some(), "the Minion claimed {:?}", state.claims[&Cpu(0)]);
+    assert!(!werewolves.contains(&target.unwrap()));
+    assert!(state.transcript
+        .iter()
+        .any(|s| s.speaker == Cpu(0) && s.kind == StatementKind::VotingFor(target.unwrap())));
+}
+
+#[test]
+fn a_minion_who_saw_no_werewolves_still_frames_someone() {
+    let mut state = deal_with_profile_until(CpuProfile::Bluffer,
+                                            role_spec_with(&[Villager, Minion]),
+                                            |state| {
+        let cards = state.table.cpu_cards();
+        cards[0] == Card::Plain(Minion) && !cards.contains(&Card::Plain(Werewolf)) &&
+        state.table.player() != Card::Plain(Werewolf)
+    });
+    proceed_until(&mut state, Vote);
+
+    assert!(state.cpu_knowledge[0].known_werewolves.is_empty());
+    match state.claims[&Cpu(0)] {
+        SeerRevealOneAction(p, Werewolf) => assert!(p != Cpu(0)),
+        claim => panic!("the Minion claimed {:?}", claim),
+    }
+}
+
+//Cpu 0 is a Cautious Minion who saw Cpu 1 is a Werewolf, and Cpu 2 isn't one.
+fn minion_at_vote() -> State {
+    let mut state = deal_with_profile_until(CpuProfile::Cautious,
+                                            role_spec_with(&[Villager, Minion]),
+                                            |state| {
+        let cards = state.table.cpu_cards();
+        cards[0] == Card::Plain(Minion) && cards[1] == Card::Plain(Werewolf) &&
+        cards[2] != Card::Plain(Werewolf)
+    });
+    proceed_until(&mut state, Vote);
+
+    state
+}
+
+#[test]
+fn a_minion_splits_a_vote_heading_for_a_werewolf() {
+    let mut state = minion_at_vote();
+    everyone_says(&mut state, &[(2, 1), (3, 1), (4, 2), (5, 3)]);
+
+    engine::apply(&mut state, engine::Action::VoteFor(Cpu(3))).unwrap();
+
+    assert!(state.votes.contains(&(Cpu(0), Cpu(2))));
+    assert!(state.vote_reasons.contains(&(Cpu(0), VoteReason::SplitVotes)));
+}
+
+#[test]
+fn a_minion_joins_a_vote_heading_for_someone_else() {
+    let mut state = minion_at_vote();
+    everyone_says(&mut state, &[(1, 2), (3, 2), (4, 2), (5, 3)]);
+
+    engine::apply(&mut state, engine::Action::VoteFor(Cpu(3))).unwrap();
+
+    assert!(state.votes.contains(&(Cpu(0), Cpu(2))));
+    assert!(state.vote_reasons.contains(&(Cpu(0), VoteReason::JoinedIn)));
+}
@@ -0,0 +1,156 @@
+//Checks the choices cpu players make during the night.
+
+extern crate common;
+extern crate state_manipulation;
+
+mod support;
+
+use common::*;
+use common::Role::*;
+use common::Turn::*;
+use common::Participant::*;
+
+use state_manipulation::cpu::ProfileStrategy;
+
+use support::*;
+
+//The same deal as `state`, with every cpu seat playing as `profile`.
+fn redeal(state: &State, profile: CpuProfile) -> State {
+    deal_with_profile_until(profile, state.role_spec, |other| other.seed == state.seed)
+}
+
+//who Cpu 0 robbed during the night
+fn robbed(state: &State) -> Vec<Participant> {
+    state.night_log
+        .iter()
+        .filter_map(|event| match *event {
+            NightEvent::Robbed { actor: Cpu(0), target, .. } => Some(target),
+            _ => None,
+        })
+        .collect()
+}
+
+//which center card Cpu 0 took, once the night is over
+fn took_from_center(state: &State) -> Option<CenterCard> {
+    let mut state = copy_state(state);
+    proceed_until(&mut state, Vote);
+
+    state.night_log
+        .iter()
+        .filter_map(|event| match *event {
+            NightEvent::TookCenter { actor: Cpu(0), center } => Some(center),
+            _ => None,
+        })
+        .next()
+}
+
+fn seer_looked_at_the_center(role_spec: RoleSpec) -> bool {
+    let mut state = deal_until(role_spec, |state| state.table.cpu_cards()[0] == Card::Plain(Seer));
+    state.cpu_profiles[0] = CpuProfile::Cautious;
+    proceed_until(&mut state, Vote);
+
+    state.night_log.iter().any(|event| match *event {
+        NightEvent::ViewedCenter { actor, .. } => actor == Cpu(0),
+        _ => false,
+    })
+}
+
+#[test]
+fn a_careful_seer_looks_at_a_player_when_most_cards_wake_up() {
+    assert!(!seer_looked_at_the_center(RoleSpec::default()));
+}
+
+#[test]
+fn a_careful_seer_looks_at_the_center_when_most_cards_sleep() {
+    assert!(seer_looked_at_the_center(role_spec_with(&[Villager, Tanner, Hunter])));
+}
+
+#[test]
+fn a_doppel_robber_doesnt_rob_the_robber_it_copied() {
+    let mut state = deal_until(role_spec_with(&[Doppelganger]), |state| {
+        matches!(state.table.cpu_cards()[0], Card::Doppel(_, Robber))
+    });
+    state.cpu_profiles[0] = CpuProfile::Cautious;
+    proceed_until(&mut state, Vote);
+
+    let copied = match state.initial_table.cpu_cards()[0] {
+        Card::Doppel(target, _) => target,
+        _ => unreachable!(),
+    };
+
+    let robbed = robbed(&state);
+
+    assert_eq!(robbed.len(), 1);
+    assert!(robbed[0] != copied);
+}
+
+#[test]
+fn a_cautious_doppelganger_copies_a_cpu_where_a_bluffer_copies_the_player() {
+    let cautious = deal_with_profile_until(CpuProfile::Cautious,
+                                           role_spec_with(&[Doppelganger]),
+                                           |state| {
+        matches!(state.initial_table.cpu_cards()[0], Card::Doppel(_, _))
+    });
+    let bluffer = redeal(&cautious, CpuProfile::Bluffer);
+
+    assert!(matches!(cautious.initial_table.cpu_cards()[0], Card::Doppel(Cpu(_), _)));
+    assert!(matches!(bluffer.initial_table.cpu_cards()[0], Card::Doppel(Player, _)));
+}
+
+#[test]
+fn a_truthful_robber_keeps_its_card_where_a_cautious_one_robs() {
+    //most cards would take a Robber off the village team
+    let role_spec = RoleSpec::from_roles(&[Werewolf, Werewolf, Minion, Tanner, Robber, Seer,
+                                           Troublemaker])
+        .unwrap();
+
+    let mut cautious = deal_with_profile_until(CpuProfile::Cautious, role_spec, |state| {
+        state.table.cpu_cards()[0] == Card::Plain(Robber)
+    });
+    let mut truthful = redeal(&cautious, CpuProfile::Truthful);
+
+    proceed_until(&mut cautious, Vote);
+    proceed_until(&mut truthful, Vote);
+
+    assert_eq!(robbed(&cautious).len(), 1);
+    assert!(robbed(&truthful).is_empty());
+}
+
+#[test]
+fn a_cautious_drunk_takes_the_first_center_card_where_a_naive_one_picks_any() {
+    let naive = deal_with_profile_until(CpuProfile::Naive, role_spec_with(&[Drunk]), |state| {
+        state.table.cpu_cards()[0] == Card::Plain(Drunk) &&
+        took_from_center(state) != Some(CenterCard::First)
+    });
+    let cautious = redeal(&naive, CpuProfile::Cautious);
+
+    assert_eq!(took_from_center(&cautious), Some(CenterCard::First));
+}
+
+#[test]
+fn a_village_robber_leaves_werewolves_it_knows_about_alone() {
+    let mut state = deal_with_profile_until(CpuProfile::Cautious, RoleSpec::default(), |state| {
+        state.table.cpu_cards()[0] == Card::Plain(Robber)
+    });
+
+    state.cpu_knowledge[0].known_werewolves.insert(Player);
+    state.cpu_knowledge[0].known_werewolves.insert(Cpu(1));
+
+    assert_eq!(ProfileStrategy.robber_target(&mut state, Cpu(0)), Some(Cpu(2)));
+}
+
+#[test]
+fn a_werewolf_team_robber_only_robs_a_teammate() {
+    let mut state = deal_with_profile_until(CpuProfile::Cautious, RoleSpec::default(), |state| {
+        state.table.cpu_cards()[0] == Card::Plain(Robber)
+    });
+
+    //as if it knew its card had been swapped for a Werewolf
+    state.cpu_knowledge[0].role = Card::Plain(Werewolf);
+
+    assert_eq!(ProfileStrategy.robber_target(&mut state, Cpu(0)), None);
+
+    state.cpu_knowledge[0].known_werewolves.insert(Cpu(1));
+
+    assert_eq!(ProfileStrategy.robber_target(&mut state, Cpu(0)), Some(Cpu(1)));
+}
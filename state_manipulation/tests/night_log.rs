@@ -0,0 +1,103 @@
+//Checks that the night log is a true record of what happened at night.
+
+extern crate common;
+extern crate state_manipulation;
+
+mod support;
+
+use common::*;
+use common::Role::*;
+use common::Turn::*;
+use common::Participant::*;
+
+use state_manipulation::engine::{self, Action};
+
+use support::*;
+
+//Plays the log back from the cards as they were dealt, checking every card
+//anyone saw was really there at the time, and returns how the table ends up.
+fn replay(state: &State) -> Table {
+    let mut table = state.initial_table.clone();
+
+    for event in state.night_log.iter() {
+        match *event {
+            NightEvent::Copied { actor, target, role } => {
+                table.copy_role(actor, target, role);
+            }
+            NightEvent::SawAwake { target, card, .. } |
+            NightEvent::ViewedPlayer { target, card, .. } => {
+                assert_eq!(table.view(target), Some(card), "{:?}", event);
+            }
+            NightEvent::ViewedCenter { center, card, .. } => {
+                assert_eq!(table.view_center(center), card, "{:?}", event);
+            }
+            NightEvent::Robbed { actor, target, card } => {
+                assert_eq!(table.view(target), Some(card), "{:?}", event);
+                table.swap(actor, target);
+            }
+            NightEvent::Swapped { first, second, .. } => {
+                table.swap(first, second);
+            }
+            NightEvent::TookCenter { actor, center } => {
+                table.swap_with_center(actor, center);
+            }
+            NightEvent::Peeked { actor, card } => {
+                assert_eq!(table.view(actor), Some(card), "{:?}", event);
+            }
+            NightEvent::AwakeAlone { .. } => {}
+        }
+    }
+
+    table
+}
+
+#[test]
+fn the_log_accounts_for_every_card_that_moved() {
+    for seed in 0..20 {
+        let mut state = engine::make_state(false, seed);
+        state.seed = seed;
+        play_until(&mut state, Vote);
+
+        assert_eq!(replay(&state), state.table, "seed {}", seed);
+    }
+}
+
+#[test]
+fn what_the_player_does_is_logged() {
+    let mut state = deal_as(RoleSpec::default(), Robber);
+    proceed_until(&mut state, RobberTurn);
+
+    let card = state.table.view(Cpu(0)).unwrap();
+    engine::apply(&mut state, Action::Rob(Cpu(0))).unwrap();
+
+    assert!(state.night_log.contains(&NightEvent::Robbed {
+        actor: Player,
+        target: Cpu(0),
+        card,
+    }));
+    assert_eq!(state.player_knowledge.role, card);
+}
+
+#[test]
+fn everyone_thinks_their_card_is_the_last_one_the_log_shows_them() {
+    for seed in 0..20 {
+        let mut state = engine::make_state(false, seed);
+        state.seed = seed;
+        play_until(&mut state, Vote);
+
+        for participant in engine::get_participants(&state) {
+            let mut knowledge = Knowledge::new(state.initial_table.view(participant).unwrap(),
+                                               participant);
+
+            for event in state.night_log.iter().filter(|e| e.actor() == participant) {
+                knowledge.learn(event);
+            }
+
+            assert_eq!(engine::get_knowledge(&state, participant).unwrap().role,
+                       knowledge.role,
+                       "seed {}, {}",
+                       seed,
+                       participant);
+        }
+    }
+}
@@ -0,0 +1,102 @@
+//Checks who dies and who wins once the votes are in.
+
+extern crate common;
+extern crate state_manipulation;
+
+mod support;
+
+use common::*;
+use common::Role::*;
+use common::Participant::*;
+
+use state_manipulation::engine;
+use state_manipulation::outcome::{self, GameOutcome, Winner};
+
+//The player has `player` and Cpu 0, 1 and 2 have `cpus`. The center is all
+//Villagers. Everyone votes the way `votes` says, in seat order.
+fn resolve_with(player: Role, cpus: [Role; 3], votes: [Participant; 4]) -> GameOutcome {
+    let mut state = engine::make_state(false, 0);
+
+    let mut roles: Vec<Card> = cpus.iter().map(|&r| Card::Plain(r)).collect();
+    roles.extend(vec![Card::Plain(Villager); 3]);
+    roles.push(Card::Plain(player));
+    engine::deal_exactly(&mut state, roles);
+
+    state.votes = vec![Player, Cpu(0), Cpu(1), Cpu(2)]
+        .into_iter()
+        .zip(votes.iter().cloned())
+        .collect();
+
+    outcome::resolve(&state)
+}
+
+#[test]
+fn the_village_wins_when_a_werewolf_dies() {
+    let outcome = resolve_with(Villager,
+                               [Werewolf, Seer, Villager],
+                               [Cpu(0), Cpu(1), Cpu(0), Cpu(0)]);
+
+    assert_eq!(outcome.dead, vec![Cpu(0)]);
+    assert_eq!(outcome.tallies, vec![(Cpu(0), 3), (Cpu(1), 1)]);
+    assert_eq!(outcome.winners, vec![Winner::Village]);
+    assert!(outcome.won(Player));
+    assert!(!outcome.won(Cpu(0)));
+}
+
+#[test]
+fn the_werewolves_win_when_nobody_dies() {
+    let outcome = resolve_with(Villager,
+                               [Werewolf, Seer, Villager],
+                               [Cpu(0), Cpu(1), Cpu(2), Player]);
+
+    assert!(outcome.dead.is_empty());
+    assert_eq!(outcome.winners, vec![Winner::Werewolf]);
+    assert!(outcome.won(Cpu(0)));
+    assert!(!outcome.won(Cpu(1)));
+}
+
+#[test]
+fn nobody_dying_without_werewolves_is_a_village_win() {
+    let outcome = resolve_with(Villager,
+                               [Seer, Robber, Villager],
+                               [Cpu(0), Cpu(1), Cpu(2), Player]);
+
+    assert_eq!(outcome.winners, vec![Winner::Village]);
+    assert!(outcome.werewolves.is_empty());
+}
+
+#[test]
+fn a_minion_wins_without_werewolves_if_someone_else_dies() {
+    let outcome = resolve_with(Villager,
+                               [Minion, Seer, Villager],
+                               [Cpu(1), Cpu(1), Cpu(0), Cpu(1)]);
+
+    assert_eq!(outcome.dead, vec![Cpu(1)]);
+    assert_eq!(outcome.winners, vec![Winner::Werewolf]);
+    assert!(outcome.won(Cpu(0)));
+}
+
+#[test]
+fn a_dead_tanner_wins_on_their_own() {
+    let outcome = resolve_with(Villager,
+                               [Werewolf, Tanner, Villager],
+                               [Cpu(1), Cpu(1), Cpu(1), Cpu(0)]);
+
+    assert_eq!(outcome.winners, vec![Winner::Tanner(Cpu(1))]);
+    assert_eq!(outcome.dead_tanners(), vec![Cpu(1)]);
+    assert!(outcome.won(Cpu(1)));
+    assert!(!outcome.won(Cpu(0)));
+    assert!(!outcome.won(Player));
+}
+
+#[test]
+fn a_dying_hunter_takes_who_they_voted_for_with_them() {
+    let outcome = resolve_with(Hunter,
+                               [Werewolf, Villager, Villager],
+                               [Cpu(0), Player, Player, Cpu(2)]);
+
+    assert_eq!(outcome.hunter_shots, vec![(Player, Cpu(0))]);
+    assert_eq!(outcome.dead, vec![Player, Cpu(0)]);
+    assert_eq!(outcome.dead_werewolf_count, 1);
+    assert_eq!(outcome.winners, vec![Winner::Village]);
+}
@@ -0,0 +1,64 @@
+//Checks the role registry and the cards built on it.
+
+extern crate common;
+extern crate state_manipulation;
+
+mod support;
+
+use common::*;
+use common::Role::*;
+use common::Participant::*;
+
+use support::*;
+
+#[test]
+fn every_role_def_is_where_its_role_looks_for_it() {
+    assert_eq!(all_roles().len(), ROLE_COUNT);
+
+    for (i, def) in ROLE_DEFS.iter().enumerate() {
+        assert_eq!(def.role as usize, i);
+        assert_eq!(role_def(def.role).name, format!("{:o}", def.role));
+    }
+}
+
+#[test]
+fn a_doppelganger_copy_plays_by_the_copied_role() {
+    let card = Card::Doppel(Cpu(1), Werewolf);
+
+    assert_eq!(card.role(), Werewolf);
+    assert_eq!(card.face(), Doppelganger);
+    assert_eq!(card.def().team, Team::Werewolf);
+    assert!(card.is_doppel());
+    assert_eq!(full_role_string(card), "a Doppel-Werewolf");
+}
+
+#[test]
+fn a_role_spec_stops_at_the_card_limit() {
+    let mut role_spec = RoleSpec::default();
+
+    for _ in 0..5 {
+        if role_spec.can_add(&Werewolf) {
+            role_spec.add(&Werewolf);
+        }
+    }
+
+    assert_eq!(role_spec.get_count(&Werewolf), role_def(Werewolf).card_limit as u32);
+}
+
+#[test]
+fn masons_come_and_go_in_pairs() {
+    let mut role_spec = role_spec_with(&[Mason]);
+    assert_eq!(role_spec.get_count(&Mason), 2);
+
+    role_spec.remove(&Mason);
+    assert_eq!(role_spec.get_count(&Mason), 0);
+}
+
+#[test]
+fn a_role_spec_can_be_rebuilt_from_its_roles() {
+    let role_spec = role_spec_with(&[Mason, Doppelganger, Hunter]);
+
+    assert_eq!(RoleSpec::from_roles(&role_spec.get_role_vector()), Some(role_spec));
+    assert_eq!(RoleSpec::from_roles(&[Mason, Villager, Villager, Villager, Villager, Seer]),
+               None);
+}
@@ -0,0 +1,259 @@
+//Checks that a saved game picks up exactly where it left off.
+
+extern crate common;
+extern crate state_manipulation;
+
+mod support;
+
+use common::*;
+use common::Participant::*;
+use common::Role::*;
+use common::Turn::*;
+
+use state_manipulation::cpu::ProfileStrategy;
+use state_manipulation::engine;
+use state_manipulation::save::{self, SaveError, SAVE_VERSION};
+
+use support::*;
+
+use std::rc::Rc;
+
+//`Knowledge` is kept in `HashSet`s, which don't save in the same order each
+//time, so this compares everything else.
+fn assert_same_game(a: &State, b: &State) {
+    assert_eq!(a.turn, b.turn);
+    assert_eq!((a.rng.seed(), a.rng.draws()), (b.rng.seed(), b.rng.draws()));
+    assert_eq!(a.table, b.table);
+    assert_eq!(a.initial_table, b.initial_table);
+    assert_eq!(a.night_log, b.night_log);
+    assert_eq!(a.claims, b.claims);
+    assert_eq!(a.transcript, b.transcript);
+    assert_eq!(a.votes, b.votes);
+    assert_eq!(a.vote_reasons, b.vote_reasons);
+    assert_eq!(a.cpu_profiles, b.cpu_profiles);
+}
+
+//The loaded state plays on the same way as the one that was saved, which it
+//couldn't if the rng had moved.
+fn assert_carries_on_the_same(state: State) {
+    let mut loaded = copy_state(&state);
+
+    assert_same_game(&loaded, &state);
+
+    let mut state = state;
+    play_until(&mut state, Ready);
+    play_until(&mut loaded, Ready);
+
+    assert_same_game(&loaded, &state);
+}
+
+#[test]
+fn a_game_saved_during_the_night_carries_on_the_same() {
+    let mut state = deal_as(role_spec_with(&[Insomniac]), Seer);
+    proceed_until(&mut state, SeerTurn);
+
+    assert_carries_on_the_same(state);
+}
+
+#[test]
+fn a_game_saved_during_the_discussion_carries_on_the_same() {
+    let mut state = deal_until(RoleSpec::default(), |_| true);
+    proceed_until(&mut state, Discuss);
+
+    assert!(!state.claims.is_empty());
+    assert!(!state.transcript.is_empty());
+    assert_carries_on_the_same(state);
+}
+
+#[test]
+fn cpu_strategies_are_left_out_of_a_save() {
+    let mut state = deal_until(RoleSpec::default(), |_| true);
+    state.cpu_strategies.insert(0, Rc::new(ProfileStrategy));
+    proceed_until(&mut state, Discuss);
+
+    let mut loaded = copy_state(&state);
+
+    assert!(loaded.cpu_strategies.is_empty());
+    play_until(&mut loaded, Ready);
+}
+
+//Somewhere to save to that no other test uses.
+fn save_path(name: &str) -> String {
+    std::env::temp_dir()
+        .join(format!("one_night_{}_{}.json", name, std::process::id()))
+        .to_string_lossy()
+        .into_owned()
+}
+
+#[test]
+fn continuing_a_game_deletes_its_save() {
+    let path = save_path("continued");
+    let mut state = deal_until(RoleSpec::default(), |_| true);
+    proceed_until(&mut state, Discuss);
+
+    save::save_or_delete(&state, &path).unwrap();
+    assert!(save::exists(&path));
+
+    let loaded = save::take(&path).unwrap();
+
+    assert_same_game(&loaded, &state);
+    assert!(!save::exists(&path));
+}
+
+#[test]
+fn a_game_that_is_over_deletes_its_save() {
+    let path = save_path("over");
+    let mut state = deal_until(RoleSpec::default(), |_| true);
+    proceed_until(&mut state, Discuss);
+
+    save::save_or_delete(&state, &path).unwrap();
+    assert!(save::exists(&path));
+
+    play_until(&mut state, Resolution);
+    save::save_or_delete(&state, &path).unwrap();
+
+    assert!(!save::exists(&path));
+}
+
+#[test]
+fn a_save_from_a_newer_build_is_refused() {
+    let future = format!("{{\"version\": {}, \"state\": {{}}}}", SAVE_VERSION + 1);
+
+    match save::from_str(&future) {
+        Err(SaveError::Version(v)) => assert_eq!(v, SAVE_VERSION + 1),
+        result => panic!("loaded {:?}", result),
+    }
+    match save::from_str("{\"version\": 0, \"state\": {}}") {
+        Err(SaveError::Version(0)) => {}
+        result => panic!("loaded {:?}", result),
+    }
+}
+
+//Saves written by older builds, one frozen in `tests/saves` for each version,
+//which have to keep loading for as long as the migrations say they do.
+fn load_old_save(json: &str, version: u32) -> State {
+    assert!(json.contains(&format!("\"version\": {},", version)));
+
+    match save::from_str(json) {
+        Ok(state) => state,
+        Err(e) => panic!("couldn't load a version {} save: {:?}", version, e),
+    }
+}
+
+#[test]
+fn a_version_1_save_loads_without_any_beliefs_about_claims() {
+    let mut state = load_old_save(include_str!("saves/v1.json"), 1);
+
+    assert_eq!(state.turn, Discuss);
+    assert!(!state.claims.is_empty());
+    for knowledge in state.cpu_knowledge.iter().chain(Some(&state.player_knowledge)) {
+        assert!(knowledge.trusted.is_empty());
+        assert!(knowledge.liars.is_empty());
+        assert!(knowledge.contested.is_empty());
+    }
+
+    play_until(&mut state, Ready);
+}
+
+#[test]
+fn a_version_7_save_loads_without_anything_heard() {
+    let mut state = load_old_save(include_str!("saves/v7.json"), 7);
+
+    assert_eq!(state.turn, Resolution);
+    for knowledge in state.cpu_knowledge.iter().chain(Some(&state.player_knowledge)) {
+        assert!(knowledge.heard_werewolves.is_empty());
+        assert!(knowledge.heard_villagers.is_empty());
+    }
+
+    let screen = render(&mut state);
+    assert!(screen.contains("Cpu 0: \"From what was said,"));
+
+    play_until(&mut state, Ready);
+}
+
+#[test]
+fn a_version_2_save_loads_with_every_cpu_playing_the_default_way() {
+    let mut state = load_old_save(include_str!("saves/v2.json"), 2);
+
+    assert_eq!(state.turn, Discuss);
+    assert!(state.cpu_profiles.is_empty());
+    assert!(state.profile_picks.iter().all(|pick| pick.is_none()));
+    for i in 0..state.cpu_knowledge.len() {
+        assert_eq!(engine::get_profile(&state, Cpu(i)), CpuProfile::default());
+    }
+
+    play_until(&mut state, Ready);
+}
+
+#[test]
+fn a_version_3_save_loads_with_everything_said_in_the_first_round() {
+    let mut state = load_old_save(include_str!("saves/v3.json"), 3);
+
+    assert_eq!(state.turn, Discuss);
+    assert_eq!(state.transcript.len(), state.claims.len());
+    for statement in state.transcript.iter() {
+        assert_eq!(statement.round, 1);
+        assert_eq!(statement.kind,
+                   StatementKind::Opening(state.claims[&statement.speaker]));
+    }
+
+    play_until(&mut state, Ready);
+}
+
+//a version 4 game kept the bluffs of anyone who had come clean, so the bluff
+//has to come back as what they said first and the claim as their confession
+#[test]
+fn a_version_4_save_loads_with_bluffs_told_and_then_owned_up_to() {
+    let mut state = load_old_save(include_str!("saves/v4.json"), 4);
+
+    assert_eq!(state.turn, Discuss);
+
+    let said_by_cpu_1: Vec<_> = state.transcript
+        .iter()
+        .filter(|statement| statement.speaker == Cpu(1))
+        .map(|statement| (statement.round, statement.kind))
+        .collect();
+    assert_eq!(said_by_cpu_1,
+               vec![(1, StatementKind::Opening(Claim::RobberAction(Player, Villager))),
+                    (2, StatementKind::Confessed(Claim::TroublemakerAction(Player, Cpu(0))))]);
+    assert_eq!(state.transcript.last().map(|statement| statement.speaker),
+               Some(Cpu(1)));
+
+    play_until(&mut state, Ready);
+}
+
+#[test]
+fn a_version_5_save_loads_with_claims_inside_what_was_said() {
+    let mut state = load_old_save(include_str!("saves/v5.json"), 5);
+
+    assert_eq!(state.turn, Vote);
+    for knowledge in state.cpu_knowledge.iter().chain(Some(&state.player_knowledge)) {
+        assert!(knowledge.suspects.is_empty());
+    }
+
+    let kinds: Vec<_> = state.transcript.iter().map(|statement| statement.kind).collect();
+    assert_eq!(kinds,
+               vec![StatementKind::Opening(Claim::Simple(Villager)),
+                    StatementKind::Opening(Claim::SeerRevealOneAction(Cpu(2), Villager)),
+                    StatementKind::Opening(Claim::RobberAction(Cpu(1), Villager)),
+                    StatementKind::Countered(Cpu(0)),
+                    StatementKind::Confessed(Claim::TroublemakerAction(Cpu(1), Player)),
+                    StatementKind::Countered(Cpu(1))]);
+
+    play_until(&mut state, Ready);
+}
+
+#[test]
+fn a_version_6_save_loads_with_nobody_saying_why_they_voted() {
+    let mut state = load_old_save(include_str!("saves/v6.json"), 6);
+
+    assert_eq!(state.turn, Resolution);
+    assert!(!state.votes.is_empty());
+    assert!(state.vote_reasons.is_empty());
+
+    let screen = render(&mut state);
+    assert!(screen.contains("Cpu 0 voted for"));
+    assert!(!screen.contains("Cpu 0: \""));
+
+    play_until(&mut state, Ready);
+}
@@ -0,0 +1,310 @@
+//Goes through each `Turn` screen the player can see and compares what was
+//drawn to the snapshots in `tests/snapshots`. See `support::assert_snapshot`
+//for how to update them after changing a screen on purpose. The snapshots
+//include the debug "Next" button, so these only pass in debug builds.
+
+extern crate common;
+extern crate state_manipulation;
+
+mod support;
+
+use common::*;
+use common::Role::*;
+use common::Turn::*;
+use common::Participant::*;
+use common::CenterPair::*;
+
+use state_manipulation::engine::{self, Action};
+
+use support::*;
+
+#[test]
+fn ready() {
+    let mut state = engine::make_state(false, 0);
+
+    assert_snapshot("ready", &render(&mut state));
+}
+
+#[test]
+fn clicking_a_cpu_style_cycles_it() {
+    let mut state = engine::make_state(false, 0);
+
+    click_button(&mut state, "1 Random");
+    click_button(&mut state, "1 Naive");
+
+    assert_eq!(state.profile_picks[1], Some(CpuProfile::Cautious));
+    assert_eq!(state.profile_picks[0], None);
+}
+
+#[test]
+fn a_failed_save_is_shown() {
+    let mut state = engine::make_state(false, 0);
+    state.save_error = Some("couldn't save: disk full".to_owned());
+
+    assert!(render(&mut state).contains("couldn't save: disk full"));
+}
+
+#[test]
+fn clicking_ready_deals() {
+    let mut state = engine::make_state(false, 0);
+
+    click_button(&mut state, "Ready");
+
+    assert_eq!(state.turn, SeeRole(false));
+    assert_snapshot("see_role", &render(&mut state));
+}
+
+#[test]
+fn clicking_ready_again_goes_through_the_night() {
+    let mut state = deal_as(role_spec_with(&[]), Villager);
+
+    click_button(&mut state, "Ready");
+
+    assert_eq!(state.turn, Discuss);
+}
+
+#[test]
+fn werewolves() {
+    let mut state = deal_as(role_spec_with(&[]), Werewolf);
+
+    proceed_until(&mut state, Werewolves);
+    assert_snapshot("werewolves", &render(&mut state));
+}
+
+#[test]
+fn minion() {
+    let mut state = deal_as(role_spec_with(&[Minion]), Minion);
+
+    proceed_until(&mut state, MinionTurn);
+    assert_snapshot("minion_turn", &render(&mut state));
+}
+
+#[test]
+fn mason() {
+    let mut state = deal_as(role_spec_with(&[Mason]), Mason);
+
+    proceed_until(&mut state, MasonTurn);
+    assert_snapshot("mason_turn", &render(&mut state));
+}
+
+#[test]
+fn seer_views_a_player() {
+    let mut state = deal_as(role_spec_with(&[]), Seer);
+
+    proceed_until(&mut state, SeerTurn);
+    assert_snapshot("seer_turn", &render(&mut state));
+
+    click_button(&mut state, "Cpu 0");
+
+    assert_eq!(state.turn, SeerRevealOne(Cpu(0)));
+    assert_snapshot("seer_reveal_one", &render(&mut state));
+}
+
+#[test]
+fn seer_views_the_center() {
+    let mut state = deal_as(role_spec_with(&[]), Seer);
+
+    proceed_until(&mut state, SeerTurn);
+    engine::apply(&mut state, Action::ViewCenter(FirstSecond)).unwrap();
+
+    assert_snapshot("seer_reveal_two", &render(&mut state));
+}
+
+#[test]
+fn robber() {
+    let mut state = deal_as(role_spec_with(&[]), Robber);
+
+    proceed_until(&mut state, RobberTurn);
+    assert_snapshot("robber_turn", &render(&mut state));
+
+    click_button(&mut state, "Cpu 1");
+
+    assert_eq!(state.turn, RobberReveal);
+    assert_snapshot("robber_reveal", &render(&mut state));
+}
+
+#[test]
+fn troublemaker() {
+    let mut state = deal_as(role_spec_with(&[]), Troublemaker);
+
+    proceed_until(&mut state, TroublemakerTurn);
+    assert_snapshot("troublemaker_turn", &render(&mut state));
+
+    click_button(&mut state, "Cpu 0");
+
+    assert_eq!(state.turn, TroublemakerSecondChoice(Cpu(0)));
+    assert_snapshot("troublemaker_second_choice", &render(&mut state));
+}
+
+#[test]
+fn drunk() {
+    let mut state = deal_as(role_spec_with(&[Drunk]), Drunk);
+
+    proceed_until(&mut state, DrunkTurn);
+    assert_snapshot("drunk_turn", &render(&mut state));
+}
+
+#[test]
+fn insomniac() {
+    let mut state = deal_as(role_spec_with(&[Insomniac]), Insomniac);
+
+    proceed_until(&mut state, InsomniacTurn);
+    assert_snapshot("insomniac_turn", &render(&mut state));
+}
+
+#[test]
+fn doppelganger() {
+    let mut state = deal_as(role_spec_with(&[Doppelganger]), Doppelganger);
+
+    assert_eq!(state.turn, SeeRole(true));
+    assert_snapshot("see_role_doppelganger", &render(&mut state));
+}
+
+#[test]
+fn doppel_seer() {
+    let mut state = deal_as_doppel(role_spec_with(&[Doppelganger]), Seer);
+
+    proceed_until(&mut state, DoppelSeerTurn);
+    assert_snapshot("doppel_seer_turn", &render(&mut state));
+
+    let mut state_two = copy_state(&state);
+
+    engine::apply(&mut state, Action::ViewPlayer(Cpu(0))).unwrap();
+    assert_snapshot("doppel_seer_reveal_one", &render(&mut state));
+
+    engine::apply(&mut state_two, Action::ViewCenter(SecondThird)).unwrap();
+    assert_snapshot("doppel_seer_reveal_two", &render(&mut state_two));
+}
+
+#[test]
+fn doppel_robber() {
+    let mut state = deal_as_doppel(role_spec_with(&[Doppelganger]), Robber);
+
+    proceed_until(&mut state, DoppelRobberTurn);
+    assert_snapshot("doppel_robber_turn", &render(&mut state));
+
+    engine::apply(&mut state, Action::Rob(Cpu(1))).unwrap();
+    assert_eq!(state.turn, DoppelRobberReveal);
+    assert_snapshot("doppel_robber_reveal", &render(&mut state));
+}
+
+#[test]
+fn doppel_troublemaker() {
+    let mut state = deal_as_doppel(role_spec_with(&[Doppelganger]), Troublemaker);
+
+    proceed_until(&mut state, DoppelTroublemakerTurn);
+    assert_snapshot("doppel_troublemaker_turn", &render(&mut state));
+
+    engine::apply(&mut state, Action::PickFirst(Cpu(1))).unwrap();
+    assert_snapshot("doppel_troublemaker_second_choice", &render(&mut state));
+}
+
+#[test]
+fn doppel_drunk() {
+    let mut state = deal_as_doppel(role_spec_with(&[Doppelganger, Drunk]), Drunk);
+
+    proceed_until(&mut state, DoppelDrunkTurn);
+    assert_snapshot("doppel_drunk_turn", &render(&mut state));
+}
+
+#[test]
+fn doppel_minion() {
+    let mut state = deal_as_doppel(role_spec_with(&[Doppelganger, Minion]), Minion);
+
+    proceed_until(&mut state, DoppelMinionTurn);
+    assert_snapshot("doppel_minion_turn", &render(&mut state));
+}
+
+#[test]
+fn doppel_insomniac() {
+    //a Doppel-Insomniac only wakes up if their card is still in front of them
+    let mut role_spec = role_spec_with(&[Doppelganger, Insomniac]);
+    role_spec.remove(&Robber);
+    role_spec.remove(&Troublemaker);
+
+    let mut state = deal_as_doppel(role_spec, Insomniac);
+
+    proceed_until(&mut state, DoppelInsomniacTurn);
+    assert_snapshot("doppel_insomniac_turn", &render(&mut state));
+}
+
+#[test]
+fn discussion_vote_and_resolution() {
+    let mut state = deal_as(role_spec_with(&[]), Villager);
+
+    proceed_until(&mut state, Discuss);
+    assert_snapshot("discuss", &render(&mut state));
+
+    //the discussion goes on until nobody has anything left to say
+    proceed_until(&mut state, Vote);
+    assert_snapshot("vote", &render(&mut state));
+
+    click_button(&mut state, "Cpu 2");
+
+    assert_eq!(state.turn, Resolution);
+    assert_snapshot("resolution", &render(&mut state));
+
+    click_button(&mut state, "Show the night");
+    assert_snapshot("resolution_night_log", &render(&mut state));
+}
+
+#[test]
+fn a_bluffer_comes_clean() {
+    let came_clean = |state: &State| {
+        state.transcript.iter().any(|s| matches!(s.kind, StatementKind::Confessed(_)))
+    };
+
+    for seed in 0..1000 {
+        let mut state = engine::make_state(false, seed);
+        state.seed = seed;
+        state.profile_picks = vec![Some(CpuProfile::Bluffer); MAXIMUM_CPU_PLAYERS as usize];
+
+        engine::apply(&mut state, Action::Deal).unwrap();
+
+        if state.table.player() == Card::Plain(Villager) {
+            proceed_until(&mut state, Discuss);
+
+            while state.turn == Discuss && !came_clean(&state) {
+                engine::apply(&mut state, Action::Proceed).unwrap();
+            }
+
+            if came_clean(&state) {
+                assert_snapshot("discuss_came_clean", &render(&mut state));
+
+                return;
+            }
+        }
+    }
+
+    panic!("nobody came clean in the first 1000 seeds");
+}
+
+#[test]
+fn a_cpu_reasons_about_a_swap() {
+    let reasoned = |state: &State| {
+        state.transcript.iter().any(|s| matches!(s.kind, StatementKind::Reasoned(_, _, _)))
+    };
+
+    for seed in 0..3000 {
+        let mut state = engine::make_state(false, seed);
+        state.seed = seed;
+
+        engine::apply(&mut state, Action::Deal).unwrap();
+
+        if state.table.player() == Card::Plain(Villager) {
+            proceed_until(&mut state, Discuss);
+
+            while state.turn == Discuss && !reasoned(&state) {
+                engine::apply(&mut state, Action::Proceed).unwrap();
+            }
+
+            if reasoned(&state) {
+                assert_snapshot("discuss_reasoned", &render(&mut state));
+
+                return;
+            }
+        }
+    }
+
+    panic!("nobody reasoned about a swap in the first 3000 seeds");
+}
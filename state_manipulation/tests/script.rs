@@ -0,0 +1,85 @@
+//Checks the order the night's roles are called in.
+
+extern crate common;
+extern crate state_manipulation;
+
+use common::*;
+use common::Role::*;
+use common::Turn::*;
+use common::Participant::*;
+
+use state_manipulation::engine::{self, GameEvent};
+
+fn script_text(roles: Vec<Role>) -> String {
+    night_script(&roles)
+        .iter()
+        .map(|step| step.to_string())
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+#[test]
+fn only_roles_in_the_deck_are_called() {
+    assert_eq!(script_text(vec![Villager, Villager, Villager, Werewolf, Werewolf, Seer, Hunter]),
+               "Werewolf, Seer");
+}
+
+#[test]
+fn roles_are_called_in_their_wake_order() {
+    let steps = night_script(&all_roles());
+    let orders: Vec<u8> = steps.iter()
+        .filter(|step| !step.doppel)
+        .filter_map(|step| role_def(step.role).wake_order)
+        .collect();
+
+    let mut sorted = orders.clone();
+    sorted.sort();
+
+    assert_eq!(orders, sorted);
+    assert_eq!(orders.len(),
+               ROLE_DEFS.iter().filter(|def| def.wake_order.is_some()).count());
+}
+
+#[test]
+fn the_doppelganger_acts_where_the_copied_role_says() {
+    assert_eq!(script_text(vec![Doppelganger, Werewolf, Seer, Insomniac, Villager, Villager]),
+               "Doppelganger, Doppel-Seer, Werewolf, Seer, Insomniac, Doppel-Insomniac");
+}
+
+#[test]
+fn a_game_only_visits_the_steps_in_its_script() {
+    for seed in 0..10 {
+        let mut state = engine::make_state(false, seed);
+        state.seed = seed;
+
+        let mut visited: Vec<NightStep> = Vec::new();
+
+        while state.turn != Vote {
+            let action = engine::available_actions(&state, Player)[0];
+
+            for event in engine::apply(&mut state, action).unwrap() {
+                if let GameEvent::TurnBegan(turn) = event {
+                    if let Some(step) = turn.night_step() {
+                        if visited.last() != Some(&step) {
+                            visited.push(step);
+                        }
+                    }
+                }
+            }
+        }
+
+        let positions: Vec<Option<usize>> = visited.iter()
+            .map(|step| state.night_script.iter().position(|s| s == step))
+            .collect();
+
+        assert!(positions.iter().all(|p| p.is_some()),
+                "seed {} visited {:?} but the script was {:?}",
+                seed,
+                visited,
+                state.night_script);
+        assert!(positions.windows(2).all(|pair| pair[0] < pair[1]),
+                "seed {} visited {:?} out of order",
+                seed,
+                visited);
+    }
+}
@@ -0,0 +1,167 @@
+//Checks the cpu seats that look ahead, see `state_manipulation::search`.
+
+extern crate common;
+extern crate rand;
+extern crate state_manipulation;
+
+mod support;
+
+use common::*;
+use common::Role::*;
+use common::Turn::*;
+use common::Participant::*;
+
+use rand::Rng;
+
+use state_manipulation::engine;
+use state_manipulation::search::SearchStrategy;
+
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use support::*;
+
+//few enough playouts to be quick, with all the time in the world to play them
+fn quick_search() -> SearchStrategy {
+    SearchStrategy {
+        budget: Duration::from_secs(600),
+        playouts: 60,
+    }
+}
+
+fn werewolf_in_seat_0() -> State {
+    deal_until(RoleSpec::default(),
+               |state| state.table.cpu_cards()[0] == Card::Plain(Werewolf))
+}
+
+#[test]
+fn a_hard_seat_plays_a_whole_game() {
+    let mut state = deal_until(RoleSpec::default(), |state| {
+        state.table.cpu_cards()[0] == Card::Plain(Robber)
+    });
+    state.cpu_strategies.insert(0, Rc::new(quick_search()));
+    proceed_until(&mut state, Vote);
+
+    assert!(state.claims.contains_key(&Cpu(0)));
+
+    engine::apply(&mut state, engine::Action::VoteFor(Cpu(1))).unwrap();
+
+    let vote = state.votes.iter().find(|&&(voter, _)| voter == Cpu(0)).map(|&(_, vote)| vote);
+    assert!(vote.is_some());
+    assert!(vote != Some(Cpu(0)));
+}
+
+#[test]
+fn a_hard_werewolf_doesnt_vote_for_the_other_werewolf() {
+    let mut role_spec = role_spec_with(&[Villager, Minion]);
+    role_spec.remove(&Robber);
+    role_spec.remove(&Troublemaker);
+
+    let mut state = deal_until(role_spec, |state| {
+        state.table.cpu_cards()[0] == Card::Plain(Werewolf) &&
+        state.table.cpu_cards()[1] == Card::Plain(Werewolf)
+    });
+    state.cpu_strategies.insert(0, Rc::new(quick_search()));
+    proceed_until(&mut state, Vote);
+    engine::apply(&mut state, engine::Action::VoteFor(Cpu(2))).unwrap();
+
+    assert!(!state.votes.contains(&(Cpu(0), Cpu(1))));
+}
+
+#[test]
+fn the_same_seed_searches_the_same_way() {
+    let play = || {
+        let mut state = werewolf_in_seat_0();
+        state.cpu_strategies.insert(0, Rc::new(quick_search()));
+        proceed_until(&mut state, Vote);
+        engine::apply(&mut state, engine::Action::VoteFor(Cpu(1))).unwrap();
+
+        (state.transcript, state.votes)
+    };
+
+    assert_eq!(play(), play());
+}
+
+#[test]
+fn the_search_stops_when_its_time_is_up() {
+    let mut state = werewolf_in_seat_0();
+    proceed_until(&mut state, Vote);
+
+    let search = SearchStrategy {
+        budget: Duration::from_millis(20),
+        playouts: usize::MAX,
+    };
+
+    let started = Instant::now();
+    search.vote(&mut state, Cpu(0));
+
+    //it only checks between rounds, and a round is a handful of games
+    assert!(started.elapsed() < Duration::from_secs(2));
+}
+
+#[test]
+fn the_hard_profile_searches() {
+    let mut state = engine::make_state(false, 0);
+    state.role_spec = RoleSpec::default();
+    state.profile_picks[0] = Some(CpuProfile::Hard);
+    engine::apply(&mut state, engine::Action::Deal).unwrap();
+
+    assert_eq!(state.cpu_profiles[0], CpuProfile::Hard);
+
+    proceed_until(&mut state, Vote);
+
+    assert!(state.claims.contains_key(&Cpu(0)));
+}
+
+#[test]
+fn hard_is_never_picked_at_random() {
+    let mut rng = GameRng::new(0);
+
+    for _ in 0..1000 {
+        assert_ne!(rng.gen::<CpuProfile>(), CpuProfile::Hard);
+    }
+}
+
+//how a playout picks up the real discussion, in a game dealt differently
+#[test]
+fn a_discussion_carries_on_from_what_was_really_said() {
+    let mut real = werewolf_in_seat_0();
+    proceed_until(&mut real, Discuss);
+
+    let mut sim = deal_until(RoleSpec::default(), |_| true);
+    proceed_until(&mut sim, Discuss);
+
+    engine::resume_discussion(&mut sim, &real.transcript, Vote);
+
+    assert_eq!(sim.turn, Vote);
+    assert_eq!(sim.transcript, real.transcript);
+    assert_eq!(sim.claims, real.claims);
+}
+
+#[test]
+fn anyone_yet_to_claim_does_once_the_discussion_carries_on() {
+    let mut real = werewolf_in_seat_0();
+    proceed_until(&mut real, Discuss);
+    let first_said = real.transcript[..1].to_vec();
+
+    let mut sim = deal_until(RoleSpec::default(), |_| true);
+    proceed_until(&mut sim, Discuss);
+
+    engine::resume_discussion(&mut sim, &first_said, BeginDiscussion);
+
+    assert_eq!(sim.turn, Discuss);
+    assert_eq!(sim.transcript[..1], first_said[..]);
+    assert_eq!(sim.claims.get(&first_said[0].speaker),
+               first_said[0].claim().as_ref());
+    assert_eq!(sim.claims.len(), sim.cpu_knowledge.len());
+}
+
+#[test]
+fn the_players_seat_isnt_searched() {
+    let mut state = werewolf_in_seat_0();
+    proceed_until(&mut state, Vote);
+
+    let (_, reason) = quick_search().vote(&mut state, Player);
+
+    assert_ne!(reason, VoteReason::PlayedOut);
+}
@@ -0,0 +1,68 @@
+//Checks that the seed and the `RoleSpec` are enough to play a game again.
+
+extern crate common;
+extern crate state_manipulation;
+
+mod support;
+
+use common::*;
+use common::Role::*;
+use common::Turn::*;
+use common::Participant::*;
+
+use state_manipulation::engine::{self, Action};
+
+use support::*;
+
+//Plays a game from `seed` up to the vote, starting from a state that was
+//made with a different seed, so nothing carries over from that.
+fn play_from(seed: usize, made_with: usize) -> State {
+    let mut state = engine::make_state(false, made_with);
+    state.role_spec = role_spec_with(&[Doppelganger, Drunk, Insomniac]);
+    state.seed = seed;
+
+    play_until(&mut state, Vote);
+
+    state
+}
+
+fn assert_same_game(a: &State, b: &State) {
+    assert_eq!(a.initial_table, b.initial_table);
+    assert_eq!(a.table, b.table);
+    assert_eq!(a.night_log, b.night_log);
+    assert_eq!(a.cpu_profiles, b.cpu_profiles);
+    assert_eq!(a.transcript, b.transcript);
+}
+
+#[test]
+fn the_same_seed_gives_the_same_deal_and_night() {
+    for &seed in [3, 77, 1234].iter() {
+        assert_same_game(&play_from(seed, 0), &play_from(seed, 1));
+    }
+}
+
+#[test]
+fn different_seeds_give_different_deals() {
+    let first = play_from(0, 0);
+
+    assert!((1..10).any(|seed| play_from(seed, 0).initial_table != first.initial_table));
+}
+
+#[test]
+fn replay_deals_the_last_game_again() {
+    let mut state = play_from(5, 0);
+    let first = copy_state(&state);
+
+    assert_eq!(engine::available_actions(&engine::make_state(false, 0), Player),
+               vec![Action::Deal]);
+
+    play_until(&mut state, Ready);
+    state.seed = 6;
+
+    assert!(engine::available_actions(&state, Player).contains(&Action::Replay));
+    engine::apply(&mut state, Action::Replay).unwrap();
+    play_until(&mut state, Vote);
+
+    assert_eq!(state.last_deal, Some((5, first.role_spec)));
+    assert_same_game(&state, &first);
+}
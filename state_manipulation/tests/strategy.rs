@@ -0,0 +1,102 @@
+//Checks that a cpu seat can be given its own `CpuStrategy`.
+
+extern crate common;
+extern crate state_manipulation;
+
+mod support;
+
+use common::*;
+use common::Role::*;
+use common::Turn::*;
+use common::Participant::*;
+
+use state_manipulation::cpu::ProfileStrategy;
+use state_manipulation::engine;
+
+use std::rc::Rc;
+
+use support::*;
+
+//Robs the player, says they're a Villager, keeps quiet after that and
+//votes for the player. Anything else is left to the profile.
+struct PickOnThePlayer;
+
+impl CpuStrategy for PickOnThePlayer {
+    fn copy_target(&self, state: &mut State, doppelganger: Participant) -> Option<Participant> {
+        ProfileStrategy.copy_target(state, doppelganger)
+    }
+
+    fn seer_choice(&self, state: &mut State, seer: Participant) -> Option<SeerView> {
+        ProfileStrategy.seer_choice(state, seer)
+    }
+
+    fn robber_target(&self, _: &mut State, _: Participant) -> Option<Participant> {
+        Some(Player)
+    }
+
+    fn troublemaker_targets(&self,
+                            state: &mut State,
+                            troublemaker: Participant)
+                            -> Option<(Participant, Participant)> {
+        ProfileStrategy.troublemaker_targets(state, troublemaker)
+    }
+
+    fn drunk_target(&self, state: &mut State, drunk: Participant) -> CenterCard {
+        ProfileStrategy.drunk_target(state, drunk)
+    }
+
+    fn claim(&self, _: &mut State, _: Participant) -> Option<Claim> {
+        Some(Claim::Simple(Villager))
+    }
+
+    fn respond(&self, _: &mut State, _: Participant) -> Option<StatementKind> {
+        None
+    }
+
+    fn vote(&self, _: &mut State, _: Participant) -> (Participant, VoteReason) {
+        (Player, VoteReason::Unexplained)
+    }
+}
+
+#[test]
+fn a_seat_plays_by_the_strategy_put_in_it() {
+    let mut state = deal_until(RoleSpec::default(),
+                               |state| state.table.cpu_cards()[0] == Card::Plain(Robber));
+    state.cpu_strategies.insert(0, Rc::new(PickOnThePlayer));
+    proceed_until(&mut state, Vote);
+
+    assert!(state.night_log.contains(&NightEvent::Robbed {
+        actor: Cpu(0),
+        target: Player,
+        card: state.initial_table.player(),
+    }));
+    assert_eq!(state.claims[&Cpu(0)], Claim::Simple(Villager));
+    assert_eq!(state.transcript.iter().filter(|s| s.speaker == Cpu(0)).count(), 1);
+
+    engine::apply(&mut state, engine::Action::VoteFor(Cpu(1))).unwrap();
+
+    assert!(state.votes.contains(&(Cpu(0), Player)));
+    assert!(state.vote_reasons.contains(&(Cpu(0), VoteReason::Unexplained)));
+}
+
+#[test]
+fn the_other_seats_keep_their_profiles() {
+    let role_spec = RoleSpec::default();
+    let wanted = |state: &State| state.table.cpu_cards()[0] == Card::Plain(Robber);
+
+    let mut plain = deal_until(role_spec, wanted);
+    let mut plugged = deal_until(role_spec, wanted);
+    plugged.cpu_strategies.insert(1, Rc::new(PickOnThePlayer));
+
+    proceed_until(&mut plain, Discuss);
+    proceed_until(&mut plugged, Discuss);
+
+    let robbed = |state: &State| {
+        state.night_log
+            .iter()
+            .find(|event| event.actor() == Cpu(0))
+            .cloned()
+    };
+
+    assert_eq!(robbed(&plain), robbed(&plugged));
+}
@@ -0,0 +1,441 @@
+//An in-memory `Platform` so the screens can be driven and checked without a
+//terminal. `Platform` is all plain `fn`s, so what gets drawn goes into a
+//thread local grid. Each test runs on its own thread, so they don't share one.
+
+#![allow(dead_code)]
+
+use common::*;
+use common::Participant::*;
+
+use state_manipulation::engine::{self, Action};
+use state_manipulation::save;
+
+use std::cell::RefCell;
+use std::env;
+use std::fs::{self, File};
+use std::io::{Read, Write};
+
+pub const WIDTH: i32 = 80;
+pub const HEIGHT: i32 = 44;
+
+//somewhere no button is, so nothing is drawn as hovered over
+const MOUSE_AWAY: Point = Point { x: -1, y: -1 };
+
+struct Screen {
+    cells: Vec<char>,
+    mouse: Point,
+    mouse_down: bool,
+    clicks: i32,
+    foreground: (u8, u8, u8, u8),
+    background: (u8, u8, u8, u8),
+    layer: i32,
+}
+
+impl Screen {
+    fn new() -> Self {
+        Screen {
+            cells: vec![' '; (WIDTH * HEIGHT) as usize],
+            mouse: MOUSE_AWAY,
+            mouse_down: false,
+            clicks: 0,
+            foreground: (255, 255, 255, 255),
+            background: (0, 0, 0, 255),
+            layer: 0,
+        }
+    }
+
+    fn index(&self, x: i32, y: i32) -> Option<usize> {
+        if x >= 0 && y >= 0 && x < WIDTH && y < HEIGHT {
+            Some((y * WIDTH + x) as usize)
+        } else {
+            None
+        }
+    }
+}
+
+thread_local! {
+    static SCREEN: RefCell<Screen> = RefCell::new(Screen::new());
+}
+
+pub fn platform() -> Platform {
+    Platform {
+        print_xy,
+        clear,
+        size,
+        pick,
+        mouse_position,
+        clicks,
+        key_pressed,
+        set_colors,
+        get_colors,
+        set_foreground,
+        get_foreground,
+        set_background,
+        get_background,
+        set_layer,
+        get_layer,
+    }
+}
+
+fn print_xy(x: i32, y: i32, text: &str) {
+    SCREEN.with(|screen| {
+        let mut screen = screen.borrow_mut();
+
+        //like BearLibTerminal, a new line starts back at `x`
+        for (j, line) in text.split('\n').enumerate() {
+            for (i, c) in line.chars().enumerate() {
+                if let Some(index) = screen.index(x + i as i32, y + j as i32) {
+                    screen.cells[index] = c;
+                }
+            }
+        }
+    });
+}
+
+fn clear(area: Option<Rect>) {
+    SCREEN.with(|screen| {
+        let mut screen = screen.borrow_mut();
+
+        let (x, y, w, h) = match area {
+            Some(rect) => (rect.top_left.x, rect.top_left.y, rect.size.width, rect.size.height),
+            None => (0, 0, WIDTH, HEIGHT),
+        };
+
+        for j in y..y + h {
+            for i in x..x + w {
+                if let Some(index) = screen.index(i, j) {
+                    screen.cells[index] = ' ';
+                }
+            }
+        }
+    });
+}
+
+fn size() -> Size {
+    Size::new(WIDTH, HEIGHT)
+}
+
+fn pick(point: Point, _: i32) -> char {
+    SCREEN.with(|screen| {
+        let screen = screen.borrow();
+
+        screen.index(point.x, point.y).map(|i| screen.cells[i]).unwrap_or(' ')
+    })
+}
+
+fn mouse_position() -> Point {
+    SCREEN.with(|screen| screen.borrow().mouse)
+}
+
+fn clicks() -> i32 {
+    SCREEN.with(|screen| screen.borrow().clicks)
+}
+
+fn key_pressed(key: KeyCode) -> bool {
+    key == KeyCode::MouseLeft && SCREEN.with(|screen| screen.borrow().mouse_down)
+}
+
+fn to_color((red, green, blue, alpha): (u8, u8, u8, u8)) -> Color {
+    Color {
+        red,
+        green,
+        blue,
+        alpha,
+    }
+}
+
+fn from_color(color: Color) -> (u8, u8, u8, u8) {
+    (color.red, color.green, color.blue, color.alpha)
+}
+
+fn set_colors(foreground: Color, background: Color) {
+    set_foreground(foreground);
+    set_background(background);
+}
+
+fn get_colors() -> (Color, Color) {
+    (get_foreground(), get_background())
+}
+
+fn set_foreground(color: Color) {
+    SCREEN.with(|screen| screen.borrow_mut().foreground = from_color(color));
+}
+
+fn get_foreground() -> Color {
+    SCREEN.with(|screen| to_color(screen.borrow().foreground))
+}
+
+fn set_background(color: Color) {
+    SCREEN.with(|screen| screen.borrow_mut().background = from_color(color));
+}
+
+fn get_background() -> Color {
+    SCREEN.with(|screen| to_color(screen.borrow().background))
+}
+
+fn set_layer(layer: i32) {
+    SCREEN.with(|screen| screen.borrow_mut().layer = layer);
+}
+
+fn get_layer() -> i32 {
+    SCREEN.with(|screen| screen.borrow().layer)
+}
+
+//Runs one frame the way `src/main.rs` does, clearing the screen first.
+pub fn frame(state: &mut State, events: Vec<Event>) {
+    clear(None);
+
+    let mut events = events;
+
+    state_manipulation::update_and_render(&platform(), state, &mut events);
+}
+
+pub fn move_mouse(x: i32, y: i32) {
+    SCREEN.with(|screen| screen.borrow_mut().mouse = Point { x, y });
+}
+
+//Draws the current screen, with the mouse out of the way, and returns it.
+pub fn render(state: &mut State) -> String {
+    move_mouse(MOUSE_AWAY.x, MOUSE_AWAY.y);
+
+    //the first frame lets go of anything hovered over by an earlier click.
+    frame(state, Vec::new());
+    frame(state, Vec::new());
+
+    screen_text()
+}
+
+//A whole click takes three frames: one to hover over the spot, one for the
+//press and one for the release.
+pub fn click(state: &mut State, x: i32, y: i32) {
+    move_mouse(x, y);
+    frame(state, Vec::new());
+
+    SCREEN.with(|screen| {
+        let mut screen = screen.borrow_mut();
+        screen.mouse_down = true;
+        screen.clicks += 1;
+    });
+    frame(state,
+          vec![Event::KeyPressed {
+                   key: KeyCode::MouseLeft,
+                   ctrl: false,
+                   shift: false,
+               }]);
+
+    SCREEN.with(|screen| screen.borrow_mut().mouse_down = false);
+    frame(state,
+          vec![Event::KeyReleased {
+                   key: KeyCode::MouseLeft,
+                   ctrl: false,
+                   shift: false,
+               }]);
+}
+
+//Clicks on the first button showing `text`. Buttons are the only place text
+//has a box drawn right above it.
+pub fn click_button(state: &mut State, text: &str) {
+    let screen = render(state);
+    let lines: Vec<Vec<char>> = screen.lines().map(|line| line.chars().collect()).collect();
+    let text: Vec<char> = text.chars().collect();
+
+    let is_box = |x: usize, y: usize| {
+        y > 0 &&
+        lines[y - 1]
+            .get(x)
+            .map(|&c| c == '─' || c == '═')
+            .unwrap_or(false)
+    };
+
+    for (y, line) in lines.iter().enumerate() {
+        for x in 0..(line.len() + 1).saturating_sub(text.len()) {
+            if line[x..x + text.len()] == text[..] && is_box(x, y) {
+                click(state, x as i32, y as i32);
+
+                return;
+            }
+        }
+    }
+
+    panic!("there's no \"{}\" button on the screen:\n{}",
+           text.iter().collect::<String>(),
+           screen);
+}
+
+//The grid as text, without trailing spaces or blank lines at the end.
+pub fn screen_text() -> String {
+    SCREEN.with(|screen| {
+        let screen = screen.borrow();
+
+        let mut lines: Vec<String> = screen.cells
+            .chunks(WIDTH as usize)
+            .map(|row| row.iter().collect::<String>().trim_end().to_owned())
+            .collect();
+
+        while lines.last().map(|l| l.is_empty()).unwrap_or(false) {
+            lines.pop();
+        }
+
+        lines.join("\n") + "\n"
+    })
+}
+
+//Compares against `tests/snapshots/<name>.txt`. Run the tests with
+//`UPDATE_SNAPSHOTS=1` set to write the current screens out instead, then
+//look over the diff before committing it.
+pub fn assert_snapshot(name: &str, screen: &str) {
+    let path = format!("{}/tests/snapshots/{}.txt", env!("CARGO_MANIFEST_DIR"), name);
+
+    if env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        fs::create_dir_all(format!("{}/tests/snapshots", env!("CARGO_MANIFEST_DIR")))
+            .unwrap();
+
+        File::create(&path).and_then(|mut f| f.write_all(screen.as_bytes())).unwrap();
+
+        return;
+    }
+
+    let mut expected = String::new();
+
+    if let Err(e) = File::open(&path).and_then(|mut f| f.read_to_string(&mut expected)) {
+        panic!("couldn't read {}: {}\nthe screen was:\n{}", path, e, screen);
+    }
+
+    if expected != screen {
+        panic!("{} doesn't match the screen.\nexpected:\n{}\ngot:\n{}",
+               name,
+               expected,
+               screen);
+    }
+}
+
+pub fn role_spec_with(roles: &[Role]) -> RoleSpec {
+    let mut role_spec = RoleSpec::default();
+
+    for role in roles.iter() {
+        assert!(role_spec.can_add(role), "can't add {:o}", role);
+        role_spec.add(role);
+    }
+
+    role_spec
+}
+
+//Nobody moves any cards around, so what's seen at night stays true.
+pub fn no_swapping() -> RoleSpec {
+    let mut role_spec = role_spec_with(&[Role::Villager]);
+    role_spec.remove(&Role::Robber);
+    role_spec.remove(&Role::Troublemaker);
+
+    role_spec
+}
+
+//Deals with each seed in turn until `wanted` is happy with the cards, so the
+//result is the same every time.
+pub fn deal_until<F>(role_spec: RoleSpec, wanted: F) -> State
+    where F: Fn(&State) -> bool
+{
+    for seed in 0..10000 {
+        let mut state = engine::make_state(false, seed);
+        state.role_spec = role_spec;
+        state.seed = seed;
+
+        engine::apply(&mut state, Action::Deal).unwrap();
+
+        if wanted(&state) {
+            return state;
+        }
+    }
+
+    panic!("no deal in the first 10000 seeds was wanted");
+}
+
+//Like `deal_until`, with every cpu seat playing as `profile`.
+pub fn deal_with_profile_until<F>(profile: CpuProfile, role_spec: RoleSpec, wanted: F) -> State
+    where F: Fn(&State) -> bool
+{
+    for seed in 0..10000 {
+        let mut state = engine::make_state(false, seed);
+        state.role_spec = role_spec;
+        state.seed = seed;
+        state.profile_picks = vec![Some(profile); MAXIMUM_CPU_PLAYERS as usize];
+
+        engine::apply(&mut state, Action::Deal).unwrap();
+
+        if wanted(&state) {
+            return state;
+        }
+    }
+
+    panic!("no deal in the first 10000 seeds was wanted");
+}
+
+pub fn deal_as(role_spec: RoleSpec, role: Role) -> State {
+    deal_until(role_spec, |state| state.table.player() == Card::Plain(role))
+}
+
+//The player is the Doppelganger and has copied a cpu player's `role`.
+pub fn deal_as_doppel(role_spec: RoleSpec, role: Role) -> State {
+    let mut state = deal_until(role_spec, |state| {
+        state.table.player() == Card::Plain(Role::Doppelganger) &&
+        state.table.cpu_cards().contains(&Card::Plain(role))
+    });
+
+    let target = state.table
+        .cpu_cards()
+        .iter()
+        .position(|&card| card == Card::Plain(role))
+        .unwrap();
+
+    engine::apply(&mut state, Action::CopyCard(Cpu(target))).unwrap();
+
+    state
+}
+
+//`State` isn't `Clone`, but it can be saved and loaded again.
+pub fn copy_state(state: &State) -> State {
+    save::from_str(&save::to_string(state).unwrap()).unwrap()
+}
+
+//Keeps acknowledging screens until the turn is `turn`.
+pub fn proceed_until(state: &mut State, turn: Turn) {
+    while state.turn != turn {
+        let actions = engine::available_actions(state, Player);
+
+        if actions.contains(&Action::Proceed) {
+            engine::apply(state, Action::Proceed).unwrap();
+        } else if actions.contains(&Action::Skip) {
+            engine::apply(state, Action::Skip).unwrap();
+        } else {
+            panic!("stuck on {:?} before reaching {:?}", state.turn, turn);
+        }
+    }
+}
+
+//Like `proceed_until`, but makes the first choice on offer whenever the
+//player has to pick something, so a whole game can be played from a seed.
+pub fn play_until(state: &mut State, turn: Turn) {
+    while state.turn != turn {
+        let actions = engine::available_actions(state, Player);
+
+        match actions.first() {
+            Some(&action) => {
+                engine::apply(state, action).unwrap();
+            }
+            None => panic!("no actions on {:?} before reaching {:?}", state.turn, turn),
+        }
+    }
+}
+
+//Replaces the discussion with each `(speaker, vote)` cpu saying who they're
+//voting for.
+pub fn everyone_says(state: &mut State, votes: &[(usize, usize)]) {
+    state.transcript = votes.iter()
+        .map(|&(speaker, vote)| {
+            Statement {
+                round: 2,
+                speaker: Cpu(speaker),
+                kind: StatementKind::VotingFor(Cpu(vote)),
+            }
+        })
+        .collect();
+}
@@ -0,0 +1,84 @@
+//Checks that cards only ever move around the table.
+
+extern crate common;
+extern crate state_manipulation;
+
+mod support;
+
+use common::*;
+use common::Role::*;
+use common::Turn::*;
+use common::Participant::*;
+use common::CenterCard::*;
+
+use state_manipulation::engine::{self, Action};
+
+use support::*;
+
+fn small_table() -> Table {
+    Table::new(Card::Plain(Seer),
+               vec![Card::Plain(Werewolf), Card::Plain(Robber)],
+               [Card::Plain(Villager), Card::Plain(Drunk), Card::Plain(Tanner)])
+}
+
+#[test]
+fn swapping_moves_cards_without_making_or_losing_any() {
+    let mut table = small_table();
+
+    assert!(table.swap(Player, Cpu(1)));
+    assert!(table.swap_with_center(Cpu(0), Third));
+
+    assert_eq!(table.player(), Card::Plain(Robber));
+    assert_eq!(table.view(Cpu(1)), Some(Card::Plain(Seer)));
+    assert_eq!(table.view(Cpu(0)), Some(Card::Plain(Tanner)));
+    assert_eq!(table.view_center(Third), Card::Plain(Werewolf));
+    assert!(table.has_same_cards(&small_table()));
+}
+
+#[test]
+fn swapping_with_someone_who_isnt_seated_does_nothing() {
+    let mut table = small_table();
+
+    assert!(!table.swap(Player, Cpu(5)));
+    assert!(!table.swap_with_center(Cpu(5), First));
+
+    assert_eq!(table, small_table());
+}
+
+#[test]
+fn the_drunk_swaps_with_the_center() {
+    let mut state = deal_as(role_spec_with(&[Drunk]), Drunk);
+    proceed_until(&mut state, DrunkTurn);
+
+    let center = state.table.view_center(Second);
+    let cpus = state.table.cpu_cards().to_vec();
+
+    engine::apply(&mut state, Action::TakeCenter(Second)).unwrap();
+
+    assert_eq!(state.table.player(), center);
+    assert_eq!(state.table.view_center(Second), Card::Plain(Drunk));
+    assert_eq!(state.table.cpu_cards(), &cpus[..]);
+}
+
+#[test]
+fn every_night_ends_with_the_cards_it_started_with() {
+    for seed in 0..10 {
+        let mut state = engine::make_state(false, seed);
+        state.seed = seed;
+        play_until(&mut state, Vote);
+
+        assert!(state.table.has_same_cards(&state.initial_table),
+                "seed {} went from {:?} to {:?}",
+                seed,
+                state.initial_table,
+                state.table);
+    }
+}
+
+#[test]
+fn the_cards_as_dealt_include_what_the_player_copied() {
+    let state = deal_as_doppel(role_spec_with(&[Doppelganger]), Villager);
+
+    assert!(matches!(state.initial_table.player(), Card::Doppel(Cpu(_), Villager)));
+    assert_eq!(state.initial_table, state.table);
+}
@@ -0,0 +1,126 @@
+//Checks how a cpu Tanner tries to get themselves voted out.
+
+extern crate common;
+extern crate state_manipulation;
+
+mod support;
+
+use common::*;
+use common::Role::*;
+use common::Turn::*;
+use common::Claim::*;
+use common::Participant::*;
+
+use state_manipulation::cpu;
+use state_manipulation::engine;
+
+use support::*;
+
+//Cpu 0 is the Tanner, and nobody has said anything yet.
+fn tanner_before_claims(profile: CpuProfile) -> State {
+    let mut state = deal_with_profile_until(profile, role_spec_with(&[Tanner, Hunter]), |state| {
+        state.table.cpu_cards()[0] == Card::Plain(Tanner)
+    });
+    proceed_until(&mut state, Discuss);
+
+    state.claims.clear();
+    state.transcript.clear();
+
+    state
+}
+
+#[test]
+fn a_tanner_claims_a_role_someone_else_already_has() {
+    let mut state = tanner_before_claims(CpuProfile::Cautious);
+    engine::insert_claim(&mut state, Cpu(1), Simple(Hunter));
+
+    assert_eq!(cpu::make_cpu_claim(&mut state, Cpu(0)), Some(Simple(Hunter)));
+}
+
+#[test]
+fn a_tanner_says_the_other_seer_is_a_villager() {
+    let mut state = tanner_before_claims(CpuProfile::Bluffer);
+    engine::insert_claim(&mut state, Cpu(1), SeerRevealOneAction(Cpu(2), Villager));
+
+    assert_eq!(cpu::make_cpu_claim(&mut state, Cpu(0)),
+               Some(SeerRevealOneAction(Cpu(1), Villager)));
+}
+
+#[test]
+fn a_contradicted_tanner_changes_their_story() {
+    let mut state = tanner_before_claims(CpuProfile::Cautious);
+    engine::insert_claim(&mut state, Cpu(1), Simple(Hunter));
+    engine::insert_claim(&mut state, Cpu(0), Simple(Hunter));
+
+    match cpu::respond(&mut state, Cpu(0)) {
+        Some(StatementKind::Changed(claim)) => assert!(claim != Simple(Hunter)),
+        kind => panic!("the Tanner said {:?}", kind),
+    }
+}
+
+#[test]
+fn a_tanner_who_was_robbed_comes_clean() {
+    let mut state = tanner_before_claims(CpuProfile::Cautious);
+    engine::insert_claim(&mut state, Cpu(0), Simple(Villager));
+
+    assert!(cpu::holds_tanner(&state, Cpu(0), &state.cpu_knowledge[0]));
+
+    engine::insert_claim(&mut state, Cpu(1), RobberAction(Cpu(0), Tanner));
+
+    assert!(!cpu::holds_tanner(&state, Cpu(0), &state.cpu_knowledge[0]));
+    assert_eq!(cpu::respond(&mut state, Cpu(0)),
+               Some(StatementKind::Confessed(Simple(Tanner))));
+}
+
+//the cpu who robbed the Tanner and is still holding it, if there is one
+fn tanner_robber(state: &State) -> Option<usize> {
+    state.night_log
+        .iter()
+        .filter_map(|event| match *event {
+            NightEvent::Robbed { actor: Cpu(i), card: Card::Plain(Tanner), .. } => Some(i),
+            _ => None,
+        })
+        .find(|&i| cpu::holds_tanner(state, Cpu(i), &state.cpu_knowledge[i]))
+}
+
+#[test]
+fn a_robber_who_took_the_tanner_plays_the_tanner() {
+    let at_vote = |state: &State| {
+        let mut state = copy_state(state);
+        proceed_until(&mut state, Vote);
+
+        state
+    };
+
+    let mut state = deal_with_profile_until(CpuProfile::Cautious,
+                                            role_spec_with(&[Tanner]),
+                                            |state| {
+        state.table.player().role() != Robber && tanner_robber(&at_vote(state)).is_some()
+    });
+    proceed_until(&mut state, Vote);
+
+    let robber = Cpu(tanner_robber(&state).unwrap());
+    engine::apply(&mut state, engine::Action::VoteFor(robber)).unwrap();
+
+    assert!(state.vote_reasons.contains(&(robber, VoteReason::FewestVotes)));
+}
+
+#[test]
+fn a_tanner_votes_where_it_counts_least() {
+    let mut state = tanner_before_claims(CpuProfile::Cautious);
+    proceed_until(&mut state, Vote);
+
+    //nobody says anything about moving the Tanner card
+    for i in 1..state.cpu_knowledge.len() {
+        state.claims.insert(Cpu(i), Simple(Villager));
+    }
+    everyone_says(&mut state, &[(1, 2), (2, 3), (3, 2), (4, 1)]);
+
+    engine::apply(&mut state, engine::Action::VoteFor(Cpu(0))).unwrap();
+
+    let vote = state.votes.iter().find(|&&(voter, _)| voter == Cpu(0)).map(|&(_, vote)| vote);
+    assert!(vote == Some(Player) || vote == Some(Cpu(4)),
+            "the Tanner voted for {:?}",
+            vote);
+    assert!(state.vote_reasons.contains(&(Cpu(0), VoteReason::FewestVotes)));
+}
@@ -0,0 +1,92 @@
+//Checks what the cpu players say about their votes once the game is over.
+
+extern crate common;
+extern crate state_manipulation;
+
+mod support;
+
+use common::*;
+use common::Role::*;
+use common::Turn::*;
+use common::Participant::*;
+
+use state_manipulation::cpu;
+use state_manipulation::engine;
+
+use support::*;
+
+fn everyone() -> Vec<Participant> {
+    vec![Player, Cpu(0), Cpu(1), Cpu(2)]
+}
+
+#[test]
+fn every_cpu_says_why_they_voted() {
+    for seed in 0..20 {
+        let mut state = engine::make_state(false, seed);
+        state.role_spec = RoleSpec::default();
+        state.seed = seed;
+        engine::apply(&mut state, engine::Action::Deal).unwrap();
+        proceed_until(&mut state, Vote);
+        engine::apply(&mut state, engine::Action::VoteFor(Cpu(0))).unwrap();
+
+        let voters: Vec<Participant> = state.votes
+            .iter()
+            .map(|&(voter, _)| voter)
+            .filter(|&voter| voter != Player)
+            .collect();
+        let explained: Vec<Participant> = state.vote_reasons
+            .iter()
+            .map(|&(voter, _)| voter)
+            .collect();
+
+        assert_eq!(voters, explained);
+    }
+}
+
+#[test]
+fn a_villager_who_saw_a_werewolf_says_so() {
+    let mut knowledge = Knowledge::new(Card::Plain(Seer), Cpu(0));
+    knowledge.known_werewolves.insert(Cpu(2));
+
+    let vote = cpu::get_vote(Cpu(0), everyone(), &knowledge, None, &mut GameRng::new(0));
+
+    assert_eq!(vote, (Cpu(2), VoteReason::SawWerewolf));
+}
+
+#[test]
+fn a_villager_votes_for_a_liar_before_a_suspect() {
+    let mut knowledge = Knowledge::new(Card::Plain(Villager), Cpu(0));
+    knowledge.suspects.insert(Cpu(1));
+    knowledge.liars.insert(Player);
+
+    let vote = cpu::get_vote(Cpu(0), everyone(), &knowledge, None, &mut GameRng::new(0));
+
+    assert_eq!(vote, (Player, VoteReason::Lied));
+}
+
+#[test]
+fn a_werewolf_says_they_knew_who_wasnt_one() {
+    let mut knowledge = Knowledge::new(Card::Plain(Werewolf), Cpu(0));
+    knowledge.known_werewolves.insert(Cpu(0));
+    knowledge.known_villagers.insert(Cpu(1));
+
+    let vote = cpu::get_vote(Cpu(0), everyone(), &knowledge, None, &mut GameRng::new(0));
+
+    assert_eq!(vote, (Cpu(1), VoteReason::KnewVillager));
+}
+
+#[test]
+fn with_nothing_to_go_on_the_vote_is_random() {
+    let knowledge = Knowledge::new(Card::Plain(Villager), Cpu(0));
+
+    for seed in 0..20 {
+        let (vote, reason) = cpu::get_vote(Cpu(0),
+                                           everyone(),
+                                           &knowledge,
+                                           None,
+                                           &mut GameRng::new(seed));
+
+        assert!(vote != Cpu(0));
+        assert_eq!(reason, VoteReason::Random);
+    }
+}